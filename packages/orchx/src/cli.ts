@@ -12,6 +12,7 @@ import { rulesCommand } from './commands/rules.js'
 import { syncCommand } from './commands/sync.js'
 import { injectCommand } from './commands/inject.js'
 import { testerCommand } from './commands/tester.js'
+import { execCommand } from './commands/exec.js'
 import { readFileSync } from 'node:fs'
 import { fileURLToPath } from 'node:url'
 import { dirname, join } from 'node:path'
@@ -34,5 +35,6 @@ program.addCommand(rulesCommand())
 program.addCommand(syncCommand())
 program.addCommand(injectCommand())
 program.addCommand(testerCommand())
+program.addCommand(execCommand())
 
 program.parse()
@@ -0,0 +1,50 @@
+// ============================================
+// commands/exec.ts — orchx exec
+// 에이전트 명령 실행 래퍼 → .orchestrator/commands.log 기록
+// (데스크톱 앱 watcher가 tail 하여 allowed_commands 집행)
+// ============================================
+
+import { Command } from 'commander'
+import { appendFileSync } from 'node:fs'
+import { join } from 'node:path'
+import { spawnSync } from 'node:child_process'
+import chalk from 'chalk'
+import { ensureOrchestratorDir } from '../config/session.js'
+
+const COMMAND_LOG = 'commands.log'
+
+// POSIX 셸 인용 — 로그의 명령을 원래 argv로 다시 나눌 수 있게 (앱 contract.rs가 파싱)
+function shellQuote(arg: string): string {
+    if (/^[A-Za-z0-9_\/.:=@%+,-]+$/.test(arg)) return arg
+    return `'${arg.replace(/'/g, `'\\''`)}'`
+}
+
+export function execCommand(): Command {
+    const cmd = new Command('exec')
+        .description('Run a command and record it for contract enforcement')
+        .argument('<command...>', 'Command and arguments, run without a shell (use -- before flags)')
+        .action((args: string[]) => {
+            const cwd = process.cwd()
+            const [program, ...programArgs] = args
+            const command = args.map(shellQuote).join(' ')
+
+            // 실행 전에 기록 (장시간/파괴적 명령도 즉시 감지)
+            try {
+                const dir = ensureOrchestratorDir(cwd)
+                const record = { command, cwd, executed_at: new Date().toISOString() }
+                appendFileSync(join(dir, COMMAND_LOG), JSON.stringify(record) + '\n', 'utf-8')
+            } catch {
+                console.error(chalk.yellow('⚠'), 'Failed to record command (running anyway)')
+            }
+
+            // 셸 없이 argv 그대로 실행 → 인자 속 메타문자가 명령으로 해석되지 않음
+            const result = spawnSync(program, programArgs, { cwd, stdio: 'inherit', shell: false })
+            if (result.error) {
+                console.error(chalk.red(`✗ Failed to run ${program}: ${result.error.message}`))
+                process.exit(127)
+            }
+            process.exit(result.status ?? 1)
+        })
+
+    return cmd
+}
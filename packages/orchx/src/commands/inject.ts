@@ -10,13 +10,20 @@ import { join } from 'node:path'
 import { readSession, ensureOrchestratorDir, updateSessionContract } from '../config/session.js'
 import { SyncClient } from './sync.js'
 
+const RISK_TIERS = ['low', 'mid', 'high'] as const
+type RiskTier = typeof RISK_TIERS[number]
+
 // === CURRENT_TASK.md 템플릿 생성 ===
 
 interface TaskInfo {
     instruction: string
-    risk_tier: string
+    risk_tier: RiskTier
     allowed_paths: string[]
     allowed_commands: string[]
+    denied_paths: string[]
+    path_severity?: { pattern: string; severity: 'info' | 'warn' | 'block' }[]
+    budget_tokens?: number
+    budget_minutes?: number
 }
 
 function generateTaskMarkdown(task: TaskInfo): string {
@@ -32,10 +39,20 @@ function generateTaskMarkdown(task: TaskInfo): string {
         ? task.allowed_paths.map(p => `- ${p}`).join('\n')
         : '- 제한 없음'
 
+    const deniedList = task.denied_paths.length > 0
+        ? task.denied_paths.map(p => `- ${p}`).join('\n')
+        : '- 없음'
+
     const cmdsList = task.allowed_commands.length > 0
         ? task.allowed_commands.map(c => `- \`${c}\``).join('\n')
         : '- 제한 없음'
 
+    const budgets = [
+        task.budget_minutes !== undefined ? `- 시간: ${task.budget_minutes}분` : null,
+        task.budget_tokens !== undefined ? `- 토큰: ${task.budget_tokens}` : null,
+    ].filter(Boolean)
+    const budgetList = budgets.length > 0 ? budgets.join('\n') : '- 제한 없음'
+
     return `# 🔒 실행 계약서
 
 > 이 파일은 orchx에 의해 자동 생성되었습니다. 수동으로 수정하지 마세요.
@@ -45,9 +62,15 @@ function generateTaskMarkdown(task: TaskInfo): string {
 ## 허용 경로
 ${pathsList}
 
+## 금지 경로 (허용 경로보다 우선)
+${deniedList}
+
 ## 허용 명령
 ${cmdsList}
 
+## 예산
+${budgetList}
+
 ---
 
 # 📋 작업 지시
@@ -66,7 +89,8 @@ export function injectCommand(): Command {
     const cmd = new Command('inject')
         .description('서버 Task를 로컬 CURRENT_TASK.md로 주입')
         .option('-m, --manual <instruction>', '수동 지시 (서버 연결 없이)')
-        .action(async (opts: { manual?: string }) => {
+        .option('-r, --risk-tier <tier>', `수동 모드 위험 등급 (${RISK_TIERS.join('|')}, 기본: 기존 계약서 유지)`)
+        .action(async (opts: { manual?: string; riskTier?: string }) => {
             const cwd = process.cwd()
             const session = readSession(cwd)
 
@@ -74,16 +98,26 @@ export function injectCommand(): Command {
                 console.error(chalk.red('✗ No active session. Run "orchx session start" first.'))
                 process.exit(1)
             }
+            if (opts.riskTier !== undefined && !RISK_TIERS.includes(opts.riskTier as RiskTier)) {
+                console.error(chalk.red(`✗ Unknown risk tier: ${opts.riskTier}`))
+                console.error(chalk.dim(`  Valid: ${RISK_TIERS.join(', ')}`))
+                process.exit(1)
+            }
 
             let taskInfo: TaskInfo
 
             if (opts.manual) {
-                // 수동 모드: 명령줄에서 직접 지시
+                // 수동 모드: 명령줄에서 직접 지시 (기존 계약서의 등급/심각도는 유지)
+                const existing = session.execution_contract
                 taskInfo = {
                     instruction: opts.manual,
-                    risk_tier: 'mid',
-                    allowed_paths: session.execution_contract?.allowed_paths ?? [],
-                    allowed_commands: session.execution_contract?.allowed_commands ?? [],
+                    risk_tier: (opts.riskTier as RiskTier | undefined) ?? existing?.risk_tier ?? 'mid',
+                    allowed_paths: existing?.allowed_paths ?? [],
+                    allowed_commands: existing?.allowed_commands ?? [],
+                    denied_paths: existing?.denied_paths ?? [],
+                    path_severity: existing?.path_severity,
+                    budget_tokens: existing?.budget_tokens,
+                    budget_minutes: existing?.budget_minutes,
                 }
                 console.log(chalk.dim('  수동 모드로 Task 생성'))
             } else {
@@ -111,9 +145,14 @@ export function injectCommand(): Command {
 
                 taskInfo = {
                     instruction: (serverTask.instruction as string) ?? '지시 없음',
-                    risk_tier: (serverTask.risk_tier as string) ?? 'mid',
+                    risk_tier: (serverTask.risk_tier as RiskTier) ?? 'mid',
                     allowed_paths: (serverTask.allowed_paths as string[]) ?? [],
                     allowed_commands: (serverTask.allowed_commands as string[]) ?? [],
+                    denied_paths: (serverTask.denied_paths as string[]) ?? [],
+                    path_severity: (serverTask.path_severity as TaskInfo['path_severity']) ?? [],
+                    // agent_tasks.budget_tokens/budget_minutes (migration 015) → 데스크톱 앱 예산 집행
+                    budget_tokens: (serverTask.budget_tokens as number | null) ?? undefined,
+                    budget_minutes: (serverTask.budget_minutes as number | null) ?? undefined,
                 }
             }
 
@@ -127,6 +166,11 @@ export function injectCommand(): Command {
             updateSessionContract(cwd, {
                 allowed_paths: taskInfo.allowed_paths,
                 allowed_commands: taskInfo.allowed_commands,
+                denied_paths: taskInfo.denied_paths,
+                risk_tier: taskInfo.risk_tier,
+                path_severity: taskInfo.path_severity,
+                budget_tokens: taskInfo.budget_tokens,
+                budget_minutes: taskInfo.budget_minutes,
             })
 
             console.log(chalk.green('✓'), `Task 주입 완료: ${taskPath}`)
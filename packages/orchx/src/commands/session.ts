@@ -5,10 +5,20 @@
 
 import { Command } from 'commander'
 import chalk from 'chalk'
-import { createSession, readSession, deleteSession } from '../config/session.js'
+import { createSession, readSession, deleteSession, updateSessionTokens } from '../config/session.js'
 
 const AGENT_TYPES = ['cursor', 'claude_code', 'codex', 'windsurf', 'copilot', 'antigravity', 'custom']
 
+// 예산 옵션 값 (양의 정수만)
+function parseLimit(value: string): number {
+    const n = Number.parseInt(value, 10)
+    if (!Number.isInteger(n) || n <= 0 || String(n) !== value.trim()) {
+        console.error(chalk.red(`✗ Invalid budget limit: ${value}`))
+        process.exit(1)
+    }
+    return n
+}
+
 export function sessionCommand(): Command {
     const cmd = new Command('session')
         .description('Manage focus sessions')
@@ -17,7 +27,18 @@ export function sessionCommand(): Command {
         .description('Start a new focus session')
         .requiredOption('-a, --agent <type>', `Agent type (${AGENT_TYPES.join(', ')})`)
         .requiredOption('-t, --task <description>', 'Task description')
-        .action((opts: { agent: string; task: string }) => {
+        .option('--max-minutes <n>', 'Budget: wall-clock minutes', parseLimit)
+        .option('--max-files <n>', 'Budget: files changed', parseLimit)
+        .option('--max-commits <n>', 'Budget: commits', parseLimit)
+        .option('--max-tokens <n>', 'Budget: tokens reported via "orchx session tokens"', parseLimit)
+        .action((opts: {
+            agent: string
+            task: string
+            maxMinutes?: number
+            maxFiles?: number
+            maxCommits?: number
+            maxTokens?: number
+        }) => {
             const cwd = process.cwd()
 
             // 이미 활성 세션 확인
@@ -39,7 +60,12 @@ export function sessionCommand(): Command {
                 process.exit(1)
             }
 
-            const session = createSession(cwd, opts.agent, opts.task)
+            const session = createSession(cwd, opts.agent, opts.task, {
+                max_minutes: opts.maxMinutes,
+                max_files: opts.maxFiles,
+                max_commits: opts.maxCommits,
+                max_tokens: opts.maxTokens,
+            })
 
             console.log(chalk.green('✓'), 'Focus session started')
             console.log('')
@@ -47,6 +73,12 @@ export function sessionCommand(): Command {
             console.log(chalk.bold(`  📋 Task:    ${session.task_name}`))
             console.log(chalk.bold(`  🕐 Started: ${new Date(session.started_at).toLocaleTimeString()}`))
             console.log(chalk.dim(`  📝 Session: ${session.session_id}`))
+            if (session.budget) {
+                const limits = Object.entries(session.budget)
+                    .filter(([, v]) => v !== undefined)
+                    .map(([k, v]) => `${k.replace('max_', '')} ${v}`)
+                console.log(chalk.dim(`  💰 Budget:  ${limits.join(', ')}`))
+            }
             console.log('')
             console.log(chalk.dim('All commits will now include Agent/Session/Task metadata.'))
             console.log(chalk.dim('Run "orchx session end" when done.'))
@@ -105,5 +137,25 @@ export function sessionCommand(): Command {
             console.log(`  🔄 Commits:  ${session.commits_detected} detected`)
         })
 
+    cmd.command('tokens')
+        .description('Report cumulative token usage for budget tracking')
+        .argument('<count>', 'Total tokens used so far')
+        .action((count: string) => {
+            const cwd = process.cwd()
+            const tokens = Number.parseInt(count, 10)
+
+            if (!readSession(cwd)) {
+                console.error(chalk.red('✗ No active session found.'))
+                process.exit(1)
+            }
+            if (!Number.isFinite(tokens) || tokens < 0) {
+                console.error(chalk.red(`✗ Invalid token count: ${count}`))
+                process.exit(1)
+            }
+
+            updateSessionTokens(cwd, tokens)
+            console.log(chalk.green('✓'), `Tokens used: ${tokens}`)
+        })
+
     return cmd
 }
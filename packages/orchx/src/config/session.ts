@@ -1,13 +1,21 @@
 // ============================================
 // config/session.ts — .orchestrator/session.json CRUD
+// 쓰기: session.json.lock(데스크톱 앱과 공유) 아래에서 임시 파일 → rename
 // ============================================
 
-import { readFileSync, writeFileSync, mkdirSync, existsSync, unlinkSync } from 'node:fs'
+import {
+    readFileSync, writeFileSync, mkdirSync, existsSync, unlinkSync,
+    openSync, closeSync, statSync, renameSync, fsyncSync, linkSync,
+} from 'node:fs'
 import { join } from 'node:path'
 import { randomUUID } from 'node:crypto'
 
 const ORCHESTRATOR_DIR = '.orchestrator'
 const SESSION_FILE = 'session.json'
+const LOCK_FILE = 'session.json.lock'
+// 잠금 대기 한도 / 이보다 오래된 잠금은 비정상 종료한 프로세스가 남긴 것
+const LOCK_TIMEOUT_MS = 2000
+const LOCK_STALE_MS = 10000
 
 export interface OrchestratorSession {
     session_id: string
@@ -22,9 +30,21 @@ export interface OrchestratorSession {
     execution_contract?: {
         allowed_paths: string[]
         allowed_commands: string[]
+        denied_paths?: string[]
+        risk_tier?: 'low' | 'mid' | 'high'
+        path_severity?: { pattern: string; severity: 'info' | 'warn' | 'block' }[]
         budget_tokens?: number
         budget_minutes?: number
     }
+    // 세션 예산 (데스크톱 앱 watcher가 80%/100% 알림)
+    budget?: {
+        max_minutes?: number
+        max_files?: number
+        max_commits?: number
+        max_tokens?: number
+    }
+    // 에이전트가 보고한 누적 토큰 사용량
+    tokens_used?: number
 }
 
 function getSessionPath(projectPath: string): string {
@@ -55,23 +75,118 @@ export function readSession(projectPath: string): OrchestratorSession | null {
     }
 }
 
-export function writeSession(projectPath: string, session: OrchestratorSession): void {
-    ensureOrchestratorDir(projectPath)
-    const sessionPath = getSessionPath(projectPath)
-    writeFileSync(sessionPath, JSON.stringify(session, null, 2), 'utf-8')
+function sleepSync(ms: number): void {
+    Atomics.wait(new Int32Array(new SharedArrayBuffer(4)), 0, 0, ms)
 }
 
-export function deleteSession(projectPath: string): void {
+function isStaleLock(path: string): boolean {
+    try {
+        return Date.now() - statSync(path).mtimeMs > LOCK_STALE_MS
+    } catch {
+        return false
+    }
+}
+
+function readLock(path: string): string | null {
+    try {
+        return readFileSync(path, 'utf-8')
+    } catch {
+        return null
+    }
+}
+
+// 오래된 잠금 빼앗기 (session.rs와 같은 규약) — true면 즉시 생성 재시도
+// 고유 이름으로 rename(한 프로세스만 성공) → 같은 내용 + 여전히 오래됨이면 제거,
+// 판정 뒤 새로 생긴 잠금을 옮겼으면 원래 이름으로 복원 (그새 생긴 잠금은 덮어쓰지 않음)
+function stealStaleLock(lockPath: string): boolean {
+    if (!isStaleLock(lockPath)) return false
+    const seen = readLock(lockPath)
+    if (seen === null) return true
+    const grabbed = `${lockPath}.stale.${randomUUID()}`
+    try {
+        renameSync(lockPath, grabbed)
+    } catch {
+        return true // 다른 프로세스가 먼저 옮김
+    }
+    if (!(isStaleLock(grabbed) && readLock(grabbed) === seen)) {
+        try { linkSync(grabbed, lockPath) } catch { /* 그새 새 잠금 생성됨 */ }
+    }
+    try { unlinkSync(grabbed) } catch { /* 이미 제거됨 */ }
+    return true
+}
+
+// 권고 잠금: 잠금 파일 배타 생성, 내용 "<pid> <토큰>" (데스크톱 앱 session.rs와 같은 규약)
+function withSessionLock<T>(projectPath: string, fn: () => T): T {
+    const lockPath = join(ensureOrchestratorDir(projectPath), LOCK_FILE)
+    const token = `${process.pid} ${randomUUID()}`
+    const deadline = Date.now() + LOCK_TIMEOUT_MS
+    for (;;) {
+        try {
+            const fd = openSync(lockPath, 'wx')
+            writeFileSync(fd, token)
+            closeSync(fd)
+            break
+        } catch (err) {
+            if ((err as NodeJS.ErrnoException).code !== 'EEXIST') throw err
+            if (stealStaleLock(lockPath)) continue
+            if (Date.now() >= deadline) {
+                throw new Error(`Timed out waiting for session lock: ${lockPath}`)
+            }
+            sleepSync(20)
+        }
+    }
+    try {
+        return fn()
+    } finally {
+        // 오래 붙잡는 사이 다른 프로세스가 빼앗아 새로 만든 잠금은 지우지 않음
+        if (readLock(lockPath) === token) {
+            try { unlinkSync(lockPath) } catch { /* 이미 제거됨 */ }
+        }
+    }
+}
+
+// 임시 파일에 쓰고 rename → 읽는 쪽은 이전/새 내용 중 하나만 봄 (잠금 보유 상태에서 호출)
+function writeSessionAtomic(projectPath: string, session: OrchestratorSession): void {
     const sessionPath = getSessionPath(projectPath)
-    if (existsSync(sessionPath)) {
-        unlinkSync(sessionPath)
+    const tmpPath = `${sessionPath}.tmp.${process.pid}`
+    const fd = openSync(tmpPath, 'w')
+    try {
+        writeFileSync(fd, JSON.stringify(session, null, 2), 'utf-8')
+        fsyncSync(fd)
+    } finally {
+        closeSync(fd)
     }
+    renameSync(tmpPath, sessionPath)
+}
+
+export function writeSession(projectPath: string, session: OrchestratorSession): void {
+    withSessionLock(projectPath, () => writeSessionAtomic(projectPath, session))
+}
+
+// 잠금 아래에서 읽기 → 수정 → 원자적 쓰기
+function modifySession(projectPath: string, update: (session: OrchestratorSession) => void): void {
+    withSessionLock(projectPath, () => {
+        const session = readSession(projectPath)
+        if (!session) return
+        update(session)
+        writeSessionAtomic(projectPath, session)
+    })
+}
+
+export function deleteSession(projectPath: string): void {
+    withSessionLock(projectPath, () => {
+        const sessionPath = getSessionPath(projectPath)
+        if (existsSync(sessionPath)) {
+            unlinkSync(sessionPath)
+        }
+    })
 }
 
 export function createSession(
     projectPath: string,
     agentType: string,
     taskName: string,
+    budget?: OrchestratorSession['budget'],
 ): OrchestratorSession {
     const session: OrchestratorSession = {
         session_id: randomUUID(),
@@ -82,6 +197,10 @@ export function createSession(
         files_changed: 0,
         commits_detected: 0,
     }
+    // 빈 예산은 저장하지 않음 (session.rs와 동일)
+    if (budget && Object.values(budget).some(v => v !== undefined)) {
+        session.budget = budget
+    }
     writeSession(projectPath, session)
     return session
 }
@@ -90,21 +209,23 @@ export function updateSessionStats(
     projectPath: string,
     updates: Partial<Pick<OrchestratorSession, 'files_changed' | 'commits_detected'>>,
 ): void {
-    const session = readSession(projectPath)
-    if (!session) return
+    modifySession(projectPath, (session) => {
+        if (updates.files_changed !== undefined) session.files_changed = updates.files_changed
+        if (updates.commits_detected !== undefined) session.commits_detected = updates.commits_detected
+    })
+}
 
-    if (updates.files_changed !== undefined) session.files_changed = updates.files_changed
-    if (updates.commits_detected !== undefined) session.commits_detected = updates.commits_detected
-    writeSession(projectPath, session)
+export function updateSessionTokens(projectPath: string, tokensUsed: number): void {
+    modifySession(projectPath, (session) => {
+        session.tokens_used = tokensUsed
+    })
 }
 
 export function updateSessionContract(
     projectPath: string,
     contract: OrchestratorSession['execution_contract'],
 ): void {
-    const session = readSession(projectPath)
-    if (!session) return
-
-    session.execution_contract = contract
-    writeSession(projectPath, session)
+    modifySession(projectPath, (session) => {
+        session.execution_contract = contract
+    })
 }
@@ -1,3 +1,34 @@
 fn main() {
+  embed_supabase_config();
   tauri_build::build()
 }
+
+// Supabase 설정 빌드 시점 임베드 (패키징된 앱에는 런타임 환경변수 / .env 없음)
+// 우선순위: 빌드 환경변수 → ../.env.local → ../.env
+fn embed_supabase_config() {
+  const KEYS: [&str; 2] = ["VITE_SUPABASE_URL", "VITE_SUPABASE_ANON_KEY"];
+  for key in KEYS {
+    println!("cargo:rerun-if-env-changed={}", key);
+  }
+  if KEYS.iter().all(|key| std::env::var(key).is_ok()) {
+    return;
+  }
+
+  for file in ["../.env.local", "../.env"] {
+    let Ok(content) = std::fs::read_to_string(file) else {
+      continue;
+    };
+    println!("cargo:rerun-if-changed={}", file);
+    let vars: Vec<(&str, &str)> = content
+      .lines()
+      .filter_map(|line| line.trim().split_once('='))
+      .map(|(k, v)| (k.trim(), v.trim().trim_matches(|c| c == '"' || c == '\'')))
+      .collect();
+    let lookup = |key: &str| vars.iter().find(|(k, _)| *k == key).map(|(_, v)| *v);
+    if let (Some(url), Some(anon_key)) = (lookup(KEYS[0]), lookup(KEYS[1])) {
+      println!("cargo:rustc-env={}={}", KEYS[0], url);
+      println!("cargo:rustc-env={}={}", KEYS[1], anon_key);
+      return;
+    }
+  }
+}
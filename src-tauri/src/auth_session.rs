@@ -0,0 +1,403 @@
+// ===========================================
+// auth_session.rs — 로그인 사용자 Supabase 세션 (Rust 코어 보관)
+// 프론트엔드 로그인/토큰 갱신 → set_auth_session으로 전달
+// 만료 직전이면 refresh_token으로 직접 갱신 → orchx:auth-refreshed로 프론트엔드 동기화
+// 로그아웃 상태에서는 anon key로 대체하지 않고 NOT_SIGNED_IN 에러
+// ===========================================
+
+use crate::sync_client::SupabaseConfig;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// 로그아웃 상태 에러 (outbox 이벤트는 재로그인 때까지 보류)
+pub const NOT_SIGNED_IN: &str = "로그인 필요: Supabase 세션 없음";
+/// 만료 이 시간 전부터 갱신 (초)
+/// supabase-js 자동 갱신(약 90초 전)이 먼저 돌도록 더 짧게 잡음
+const REFRESH_MARGIN_SECS: i64 = 60;
+
+/// 사용자 토큰 (expires_at: unix 초)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthTokens {
+    pub user_id: String,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_at: i64,
+}
+
+impl AuthTokens {
+    /// 갱신 필요 여부 (만료 REFRESH_MARGIN_SECS 전부터)
+    fn needs_refresh(&self, now: i64) -> bool {
+        self.expires_at - now <= REFRESH_MARGIN_SECS
+    }
+}
+
+/// GoTrue /token 응답 → 토큰 (expires_at 없으면 expires_in으로 계산)
+pub fn parse_token_response(body: &serde_json::Value, now: i64) -> Result<AuthTokens, String> {
+    let field = |name: &str| {
+        body[name]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| format!("토큰 응답에 {} 없음", name))
+    };
+    let expires_at = body["expires_at"]
+        .as_i64()
+        .or_else(|| body["expires_in"].as_i64().map(|secs| now + secs))
+        .ok_or_else(|| "토큰 응답에 만료 시각 없음".to_string())?;
+    Ok(AuthTokens {
+        user_id: body["user"]["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| "토큰 응답에 user.id 없음".to_string())?,
+        access_token: field("access_token")?,
+        refresh_token: field("refresh_token")?,
+        expires_at,
+    })
+}
+
+/// 갱신 거부 중 세션 무효 판정 (400/401 + invalid_grant만)
+fn is_invalid_grant(status: reqwest::StatusCode, body: &str) -> bool {
+    if status != reqwest::StatusCode::BAD_REQUEST && status != reqwest::StatusCode::UNAUTHORIZED {
+        return false;
+    }
+    serde_json::from_str::<serde_json::Value>(body)
+        .map(|v| v["error"] == "invalid_grant")
+        .unwrap_or(false)
+}
+
+pub fn unix_now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+type RefreshHook = Box<dyn Fn(&AuthTokens) + Send + Sync>;
+
+/// 세션 보관 + 갱신 (SyncClient / SyncEngine / Realtime 공용)
+pub struct AuthSession {
+    client: reqwest::Client,
+    config: SupabaseConfig,
+    tokens: Mutex<Option<AuthTokens>>,
+    /// 갱신 직렬화 (회전된 refresh token을 두 번 쓰면 invalid_grant → 로그아웃)
+    refresh_lock: tokio::sync::Mutex<()>,
+    /// Rust에서 갱신했을 때 호출 (프론트엔드 세션 교체용)
+    on_refresh: Mutex<Option<RefreshHook>>,
+}
+
+impl AuthSession {
+    pub fn new(config: SupabaseConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+            tokens: Mutex::new(None),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            on_refresh: Mutex::new(None),
+        }
+    }
+
+    fn lock_tokens(&self) -> std::sync::MutexGuard<'_, Option<AuthTokens>> {
+        self.tokens.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_on_refresh(&self, hook: impl Fn(&AuthTokens) + Send + Sync + 'static) {
+        *self.on_refresh.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(hook));
+    }
+
+    /// 로그인/프론트엔드 갱신 반영 → 사용자가 바뀌었으면 true
+    pub fn set_session(&self, tokens: AuthTokens) -> bool {
+        let mut current = self.lock_tokens();
+        let user_changed = current.as_ref().map(|t| t.user_id.as_str()) != Some(&tokens.user_id);
+        *current = Some(tokens);
+        user_changed
+    }
+
+    /// 로그아웃 → 세션이 있었으면 true
+    pub fn clear(&self) -> bool {
+        self.lock_tokens().take().is_some()
+    }
+
+    pub fn user_id(&self) -> Option<String> {
+        self.lock_tokens().as_ref().map(|t| t.user_id.clone())
+    }
+
+    pub fn is_signed_in(&self) -> bool {
+        self.lock_tokens().is_some()
+    }
+
+    /// 갱신 없이 현재 access token (Realtime 채널 토큰 교체 확인용)
+    pub fn current_access_token(&self) -> Option<String> {
+        self.lock_tokens().as_ref().map(|t| t.access_token.clone())
+    }
+
+    /// 유효한 access token (만료 임박 시 갱신, 로그아웃 상태면 NOT_SIGNED_IN)
+    pub async fn access_token(&self) -> Result<String, String> {
+        let tokens = self
+            .lock_tokens()
+            .clone()
+            .ok_or_else(|| NOT_SIGNED_IN.to_string())?;
+        if !tokens.needs_refresh(unix_now()) {
+            return Ok(tokens.access_token);
+        }
+        self.refresh(&tokens).await.map(|t| t.access_token)
+    }
+
+    /// refresh_token → 새 토큰 (refresh token은 회전됨)
+    /// 동시 호출은 한 번만 갱신, 대기하던 쪽은 이미 회전된 토큰 재사용
+    async fn refresh(&self, stale: &AuthTokens) -> Result<AuthTokens, String> {
+        let _guard = self.refresh_lock.lock().await;
+        let current = self.lock_tokens().clone();
+        match current {
+            None => return Err(NOT_SIGNED_IN.to_string()),
+            Some(t) if t.refresh_token != stale.refresh_token => return Ok(t),
+            Some(_) => {}
+        }
+
+        let url = format!("{}/auth/v1/token", self.config.url.trim_end_matches('/'));
+        let resp = self
+            .client
+            .post(&url)
+            .query(&[("grant_type", "refresh_token")])
+            .header("apikey", &self.config.anon_key)
+            .json(&serde_json::json!({ "refresh_token": stale.refresh_token }))
+            .send()
+            .await
+            .map_err(|e| format!("토큰 갱신 네트워크 오류: {}", e))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            // 폐기/재사용된 refresh token → 세션 무효, 재로그인 필요
+            // 그 외(429 / 408 / 5xx 등)는 세션 유지 → 다음 호출에서 재시도
+            if is_invalid_grant(status, &body) {
+                self.clear_if_current(stale);
+                log::warn!("🔑 세션 만료 → 재로그인 필요 ({})", status);
+                return Err(format!(
+                    "{} (토큰 갱신 거부 {}: {})",
+                    NOT_SIGNED_IN, status, body
+                ));
+            }
+            return Err(format!("토큰 갱신 실패 ({}): {}", status, body));
+        }
+
+        let body: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| format!("토큰 응답 파싱 실패: {}", e))?;
+        let fresh = parse_token_response(&body, unix_now())?;
+
+        {
+            let mut current = self.lock_tokens();
+            match current.as_ref() {
+                // 그 사이 로그아웃 → 갱신 결과 버림
+                None => return Err(NOT_SIGNED_IN.to_string()),
+                // 다른 경로(프론트엔드)가 먼저 갱신함 → 그쪽 유지
+                Some(t) if t.refresh_token != stale.refresh_token => {
+                    return Ok(t.clone());
+                }
+                Some(_) => *current = Some(fresh.clone()),
+            }
+        }
+        log::info!("🔑 access token 갱신 (만료 {})", fresh.expires_at);
+        if let Some(hook) = self
+            .on_refresh
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+        {
+            hook(&fresh);
+        }
+        Ok(fresh)
+    }
+
+    fn clear_if_current(&self, stale: &AuthTokens) {
+        let mut current = self.lock_tokens();
+        if current.as_ref().map(|t| &t.refresh_token) == Some(&stale.refresh_token) {
+            *current = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn tokens(access: &str, expires_at: i64) -> AuthTokens {
+        AuthTokens {
+            user_id: "u1".to_string(),
+            access_token: access.to_string(),
+            refresh_token: format!("{}-refresh", access),
+            expires_at,
+        }
+    }
+
+    /// HTTP 요청 1건 수신 → 고정 응답, 요청 원문 반환
+    async fn serve_once(listener: tokio::net::TcpListener, status: &str, body: String) -> String {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 1024];
+        loop {
+            let n = socket.read(&mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+            let text = String::from_utf8_lossy(&buf).to_string();
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text
+                    .lines()
+                    .find_map(|l| {
+                        l.to_ascii_lowercase()
+                            .strip_prefix("content-length:")
+                            .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                    })
+                    .unwrap_or(0);
+                if buf.len() >= end + 4 + length || n == 0 {
+                    break;
+                }
+            }
+        }
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+        String::from_utf8_lossy(&buf).to_string()
+    }
+
+    #[test]
+    fn test_parse_token_response_and_expiry() {
+        let body = serde_json::json!({
+            "access_token": "a2",
+            "refresh_token": "r2",
+            "expires_in": 3600,
+            "user": { "id": "u1" },
+        });
+        let parsed = parse_token_response(&body, 1_000).unwrap();
+        assert_eq!(parsed.expires_at, 4_600);
+        assert_eq!(parsed.refresh_token, "r2");
+        assert!(parse_token_response(&serde_json::json!({ "access_token": "a" }), 0).is_err());
+
+        assert!(!tokens("a", 1_000).needs_refresh(1_000 - REFRESH_MARGIN_SECS - 1));
+        assert!(tokens("a", 1_000).needs_refresh(1_000 - REFRESH_MARGIN_SECS));
+    }
+
+    #[test]
+    fn test_only_invalid_grant_signs_out() {
+        use reqwest::StatusCode;
+        let grant = r#"{"error":"invalid_grant","error_description":"Invalid Refresh Token"}"#;
+        assert!(is_invalid_grant(StatusCode::BAD_REQUEST, grant));
+        assert!(is_invalid_grant(StatusCode::UNAUTHORIZED, grant));
+        assert!(!is_invalid_grant(StatusCode::TOO_MANY_REQUESTS, grant));
+        assert!(!is_invalid_grant(StatusCode::REQUEST_TIMEOUT, ""));
+        assert!(!is_invalid_grant(
+            StatusCode::BAD_REQUEST,
+            r#"{"error":"invalid_request"}"#
+        ));
+        assert!(!is_invalid_grant(StatusCode::BAD_REQUEST, "not json"));
+    }
+
+    #[tokio::test]
+    async fn test_access_token_refreshes_before_expiry() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let reply = serde_json::json!({
+            "access_token": "fresh",
+            "refresh_token": "fresh-refresh",
+            "expires_at": unix_now() + 3600,
+            "user": { "id": "u1" },
+        });
+        let server = tokio::spawn(serve_once(listener, "200 OK", reply.to_string()));
+
+        let auth = AuthSession::new(SupabaseConfig {
+            url: format!("http://{}", addr),
+            anon_key: "anon".to_string(),
+        });
+        assert_eq!(auth.access_token().await.unwrap_err(), NOT_SIGNED_IN);
+
+        let hook_calls = Arc::new(AtomicU32::new(0));
+        let calls = hook_calls.clone();
+        auth.set_on_refresh(move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+        });
+
+        // 유효한 토큰은 그대로
+        assert!(auth.set_session(tokens("valid", unix_now() + 3600)));
+        assert_eq!(auth.access_token().await.unwrap(), "valid");
+
+        // 만료 임박 → refresh_token으로 갱신
+        assert!(!auth.set_session(tokens("stale", unix_now() + 10)));
+        assert_eq!(auth.access_token().await.unwrap(), "fresh");
+        assert_eq!(hook_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(auth.current_access_token().as_deref(), Some("fresh"));
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /auth/v1/token?grant_type=refresh_token"));
+        assert!(request.contains("apikey: anon"));
+        assert!(request.contains("stale-refresh"));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_refresh_rotates_once() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let reply = serde_json::json!({
+            "access_token": "fresh",
+            "refresh_token": "fresh-refresh",
+            "expires_at": unix_now() + 3600,
+            "user": { "id": "u1" },
+        });
+        // 요청 1건만 처리 → 두 번째 갱신 요청은 연결 실패
+        let server = tokio::spawn(serve_once(listener, "200 OK", reply.to_string()));
+
+        let auth = AuthSession::new(SupabaseConfig {
+            url: format!("http://{}", addr),
+            anon_key: "anon".to_string(),
+        });
+        auth.set_session(tokens("stale", unix_now() + 10));
+        let (a, b) = tokio::join!(auth.access_token(), auth.access_token());
+        assert_eq!(a.unwrap(), "fresh");
+        assert_eq!(b.unwrap(), "fresh");
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejected_refresh_signs_out() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve_once(
+            listener,
+            "400 Bad Request",
+            r#"{"error":"invalid_grant"}"#.to_string(),
+        ));
+
+        let auth = AuthSession::new(SupabaseConfig {
+            url: format!("http://{}", addr),
+            anon_key: "anon".to_string(),
+        });
+        auth.set_session(tokens("stale", unix_now()));
+        let err = auth.access_token().await.unwrap_err();
+        assert!(err.starts_with(NOT_SIGNED_IN));
+        assert!(!auth.is_signed_in());
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_refresh_keeps_session() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(serve_once(
+            listener,
+            "429 Too Many Requests",
+            r#"{"error":"over_request_rate_limit"}"#.to_string(),
+        ));
+
+        let auth = AuthSession::new(SupabaseConfig {
+            url: format!("http://{}", addr),
+            anon_key: "anon".to_string(),
+        });
+        auth.set_session(tokens("stale", unix_now()));
+        let err = auth.access_token().await.unwrap_err();
+        assert!(!err.starts_with(NOT_SIGNED_IN));
+        assert!(auth.is_signed_in());
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+// ===========================================
+// budget.rs — 세션 예산 추적
+// 시간(분)/변경 파일/커밋/토큰 사용량 → 80% 경고, 100% 초과
+// ===========================================
+
+use crate::session::Budget;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// 경고 발생 비율
+const WARNING_RATIO: f64 = 0.8;
+
+/// 예산 항목
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetMetric {
+    Minutes,
+    Files,
+    Commits,
+    Tokens,
+}
+
+/// 알림 단계
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetLevel {
+    Warning,
+    Exceeded,
+}
+
+impl BudgetLevel {
+    /// Tauri 이벤트 이름
+    pub fn tauri_event(&self) -> &'static str {
+        match self {
+            BudgetLevel::Warning => "orchx:budget-warning",
+            BudgetLevel::Exceeded => "orchx:budget-exceeded",
+        }
+    }
+
+    /// cli_events event_type
+    pub fn cli_event(&self) -> &'static str {
+        match self {
+            BudgetLevel::Warning => "budget.warning",
+            BudgetLevel::Exceeded => "budget.exceeded",
+        }
+    }
+}
+
+/// 예산 알림 (프론트엔드/서버로 전송)
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetAlert {
+    pub metric: BudgetMetric,
+    pub level: BudgetLevel,
+    pub used: u64,
+    pub limit: u64,
+    pub ratio: f64,
+    /// 알림 대상 세션 (멀티 세션 작업 트리에서 watcher가 채움)
+    pub session_id: Option<String>,
+}
+
+/// 현재 사용량
+#[derive(Debug, Clone, Default)]
+pub struct BudgetUsage {
+    pub minutes: u64,
+    pub files: u64,
+    pub commits: u64,
+    pub tokens: Option<u64>,
+}
+
+/// 예산 추적기 — 항목별로 경고/초과를 한 번씩만 알림
+pub struct BudgetTracker {
+    budget: Budget,
+    warned: HashSet<BudgetMetric>,
+    exceeded: HashSet<BudgetMetric>,
+}
+
+impl BudgetTracker {
+    pub fn new(budget: Budget) -> Self {
+        Self {
+            budget,
+            warned: HashSet::new(),
+            exceeded: HashSet::new(),
+        }
+    }
+
+    pub fn has_budget(&self) -> bool {
+        !self.budget.is_empty()
+    }
+
+    /// 사용량 체크 → 새로 넘은 임계치에 대한 알림 반환
+    pub fn check(&mut self, usage: &BudgetUsage) -> Vec<BudgetAlert> {
+        let items = [
+            (BudgetMetric::Minutes, Some(usage.minutes), self.budget.max_minutes),
+            (BudgetMetric::Files, Some(usage.files), self.budget.max_files),
+            (BudgetMetric::Commits, Some(usage.commits), self.budget.max_commits),
+            (BudgetMetric::Tokens, usage.tokens, self.budget.max_tokens),
+        ];
+
+        let mut alerts = Vec::new();
+        for (metric, used, limit) in items {
+            let (used, limit) = match (used, limit) {
+                (Some(u), Some(l)) if l > 0 => (u, l),
+                _ => continue,
+            };
+            let ratio = used as f64 / limit as f64;
+
+            let level = if ratio >= 1.0 {
+                if !self.exceeded.insert(metric) {
+                    continue;
+                }
+                // 초과가 먼저 감지되면 경고는 생략
+                self.warned.insert(metric);
+                BudgetLevel::Exceeded
+            } else if ratio >= WARNING_RATIO {
+                if !self.warned.insert(metric) {
+                    continue;
+                }
+                BudgetLevel::Warning
+            } else {
+                continue;
+            };
+
+            alerts.push(BudgetAlert {
+                metric,
+                level,
+                used,
+                limit,
+                ratio,
+                session_id: None,
+            });
+        }
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning_then_exceeded_once() {
+        let mut tracker = BudgetTracker::new(Budget {
+            max_files: Some(10),
+            ..Default::default()
+        });
+        let usage = |files| BudgetUsage {
+            files,
+            ..Default::default()
+        };
+
+        assert!(tracker.check(&usage(7)).is_empty());
+
+        let alerts = tracker.check(&usage(8));
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].level, BudgetLevel::Warning);
+        assert!(tracker.check(&usage(9)).is_empty());
+
+        let alerts = tracker.check(&usage(10));
+        assert_eq!(alerts[0].level, BudgetLevel::Exceeded);
+        assert!(tracker.check(&usage(12)).is_empty());
+    }
+
+    #[test]
+    fn test_exceeded_skips_warning_and_ignores_unreported_tokens() {
+        let mut tracker = BudgetTracker::new(Budget {
+            max_minutes: Some(30),
+            max_tokens: Some(1000),
+            ..Default::default()
+        });
+        let alerts = tracker.check(&BudgetUsage {
+            minutes: 45,
+            tokens: None,
+            ..Default::default()
+        });
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].metric, BudgetMetric::Minutes);
+        assert_eq!(alerts[0].level, BudgetLevel::Exceeded);
+    }
+}
@@ -0,0 +1,131 @@
+// ===========================================
+// command_log.rs — 에이전트 실행 명령 로그
+// .orchestrator/commands.log (JSONL) tail → 계약 집행기 입력
+// `orchx exec -- <cmd>` 래퍼 또는 쉘 훅이 한 줄씩 append
+// ===========================================
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// 실행 명령 기록 (로그 한 줄)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandRecord {
+    pub command: String,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub executed_at: Option<String>,
+}
+
+/// 명령 로그 파일 경로
+pub fn command_log_path(project_path: &Path) -> PathBuf {
+    project_path.join(".orchestrator").join("commands.log")
+}
+
+/// 명령 로그 tail 리더
+/// 마지막으로 읽은 위치(offset)를 기억하고 새로 추가된 줄만 반환
+pub struct CommandLogTail {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl CommandLogTail {
+    /// 현재 파일 끝에서 시작 (watcher 시작 전 기록은 재생하지 않음)
+    pub fn new(project_path: &Path) -> Self {
+        let path = command_log_path(project_path);
+        let offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self { path, offset }
+    }
+
+    /// 새로 추가된 명령 읽기
+    /// 줄바꿈으로 끝나지 않은 마지막 줄은 다음 호출로 미룸
+    pub fn read_new(&mut self) -> Vec<CommandRecord> {
+        let mut file = match File::open(&self.path) {
+            Ok(f) => f,
+            Err(_) => return Vec::new(),
+        };
+
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < self.offset {
+            // truncate/rotate 됨 → 처음부터
+            self.offset = 0;
+        }
+        if len == self.offset {
+            return Vec::new();
+        }
+
+        let mut buf = Vec::new();
+        if file.seek(SeekFrom::Start(self.offset)).is_err() || file.read_to_end(&mut buf).is_err() {
+            return Vec::new();
+        }
+
+        let complete = match buf.iter().rposition(|b| *b == b'\n') {
+            Some(pos) => pos + 1,
+            None => return Vec::new(),
+        };
+        self.offset += complete as u64;
+
+        String::from_utf8_lossy(&buf[..complete])
+            .lines()
+            .filter_map(parse_line)
+            .collect()
+    }
+}
+
+/// 로그 한 줄 파싱: JSON 객체 또는 평문 명령 (쉘 history 훅 호환)
+fn parse_line(line: &str) -> Option<CommandRecord> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    if trimmed.starts_with('{') {
+        return serde_json::from_str(trimmed).ok();
+    }
+    Some(CommandRecord {
+        command: trimmed.to_string(),
+        cwd: None,
+        executed_at: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_tail_reads_only_new_complete_lines() {
+        let project = std::env::temp_dir().join("orchestrator_command_log_test");
+        let _ = std::fs::remove_dir_all(&project);
+        std::fs::create_dir_all(project.join(".orchestrator")).unwrap();
+        let log_path = command_log_path(&project);
+        std::fs::write(&log_path, "npm test\n").unwrap();
+
+        // 시작 이전 기록은 무시
+        let mut tail = CommandLogTail::new(&project);
+        assert!(tail.read_new().is_empty());
+
+        let mut file = std::fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+        write!(file, "{{\"command\":\"npm publish\",\"cwd\":\"/tmp\"}}\nrm -rf di").unwrap();
+        let records = tail.read_new();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].command, "npm publish");
+        assert_eq!(records[0].cwd.as_deref(), Some("/tmp"));
+
+        // 미완성 줄은 완성된 뒤에 반환
+        writeln!(file, "st").unwrap();
+        let records = tail.read_new();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].command, "rm -rf dist");
+
+        // truncate 후 처음부터
+        std::fs::write(&log_path, "git push\n").unwrap();
+        let records = tail.read_new();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].command, "git push");
+
+        let _ = std::fs::remove_dir_all(&project);
+    }
+}
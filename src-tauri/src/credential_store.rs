@@ -0,0 +1,515 @@
+// ===========================================
+// credential_store.rs — 토큰 등 비밀값 저장소
+// 1순위: OS 키링 (macOS Keychain / Windows Credential Manager / Linux Secret Service)
+// 2순위: 암호화 파일 ~/.orchestrator/credentials.enc (사용자 암호 → Argon2id → AES-256-GCM)
+// DB에는 "cred:<backend>:<account>" 참조만 저장
+// ===========================================
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use zeroize::Zeroizing;
+
+/// 키링 서비스 이름 (tauri.conf.json identifier)
+const KEYRING_SERVICE: &str = "com.jungjipdo.orchestrator";
+/// 참조 접두사
+const REF_PREFIX: &str = "cred:";
+/// 암호화 파일 형식 버전
+const FILE_VERSION: u32 = 1;
+/// 암호 확인용 평문 (잘못된 암호면 복호화 실패)
+const CHECK_PLAINTEXT: &str = "orchestrator-credentials";
+/// 잠금 상태 에러
+pub const LOCKED: &str = "자격 증명 저장소 잠김: 암호 입력 필요";
+
+/// 비밀값 저장 백엔드
+pub trait CredentialStore: Send + Sync {
+    /// 참조에 기록되는 백엔드 이름
+    fn backend(&self) -> &'static str;
+    fn put(&self, account: &str, secret: &str) -> Result<(), String>;
+    fn get(&self, account: &str) -> Result<Option<String>, String>;
+    fn delete(&self, account: &str) -> Result<(), String>;
+}
+
+// --- OS 키링 ---
+
+pub struct KeyringStore;
+
+impl KeyringStore {
+    /// 키링 사용 가능 여부 확인 (Secret Service 데몬 없음 등 → None)
+    /// 읽기만 시도: 없는 항목 조회가 NoEntry로 끝나면 사용 가능 (시작마다 키링에 쓰지 않음)
+    pub fn probe() -> Option<Self> {
+        let store = KeyringStore;
+        store.get("__probe__").is_ok().then_some(store)
+    }
+
+    /// 키링 호출은 별도 스레드에서 (Secret Service + tokio: 런타임 스레드에서 호출 시 교착)
+    fn with_entry<T: Send + 'static>(
+        account: &str,
+        op: impl FnOnce(keyring::Entry) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        let account = account.to_string();
+        std::thread::spawn(move || {
+            let entry = keyring::Entry::new(KEYRING_SERVICE, &account)
+                .map_err(|e| format!("키링 오류: {}", e))?;
+            op(entry)
+        })
+        .join()
+        .map_err(|_| "키링 스레드 비정상 종료".to_string())?
+    }
+}
+
+impl CredentialStore for KeyringStore {
+    fn backend(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn put(&self, account: &str, secret: &str) -> Result<(), String> {
+        let secret = Zeroizing::new(secret.to_string());
+        Self::with_entry(account, move |entry| {
+            entry
+                .set_password(&secret)
+                .map_err(|e| format!("키링 저장 실패: {}", e))
+        })
+    }
+
+    fn get(&self, account: &str) -> Result<Option<String>, String> {
+        Self::with_entry(account, |entry| match entry.get_password() {
+            Ok(secret) => Ok(Some(secret)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("키링 조회 실패: {}", e)),
+        })
+    }
+
+    fn delete(&self, account: &str) -> Result<(), String> {
+        Self::with_entry(account, |entry| match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("키링 삭제 실패: {}", e)),
+        })
+    }
+}
+
+// --- 암호화 파일 ---
+
+/// Argon2id 파라미터 + salt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    m_cost: u32,
+    t_cost: u32,
+    p_cost: u32,
+    salt: String,
+}
+
+impl KdfParams {
+    fn derive(&self, passphrase: &str) -> Result<Zeroizing<[u8; 32]>, String> {
+        let params = argon2::Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map_err(|e| format!("KDF 설정 오류: {}", e))?;
+        let salt = b64_decode(&self.salt)?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key[..])
+            .map_err(|e| format!("키 유도 실패: {}", e))?;
+        Ok(key)
+    }
+}
+
+/// 암호문 1건 (account를 AAD로 묶어 항목 바꿔치기 방지)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Sealed {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: KdfParams,
+    check: Sealed,
+    entries: BTreeMap<String, Sealed>,
+}
+
+fn b64_decode(value: &str) -> Result<Vec<u8>, String> {
+    base64::engine::general_purpose::STANDARD
+        .decode(value)
+        .map_err(|e| format!("자격 증명 파일 손상: {}", e))
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut buf = [0u8; N];
+    getrandom::getrandom(&mut buf).map_err(|e| format!("난수 생성 실패: {}", e))?;
+    Ok(buf)
+}
+
+fn seal(key: &[u8; 32], account: &str, plaintext: &str) -> Result<Sealed, String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
+    let nonce = random_bytes::<12>()?;
+    let ciphertext = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext.as_bytes(),
+                aad: account.as_bytes(),
+            },
+        )
+        .map_err(|_| "암호화 실패".to_string())?;
+    let b64 = base64::engine::general_purpose::STANDARD;
+    Ok(Sealed {
+        nonce: b64.encode(nonce),
+        ciphertext: b64.encode(ciphertext),
+    })
+}
+
+fn open_sealed(key: &[u8; 32], account: &str, sealed: &Sealed) -> Result<String, String> {
+    let cipher = Aes256Gcm::new_from_slice(key).map_err(|e| e.to_string())?;
+    let nonce = b64_decode(&sealed.nonce)?;
+    if nonce.len() != 12 {
+        return Err("자격 증명 파일 손상: nonce 길이".to_string());
+    }
+    let plaintext = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: &b64_decode(&sealed.ciphertext)?,
+                aad: account.as_bytes(),
+            },
+        )
+        .map_err(|_| "복호화 실패 (암호 불일치 또는 파일 손상)".to_string())?;
+    String::from_utf8(plaintext).map_err(|e| e.to_string())
+}
+
+/// 암호화 파일 저장소 (unlock 전에는 읽기/쓰기 불가)
+pub struct EncryptedFileStore {
+    path: PathBuf,
+    /// 새 파일 생성 시 KDF 비용 (m_cost KiB, t_cost, p_cost)
+    cost: (u32, u32, u32),
+    key: Mutex<Option<Zeroizing<[u8; 32]>>>,
+}
+
+impl EncryptedFileStore {
+    pub fn new(path: PathBuf) -> Self {
+        let defaults = argon2::Params::default();
+        Self::with_cost(
+            path,
+            (defaults.m_cost(), defaults.t_cost(), defaults.p_cost()),
+        )
+    }
+
+    fn with_cost(path: PathBuf, cost: (u32, u32, u32)) -> Self {
+        Self {
+            path,
+            cost,
+            key: Mutex::new(None),
+        }
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.key.lock().map(|k| k.is_some()).unwrap_or(false)
+    }
+
+    /// 암호로 잠금 해제 (파일이 없으면 이 암호로 새로 생성)
+    pub fn unlock(&self, passphrase: &str) -> Result<(), String> {
+        if passphrase.is_empty() {
+            return Err("암호가 비어 있음".to_string());
+        }
+        let key = match self.read()? {
+            Some(file) => {
+                let key = file.kdf.derive(passphrase)?;
+                open_sealed(&key, "", &file.check)
+                    .map_err(|_| "암호가 일치하지 않음".to_string())?;
+                key
+            }
+            None => {
+                let (m_cost, t_cost, p_cost) = self.cost;
+                let kdf = KdfParams {
+                    m_cost,
+                    t_cost,
+                    p_cost,
+                    salt: base64::engine::general_purpose::STANDARD.encode(random_bytes::<16>()?),
+                };
+                let key = kdf.derive(passphrase)?;
+                let file = VaultFile {
+                    version: FILE_VERSION,
+                    check: seal(&key, "", CHECK_PLAINTEXT)?,
+                    kdf,
+                    entries: BTreeMap::new(),
+                };
+                self.write(&file)?;
+                log::info!("🔐 암호화 자격 증명 파일 생성: {}", self.path.display());
+                key
+            }
+        };
+        *self.key.lock().map_err(|e| e.to_string())? = Some(key);
+        Ok(())
+    }
+
+    pub fn lock(&self) {
+        if let Ok(mut key) = self.key.lock() {
+            *key = None;
+        }
+    }
+
+    fn key(&self) -> Result<Zeroizing<[u8; 32]>, String> {
+        self.key
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone()
+            .ok_or_else(|| LOCKED.to_string())
+    }
+
+    fn read(&self) -> Result<Option<VaultFile>, String> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("자격 증명 파일 읽기 실패: {}", e)),
+        };
+        let file: VaultFile =
+            serde_json::from_str(&content).map_err(|e| format!("자격 증명 파일 손상: {}", e))?;
+        if file.version > FILE_VERSION {
+            return Err(format!(
+                "지원하지 않는 자격 증명 파일 버전: {}",
+                file.version
+            ));
+        }
+        Ok(Some(file))
+    }
+
+    /// 임시 파일 → rename (소유자만 읽기/쓰기)
+    fn write(&self, file: &VaultFile) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let tmp = self.path.with_extension("enc.tmp");
+        let content = serde_json::to_string_pretty(file).map_err(|e| e.to_string())?;
+        // 생성 시점부터 0600 (이전 실행이 남긴 임시 파일의 권한을 물려받지 않도록 새로 생성)
+        let _ = std::fs::remove_file(&tmp);
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        options
+            .open(&tmp)
+            .and_then(|mut f| {
+                use std::io::Write;
+                f.write_all(content.as_bytes())?;
+                f.sync_all()
+            })
+            .map_err(|e| format!("자격 증명 파일 쓰기 실패: {}", e))?;
+        std::fs::rename(&tmp, &self.path).map_err(|e| format!("자격 증명 파일 쓰기 실패: {}", e))
+    }
+
+    fn unlocked_file(&self) -> Result<(Zeroizing<[u8; 32]>, VaultFile), String> {
+        let key = self.key()?;
+        let file = self
+            .read()?
+            .ok_or_else(|| "자격 증명 파일 없음".to_string())?;
+        Ok((key, file))
+    }
+}
+
+impl CredentialStore for EncryptedFileStore {
+    fn backend(&self) -> &'static str {
+        "file"
+    }
+
+    fn put(&self, account: &str, secret: &str) -> Result<(), String> {
+        let (key, mut file) = self.unlocked_file()?;
+        file.entries
+            .insert(account.to_string(), seal(&key, account, secret)?);
+        self.write(&file)
+    }
+
+    fn get(&self, account: &str) -> Result<Option<String>, String> {
+        let (key, file) = self.unlocked_file()?;
+        file.entries
+            .get(account)
+            .map(|sealed| open_sealed(&key, account, sealed))
+            .transpose()
+    }
+
+    fn delete(&self, account: &str) -> Result<(), String> {
+        let (_, mut file) = self.unlocked_file()?;
+        if file.entries.remove(account).is_some() {
+            self.write(&file)?;
+        }
+        Ok(())
+    }
+}
+
+// --- 참조 기반 접근 ---
+
+/// 참조 파싱: "cred:<backend>:<account>"
+fn parse_ref(reference: &str) -> Option<(&str, &str)> {
+    reference.strip_prefix(REF_PREFIX)?.split_once(':')
+}
+
+/// 저장소 상태 (credentials_status 응답)
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialStatus {
+    /// keyring | file
+    pub backend: &'static str,
+    /// 암호화 파일 사용 중이고 잠겨 있으면 true
+    pub locked: bool,
+}
+
+/// 키링 우선, 없으면 암호화 파일
+/// 참조에 백엔드가 기록되므로 이전에 저장한 값은 백엔드가 바뀌어도 찾을 수 있음
+pub struct Credentials {
+    keyring: Option<KeyringStore>,
+    file: EncryptedFileStore,
+}
+
+impl Credentials {
+    /// 기본 위치 (~/.orchestrator/credentials.enc) + 키링 확인
+    pub fn open() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        let keyring = KeyringStore::probe();
+        if keyring.is_some() {
+            log::info!("🔐 자격 증명 저장소: OS 키링");
+        } else {
+            log::warn!("⚠ OS 키링 사용 불가 → 암호화 파일 (잠금 해제 필요)");
+        }
+        Self {
+            keyring,
+            file: EncryptedFileStore::new(home.join(".orchestrator").join("credentials.enc")),
+        }
+    }
+
+    /// 키링 없이 낮은 KDF 비용의 암호화 파일만 사용
+    #[cfg(test)]
+    pub fn for_test(path: &std::path::Path) -> Self {
+        Self {
+            keyring: None,
+            file: EncryptedFileStore::with_cost(path.to_path_buf(), (1024, 1, 1)),
+        }
+    }
+
+    fn active(&self) -> &dyn CredentialStore {
+        match &self.keyring {
+            Some(keyring) => keyring,
+            None => &self.file,
+        }
+    }
+
+    fn store_for(&self, backend: &str) -> Result<&dyn CredentialStore, String> {
+        match (backend, &self.keyring) {
+            ("keyring", Some(keyring)) => Ok(keyring),
+            ("keyring", None) => Err("OS 키링 사용 불가".to_string()),
+            ("file", _) => Ok(&self.file),
+            _ => Err(format!("알 수 없는 자격 증명 백엔드: {}", backend)),
+        }
+    }
+
+    pub fn status(&self) -> CredentialStatus {
+        let backend = self.active().backend();
+        CredentialStatus {
+            backend,
+            locked: backend == "file" && !self.file.is_unlocked(),
+        }
+    }
+
+    pub fn unlock(&self, passphrase: &str) -> Result<(), String> {
+        self.file.unlock(passphrase)
+    }
+
+    pub fn lock(&self) {
+        self.file.lock();
+    }
+
+    /// 비밀값 저장 → 참조 반환
+    pub fn put(&self, account: &str, secret: &str) -> Result<String, String> {
+        let store = self.active();
+        store.put(account, secret)?;
+        Ok(format!("{}{}:{}", REF_PREFIX, store.backend(), account))
+    }
+
+    /// 참조 → 비밀값 (없으면 None)
+    pub fn resolve(&self, reference: &str) -> Result<Option<String>, String> {
+        let (backend, account) =
+            parse_ref(reference).ok_or_else(|| format!("잘못된 자격 증명 참조: {}", reference))?;
+        self.store_for(backend)?.get(account)
+    }
+
+    pub fn remove(&self, reference: &str) -> Result<(), String> {
+        let (backend, account) =
+            parse_ref(reference).ok_or_else(|| format!("잘못된 자격 증명 참조: {}", reference))?;
+        self.store_for(backend)?.delete(account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_file_roundtrip_and_wrong_passphrase() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.enc");
+        // 테스트는 낮은 KDF 비용
+        let store = EncryptedFileStore::with_cost(path.clone(), (1024, 1, 1));
+
+        assert_eq!(store.get("github:c1").unwrap_err(), LOCKED);
+        store.unlock("correct horse").unwrap();
+        store.put("github:c1:access", "gho_secret").unwrap();
+        assert_eq!(
+            store.get("github:c1:access").unwrap().as_deref(),
+            Some("gho_secret")
+        );
+        assert_eq!(store.get("missing").unwrap(), None);
+
+        // 평문이 파일에 남지 않음
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("gho_secret"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // 다시 열기: 잘못된 암호 거부, 올바른 암호로 복호화
+        let reopened = EncryptedFileStore::with_cost(path.clone(), (1024, 1, 1));
+        assert!(reopened.unlock("wrong").is_err());
+        assert!(!reopened.is_unlocked());
+        reopened.unlock("correct horse").unwrap();
+        assert_eq!(
+            reopened.get("github:c1:access").unwrap().as_deref(),
+            Some("gho_secret")
+        );
+
+        // 다른 account로 옮긴 암호문은 복호화 실패 (AAD)
+        let mut file = reopened.read().unwrap().unwrap();
+        let sealed = file.entries["github:c1:access"].clone();
+        file.entries.insert("github:c2:access".to_string(), sealed);
+        reopened.write(&file).unwrap();
+        assert!(reopened.get("github:c2:access").is_err());
+
+        reopened.delete("github:c1:access").unwrap();
+        assert_eq!(reopened.get("github:c1:access").unwrap(), None);
+    }
+
+    #[test]
+    fn test_credentials_references() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.enc");
+        let creds = Credentials::for_test(&path);
+        assert_eq!(creds.status().backend, "file");
+        assert!(creds.status().locked);
+        assert_eq!(creds.put("a", "b").unwrap_err(), LOCKED);
+
+        creds.unlock("pw").unwrap();
+        let reference = creds.put("github:c1:access", "gho_x").unwrap();
+        assert_eq!(reference, "cred:file:github:c1:access");
+        assert_eq!(creds.resolve(&reference).unwrap().as_deref(), Some("gho_x"));
+        assert!(creds.resolve("cred:keyring:github:c1:access").is_err());
+        assert!(creds.resolve("gho_plaintext").is_err());
+
+        creds.remove(&reference).unwrap();
+        assert_eq!(creds.resolve(&reference).unwrap(), None);
+    }
+}
@@ -0,0 +1,778 @@
+// ===========================================
+// db_migrations.rs — local_db 버전별 마이그레이션
+// 레지스트리 순서대로 미적용 버전만 트랜잭션 단위로 실행
+// schema_version에 버전별 체크섬 기록 → 적용 후 정의가 바뀌면 거부
+// 앱보다 새 버전의 DB는 열지 않음, 적용 전 local.db 백업
+// ===========================================
+
+use crate::models::{DbError, DbResult};
+use rusqlite::{params, Connection, OpenFlags};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// 마이그레이션 1단계 (version은 1부터 증가, 적용 후에는 sql 수정 금지 → 새 버전 추가)
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// 이 바이너리가 지원하는 최신 스키마 버전
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// SQL 체크섬 (줄 앞뒤 공백/빈 줄 차이는 무시)
+pub fn checksum(sql: &str) -> String {
+    let normalized: Vec<&str> = sql
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    Sha256::digest(normalized.join("\n").as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 미적용 마이그레이션 실행
+pub fn run(conn: &mut Connection) -> DbResult<()> {
+    run_with(conn, MIGRATIONS)
+}
+
+fn run_with(conn: &mut Connection, migrations: &[Migration]) -> DbResult<()> {
+    ensure_version_table(conn)?;
+
+    let applied: Vec<(u32, Option<String>)> = {
+        let mut stmt =
+            conn.prepare("SELECT version, checksum FROM schema_version ORDER BY version")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+
+    let latest = migrations.last().map(|m| m.version).unwrap_or(0);
+    let current = applied.iter().map(|(v, _)| *v).max().unwrap_or(0);
+    if current > latest {
+        return Err(DbError::Migration(format!(
+            "로컬 DB 스키마 v{}가 앱 지원 버전 v{}보다 높음 → 앱 업데이트 필요",
+            current, latest
+        )));
+    }
+
+    // 적용된 버전 검증 (체크섬 기록 이전의 행은 현재 정의로 채움)
+    for (version, recorded) in &applied {
+        let migration = migrations
+            .iter()
+            .find(|m| m.version == *version)
+            .ok_or_else(|| DbError::Migration(format!("알 수 없는 스키마 버전 v{}", version)))?;
+        let expected = checksum(migration.sql);
+        match recorded {
+            None => {
+                conn.execute(
+                    "UPDATE schema_version SET description = ?2, checksum = ?3 WHERE version = ?1",
+                    params![version, migration.description, expected],
+                )?;
+            }
+            Some(recorded) if *recorded != expected => {
+                return Err(DbError::Migration(format!(
+                    "v{} 마이그레이션 체크섬 불일치: 적용 후 정의가 변경됨",
+                    version
+                )));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let pending: Vec<&Migration> = migrations
+        .iter()
+        .filter(|m| !applied.iter().any(|(v, _)| *v == m.version))
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+    if current > 0 {
+        backup(conn, current)?;
+    }
+
+    for migration in pending {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration.sql).map_err(|e| {
+            DbError::Migration(format!("v{} 마이그레이션 실패: {}", migration.version, e))
+        })?;
+        tx.execute(
+            "INSERT INTO schema_version (version, description, checksum) VALUES (?1, ?2, ?3)",
+            params![
+                migration.version,
+                migration.description,
+                checksum(migration.sql)
+            ],
+        )?;
+        tx.commit()?;
+        log::info!(
+            "✅ v{} 마이그레이션: {}",
+            migration.version,
+            migration.description
+        );
+    }
+    Ok(())
+}
+
+/// schema_version 생성 + 체크섬 도입 이전 테이블에 컬럼 추가
+fn ensure_version_table(conn: &Connection) -> DbResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            applied_at TEXT NOT NULL DEFAULT (datetime('now')),
+            description TEXT,
+            checksum TEXT
+        );",
+    )?;
+    let columns: Vec<String> = {
+        let mut stmt = conn.prepare("PRAGMA table_info(schema_version)")?;
+        let rows = stmt.query_map([], |r| r.get::<_, String>(1))?;
+        rows.collect::<rusqlite::Result<_>>()?
+    };
+    for column in ["description", "checksum"] {
+        if !columns.iter().any(|c| c == column) {
+            conn.execute_batch(&format!(
+                "ALTER TABLE schema_version ADD COLUMN {} TEXT;",
+                column
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// 백업 경로: local.db → local.db.v{N}.bak
+fn backup_path(db_path: &str, version: u32) -> PathBuf {
+    PathBuf::from(format!("{}.v{}.bak", db_path, version))
+}
+
+/// 마이그레이션 전 백업 (VACUUM INTO: WAL 내용까지 포함한 일관된 사본)
+fn backup(conn: &Connection, from_version: u32) -> DbResult<Option<PathBuf>> {
+    let Some(db_path) = conn.path().filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    let target = backup_path(db_path, from_version);
+    let _ = std::fs::remove_file(&target);
+    conn.execute("VACUUM INTO ?1", params![target.to_string_lossy()])
+        .map_err(|e| DbError::Migration(format!("마이그레이션 전 백업 실패: {}", e)))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o600));
+    }
+    log::info!("💾 마이그레이션 전 백업: {}", target.display());
+    Ok(Some(target))
+}
+
+/// 평문 GitHub 토큰이 남아 있는 백업 삭제
+/// - v6 이전 백업: github_connections.access_token
+/// - v6 이후 백업: 저장소 잠금으로 이전이 미뤄진 github_connections_legacy
+pub fn remove_plaintext_backups(conn: &Connection) {
+    let Some(db_path) = conn.path().filter(|p| !p.is_empty()) else {
+        return;
+    };
+    for v in 1..=latest_version() {
+        let path = backup_path(db_path, v);
+        if path.exists() && has_plaintext_tokens(&path) && std::fs::remove_file(&path).is_ok() {
+            log::info!("🗑 평문 토큰이 남은 백업 삭제: {}", path.display());
+        }
+    }
+}
+
+/// 백업에 평문 토큰 테이블/컬럼이 있는지 (열 수 없으면 있다고 간주)
+fn has_plaintext_tokens(path: &std::path::Path) -> bool {
+    let Ok(backup) = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY) else {
+        return true;
+    };
+    backup
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'github_connections_legacy')
+                 OR EXISTS(SELECT 1 FROM pragma_table_info('github_connections') WHERE name = 'access_token')",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(true)
+}
+
+// ─── 레지스트리 ───
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "기본 스키마",
+        sql: "
+            -- 프로젝트 (Supabase projects → 로컬)
+            CREATE TABLE IF NOT EXISTS projects (
+                id TEXT PRIMARY KEY,
+                repo_id INTEGER NOT NULL,
+                repo_name TEXT NOT NULL,
+                repo_full_name TEXT NOT NULL UNIQUE,
+                repo_url TEXT NOT NULL,
+                description TEXT,
+                default_branch TEXT NOT NULL DEFAULT 'main',
+                language TEXT,
+                is_private INTEGER NOT NULL DEFAULT 0,
+                status TEXT NOT NULL DEFAULT 'active',
+                metadata TEXT NOT NULL DEFAULT '{}',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- GitHub 연결 정보
+            CREATE TABLE IF NOT EXISTS github_connections (
+                id TEXT PRIMARY KEY,
+                github_username TEXT,
+                access_token TEXT NOT NULL,
+                refresh_token TEXT,
+                token_expires_at TEXT,
+                connected_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- AI 모델 점수 커스텀
+            CREATE TABLE IF NOT EXISTS model_scores (
+                id TEXT PRIMARY KEY,
+                model_key TEXT NOT NULL UNIQUE,
+                coding REAL NOT NULL DEFAULT 50,
+                analysis REAL NOT NULL DEFAULT 50,
+                documentation REAL NOT NULL DEFAULT 50,
+                speed REAL NOT NULL DEFAULT 50,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- 에디터별 지원 모델
+            CREATE TABLE IF NOT EXISTS editor_models (
+                id TEXT PRIMARY KEY,
+                editor_type TEXT NOT NULL UNIQUE,
+                supported_models TEXT NOT NULL DEFAULT '[]',
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- 프로젝트 데드라인
+            CREATE TABLE IF NOT EXISTS project_deadlines (
+                id TEXT PRIMARY KEY,
+                project_id TEXT NOT NULL,
+                milestone TEXT NOT NULL,
+                deadline_at TEXT NOT NULL,
+                risk_score REAL NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- 고정 이벤트/일정
+            CREATE TABLE IF NOT EXISTS fixed_events (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                start_at TEXT NOT NULL,
+                end_at TEXT NOT NULL,
+                importance TEXT NOT NULL DEFAULT 'medium',
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- Watcher 경로 매핑 (현재 메모리에만 있던 것)
+            CREATE TABLE IF NOT EXISTS watcher_paths (
+                repo_full_name TEXT PRIMARY KEY,
+                local_path TEXT NOT NULL,
+                watching INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- 사용자 설정 (동의 상태 등)
+            CREATE TABLE IF NOT EXISTS user_preferences (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+        ",
+    },
+    Migration {
+        version: 2,
+        description: "동기화 대상 테이블 5개 추가",
+        sql: "
+            -- 작업 항목 (동기화 대상)
+            CREATE TABLE IF NOT EXISTS work_items (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'open',
+                priority TEXT NOT NULL DEFAULT 'medium',
+                project_id TEXT,
+                description TEXT,
+                due_at TEXT,
+                metadata TEXT NOT NULL DEFAULT '{}',
+                sync_status TEXT NOT NULL DEFAULT 'pending',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- 플랜 (동기화 대상)
+            CREATE TABLE IF NOT EXISTS plans (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                plan_type TEXT NOT NULL DEFAULT 'task',
+                status TEXT NOT NULL DEFAULT 'open',
+                priority TEXT NOT NULL DEFAULT 'medium',
+                description TEXT,
+                due_at TEXT,
+                start_at TEXT,
+                metadata TEXT NOT NULL DEFAULT '{}',
+                sync_status TEXT NOT NULL DEFAULT 'pending',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- 목표 (동기화 대상)
+            CREATE TABLE IF NOT EXISTS goals (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                plan_id TEXT,
+                status TEXT NOT NULL DEFAULT 'open',
+                progress REAL NOT NULL DEFAULT 0,
+                metadata TEXT NOT NULL DEFAULT '{}',
+                sync_status TEXT NOT NULL DEFAULT 'pending',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- 세션 로그 (동기화 대상)
+            CREATE TABLE IF NOT EXISTS session_logs (
+                id TEXT PRIMARY KEY,
+                project_id TEXT,
+                editor_type TEXT,
+                started_at TEXT NOT NULL DEFAULT (datetime('now')),
+                ended_at TEXT,
+                duration_min INTEGER,
+                summary TEXT,
+                metadata TEXT NOT NULL DEFAULT '{}',
+                sync_status TEXT NOT NULL DEFAULT 'pending',
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- 동기화 큐 (오프라인 변경 추적)
+            CREATE TABLE IF NOT EXISTS sync_queue (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                table_name TEXT NOT NULL,
+                record_id TEXT NOT NULL,
+                operation TEXT NOT NULL,
+                payload TEXT NOT NULL DEFAULT '{}',
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                synced INTEGER NOT NULL DEFAULT 0
+            );
+        ",
+    },
+    Migration {
+        version: 3,
+        description: "event_outbox 추가",
+        sql: "
+            -- 서버 전송 전 먼저 기록 (pending → delivered | dead)
+            CREATE TABLE IF NOT EXISTS event_outbox (
+                event_id TEXT PRIMARY KEY,
+                event_type TEXT NOT NULL,
+                payload TEXT NOT NULL DEFAULT '{}',
+                session_id TEXT,
+                status TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at TEXT NOT NULL DEFAULT (datetime('now')),
+                last_error TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                delivered_at TEXT
+            );
+            CREATE INDEX IF NOT EXISTS idx_event_outbox_due
+                ON event_outbox(status, next_attempt_at);
+        ",
+    },
+    Migration {
+        version: 4,
+        description: "sync_cursors 추가",
+        sql: "
+            -- 테이블별 마지막으로 받은 원격 변경 시각
+            CREATE TABLE IF NOT EXISTS sync_cursors (
+                table_name TEXT PRIMARY KEY,
+                cursor TEXT NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+        ",
+    },
+    Migration {
+        version: 5,
+        description: "remote_updated_at + sync_conflicts 추가",
+        sql: "
+            -- 마지막으로 확인한 원격 updated_at (로컬 변경의 기준 버전)
+            ALTER TABLE work_items ADD COLUMN remote_updated_at TEXT;
+            ALTER TABLE plans ADD COLUMN remote_updated_at TEXT;
+            ALTER TABLE goals ADD COLUMN remote_updated_at TEXT;
+            ALTER TABLE session_logs ADD COLUMN remote_updated_at TEXT;
+
+            -- 충돌 기록 (resolution NULL = 미해결)
+            CREATE TABLE IF NOT EXISTS sync_conflicts (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                table_name TEXT NOT NULL,
+                record_id TEXT NOT NULL,
+                policy TEXT NOT NULL,
+                local_payload TEXT NOT NULL DEFAULT '{}',
+                remote_payload TEXT NOT NULL DEFAULT '{}',
+                resolution TEXT,
+                detected_at TEXT NOT NULL DEFAULT (datetime('now')),
+                resolved_at TEXT
+            );
+            CREATE UNIQUE INDEX IF NOT EXISTS idx_sync_conflicts_open
+                ON sync_conflicts(table_name, record_id) WHERE resolution IS NULL;
+        ",
+    },
+    Migration {
+        version: 6,
+        description: "github_connections 토큰 참조화",
+        sql: "
+            -- 기존 행은 move_plaintext_credentials()가 저장소로 옮긴 뒤 삭제
+            ALTER TABLE github_connections RENAME TO github_connections_legacy;
+
+            CREATE TABLE github_connections (
+                id TEXT PRIMARY KEY,
+                github_username TEXT,
+                access_token_ref TEXT NOT NULL,
+                refresh_token_ref TEXT,
+                token_expires_at TEXT,
+                connected_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+        ",
+    },
+    Migration {
+        version: 7,
+        description: "작업 수명주기 + 계층 구조 (Supabase 011/013)",
+        sql: "
+            -- 수명주기: active 최초 전환 / done 전환 / soft delete
+            ALTER TABLE work_items ADD COLUMN goal_id TEXT;
+            ALTER TABLE work_items ADD COLUMN started_at TEXT;
+            ALTER TABLE work_items ADD COLUMN completed_at TEXT;
+            ALTER TABLE work_items ADD COLUMN deleted_at TEXT;
+            ALTER TABLE work_items ADD COLUMN actual_min INTEGER;
+            CREATE INDEX IF NOT EXISTS idx_work_items_deleted_at ON work_items(deleted_at);
+            CREATE INDEX IF NOT EXISTS idx_work_items_goal_id ON work_items(goal_id);
+
+            -- 목표 상위(project/plan) + 우선순위 1~5
+            ALTER TABLE goals ADD COLUMN project_id TEXT;
+            ALTER TABLE goals ADD COLUMN priority INTEGER NOT NULL DEFAULT 3
+                CHECK (priority BETWEEN 1 AND 5);
+            ALTER TABLE goals ADD COLUMN description TEXT;
+            ALTER TABLE goals ADD COLUMN due_at TEXT;
+            CREATE INDEX IF NOT EXISTS idx_goals_project_id ON goals(project_id);
+            CREATE INDEX IF NOT EXISTS idx_goals_plan_id ON goals(plan_id);
+
+            -- goals_parent_xor: ALTER로 CHECK를 걸 수 없어 트리거로
+            -- (상위 컬럼을 바꿀 때만 검사 → 상위 없는 기존 행도 다른 필드 수정 가능)
+            CREATE TRIGGER IF NOT EXISTS goals_parent_xor_insert
+            BEFORE INSERT ON goals
+            WHEN (NEW.project_id IS NULL) = (NEW.plan_id IS NULL)
+            BEGIN
+                SELECT RAISE(ABORT, 'goals_parent_xor: project_id / plan_id 중 정확히 하나 필요');
+            END;
+            CREATE TRIGGER IF NOT EXISTS goals_parent_xor_update
+            BEFORE UPDATE OF project_id, plan_id ON goals
+            WHEN (NEW.project_id IS NULL) = (NEW.plan_id IS NULL)
+            BEGIN
+                SELECT RAISE(ABORT, 'goals_parent_xor: project_id / plan_id 중 정확히 하나 필요');
+            END;
+        ",
+    },
+    Migration {
+        version: 8,
+        description: "event_outbox 기록 사용자",
+        sql: "
+            -- 기록 시점 로그인 사용자 (다른 사용자로 전송되지 않도록, NULL = 로그아웃 중 기록)
+            ALTER TABLE event_outbox ADD COLUMN user_id TEXT;
+            CREATE INDEX IF NOT EXISTS idx_event_outbox_user
+                ON event_outbox(user_id, status, next_attempt_at);
+        ",
+    },
+    Migration {
+        version: 9,
+        description: "동기화 테이블 Supabase 스키마 정렬",
+        sql: "
+            -- 기본값/CHECK 변경은 ALTER 불가 → 새 테이블로 옮긴 뒤 교체
+            -- 원격에 없는 상태값(이전 기본값 'open' 등)은 backlog로
+            -- 원격 컬럼이 없는 로컬 값(work_items priority/description/metadata,
+            -- goals progress/metadata)은 버림 (마이그레이션 전 백업에 남음)
+
+            -- work_items: Supabase 001/006/011/013
+            CREATE TABLE work_items_v9 (
+                id TEXT PRIMARY KEY,
+                project_id TEXT,
+                title TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'backlog'
+                    CHECK (status IN ('backlog', 'candidate', 'active', 'done', 'blocked', 'deferred')),
+                next_action TEXT,
+                estimate_min INTEGER,
+                energy TEXT CHECK (energy IN ('high', 'medium', 'low')),
+                due_at TEXT,
+                source_app TEXT,
+                source_ref TEXT,
+                goal_id TEXT,
+                started_at TEXT,
+                completed_at TEXT,
+                deleted_at TEXT,
+                actual_min INTEGER,
+                sync_status TEXT NOT NULL DEFAULT 'pending',
+                remote_updated_at TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            INSERT INTO work_items_v9
+                (id, project_id, title, status, due_at, goal_id, started_at, completed_at,
+                 deleted_at, actual_min, sync_status, remote_updated_at, created_at, updated_at)
+            SELECT id, project_id, title,
+                   CASE WHEN status IN ('backlog', 'candidate', 'active', 'done', 'blocked', 'deferred')
+                        THEN status ELSE 'backlog' END,
+                   due_at, goal_id, started_at, completed_at,
+                   deleted_at, actual_min, sync_status, remote_updated_at, created_at, updated_at
+            FROM work_items;
+            DROP TABLE work_items;
+            ALTER TABLE work_items_v9 RENAME TO work_items;
+            CREATE INDEX idx_work_items_deleted_at ON work_items(deleted_at);
+            CREATE INDEX idx_work_items_goal_id ON work_items(goal_id);
+
+            -- plans: Supabase 001_create_plans (start_at은 원격처럼 metadata로)
+            CREATE TABLE plans_v9 (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                plan_type TEXT NOT NULL DEFAULT 'task'
+                    CHECK (plan_type IN ('task', 'event', 'fixed', 'project')),
+                status TEXT NOT NULL DEFAULT 'backlog'
+                    CHECK (status IN ('backlog', 'candidate', 'active', 'done', 'blocked', 'deferred')),
+                priority TEXT CHECK (priority IN ('low', 'medium', 'high', 'critical')),
+                description TEXT,
+                due_at TEXT,
+                metadata TEXT NOT NULL DEFAULT '{}',
+                sync_status TEXT NOT NULL DEFAULT 'pending',
+                remote_updated_at TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            INSERT INTO plans_v9
+                (id, title, plan_type, status, priority, description, due_at, metadata,
+                 sync_status, remote_updated_at, created_at, updated_at)
+            SELECT id, title, plan_type,
+                   CASE WHEN status IN ('backlog', 'candidate', 'active', 'done', 'blocked', 'deferred')
+                        THEN status ELSE 'backlog' END,
+                   priority, description, due_at,
+                   CASE WHEN start_at IS NULL THEN metadata
+                        ELSE json_set(metadata, '$.start_at', start_at) END,
+                   sync_status, remote_updated_at, created_at, updated_at
+            FROM plans;
+            DROP TABLE plans;
+            ALTER TABLE plans_v9 RENAME TO plans;
+
+            -- goals: Supabase 013
+            CREATE TABLE goals_v9 (
+                id TEXT PRIMARY KEY,
+                project_id TEXT,
+                plan_id TEXT,
+                title TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'backlog'
+                    CHECK (status IN ('backlog', 'active', 'done', 'deferred')),
+                priority INTEGER NOT NULL DEFAULT 3 CHECK (priority BETWEEN 1 AND 5),
+                description TEXT,
+                due_at TEXT,
+                sync_status TEXT NOT NULL DEFAULT 'pending',
+                remote_updated_at TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            INSERT INTO goals_v9
+                (id, project_id, plan_id, title, status, priority, description, due_at,
+                 sync_status, remote_updated_at, created_at, updated_at)
+            SELECT id, project_id, plan_id, title,
+                   CASE WHEN status IN ('backlog', 'active', 'done', 'deferred')
+                        THEN status ELSE 'backlog' END,
+                   priority, description, due_at,
+                   sync_status, remote_updated_at, created_at, updated_at
+            FROM goals;
+            DROP TABLE goals;
+            ALTER TABLE goals_v9 RENAME TO goals;
+            CREATE INDEX idx_goals_project_id ON goals(project_id);
+            CREATE INDEX idx_goals_plan_id ON goals(plan_id);
+            CREATE TRIGGER goals_parent_xor_insert
+            BEFORE INSERT ON goals
+            WHEN (NEW.project_id IS NULL) = (NEW.plan_id IS NULL)
+            BEGIN
+                SELECT RAISE(ABORT, 'goals_parent_xor: project_id / plan_id 중 정확히 하나 필요');
+            END;
+            CREATE TRIGGER goals_parent_xor_update
+            BEFORE UPDATE OF project_id, plan_id ON goals
+            WHEN (NEW.project_id IS NULL) = (NEW.plan_id IS NULL)
+            BEGIN
+                SELECT RAISE(ABORT, 'goals_parent_xor: project_id / plan_id 중 정확히 하나 필요');
+            END;
+
+            -- session_logs: Supabase 001 컬럼 추가 (에이전트 세션 기록 컬럼은 로컬 전용으로 유지)
+            ALTER TABLE session_logs ADD COLUMN work_item_id TEXT;
+            ALTER TABLE session_logs ADD COLUMN result TEXT
+                CHECK (result IN ('done', 'partial', 'blocked'));
+            ALTER TABLE session_logs ADD COLUMN done_log TEXT;
+        ",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db(name: &str) -> (PathBuf, Connection) {
+        let path = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_file(&path);
+        for v in 1..=latest_version() {
+            let _ = std::fs::remove_file(backup_path(&path.to_string_lossy(), v));
+        }
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch("PRAGMA journal_mode=WAL;").unwrap();
+        (path, conn)
+    }
+
+    fn versions(conn: &Connection) -> Vec<(u32, Option<String>)> {
+        let mut stmt = conn
+            .prepare("SELECT version, checksum FROM schema_version ORDER BY version")
+            .unwrap();
+        let rows = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?))).unwrap();
+        rows.collect::<rusqlite::Result<_>>().unwrap()
+    }
+
+    #[test]
+    fn test_registry_is_ordered() {
+        for (i, m) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(m.version as usize, i + 1);
+        }
+        assert_eq!(
+            checksum("CREATE TABLE a (\n  id TEXT\n);"),
+            checksum("    CREATE TABLE a (\n        id TEXT\n    );\n\n")
+        );
+    }
+
+    #[test]
+    fn test_fresh_db_and_upgrade_with_backup() {
+        let (path, mut conn) = temp_db("orchestrator_migrations_test.db");
+
+        // v3까지 적용된 이전 버전 앱의 DB
+        run_with(&mut conn, &MIGRATIONS[..3]).unwrap();
+        assert_eq!(versions(&conn).len(), 3);
+        conn.execute(
+            "INSERT INTO projects (id, repo_id, repo_name, repo_full_name, repo_url)
+             VALUES ('p1', 1, 'a', 'me/a', 'https://github.com/me/a')",
+            [],
+        )
+        .unwrap();
+        // 이전 기본값('open', priority 'medium') 행
+        conn.execute_batch(
+            "INSERT INTO work_items (id, title, description) VALUES ('w1', 'W', 'd');
+             INSERT INTO plans (id, title, start_at) VALUES ('pl1', 'P', '2026-03-01');
+             INSERT INTO goals (id, title) VALUES ('g1', 'G');",
+        )
+        .unwrap();
+
+        run(&mut conn).unwrap();
+        let applied = versions(&conn);
+        assert_eq!(applied.len(), MIGRATIONS.len());
+        assert!(applied
+            .iter()
+            .zip(MIGRATIONS)
+            .all(|((v, c), m)| *v == m.version && c.as_deref() == Some(checksum(m.sql).as_str())));
+
+        // 업그레이드 전 사본 (v3 상태 + 데이터)
+        let backup = backup_path(&path.to_string_lossy(), 3);
+        let copy = Connection::open(&backup).unwrap();
+        assert_eq!(versions(&copy).len(), 3);
+        let count: i64 = copy
+            .query_row("SELECT COUNT(*) FROM projects", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+        drop(copy);
+
+        // v9: 원격 상태값/컬럼으로 정렬
+        let status = |table: &str| -> String {
+            conn.query_row(&format!("SELECT status FROM {}", table), [], |r| r.get(0))
+                .unwrap()
+        };
+        assert_eq!(status("work_items"), "backlog");
+        assert_eq!(status("plans"), "backlog");
+        assert_eq!(status("goals"), "backlog");
+        let start_at: String = conn
+            .query_row(
+                "SELECT json_extract(metadata, '$.start_at') FROM plans",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(start_at, "2026-03-01");
+        assert!(conn
+            .execute("UPDATE work_items SET status = 'open'", [])
+            .is_err());
+        assert!(conn
+            .execute("INSERT INTO goals (id, title) VALUES ('g2', 'G')", [])
+            .is_err());
+
+        // 재실행은 no-op
+        run(&mut conn).unwrap();
+        assert_eq!(versions(&conn).len(), MIGRATIONS.len());
+
+        // v3 백업: github_connections.access_token 평문 컬럼
+        remove_plaintext_backups(&conn);
+        assert!(!backup.exists());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_refuses_newer_or_modified_schema() {
+        let (path, mut conn) = temp_db("orchestrator_migrations_refuse_test.db");
+        run(&mut conn).unwrap();
+
+        conn.execute(
+            "UPDATE schema_version SET checksum = 'edited' WHERE version = 2",
+            [],
+        )
+        .unwrap();
+        let err = run(&mut conn).unwrap_err().to_string();
+        assert!(err.contains("v2"), "{}", err);
+
+        conn.execute(
+            "UPDATE schema_version SET checksum = ?1 WHERE version = 2",
+            params![checksum(MIGRATIONS[1].sql)],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO schema_version (version) VALUES (?1)",
+            params![latest_version() + 1],
+        )
+        .unwrap();
+        let err = run(&mut conn).unwrap_err().to_string();
+        assert!(err.contains("앱 업데이트 필요"), "{}", err);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_backfills_checksums_of_pre_registry_db() {
+        let (path, mut conn) = temp_db("orchestrator_migrations_legacy_test.db");
+        run(&mut conn).unwrap();
+
+        // 체크섬 컬럼이 없던 시절의 schema_version
+        conn.execute_batch(
+            "DROP TABLE schema_version;
+             CREATE TABLE schema_version (
+                 version INTEGER PRIMARY KEY,
+                 applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+             );",
+        )
+        .unwrap();
+        for m in MIGRATIONS {
+            conn.execute(
+                "INSERT INTO schema_version (version) VALUES (?1)",
+                params![m.version],
+            )
+            .unwrap();
+        }
+
+        run(&mut conn).unwrap();
+        assert!(versions(&conn).iter().all(|(_, c)| c.is_some()));
+        let _ = std::fs::remove_file(&path);
+    }
+}
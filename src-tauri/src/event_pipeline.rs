@@ -0,0 +1,377 @@
+// ===========================================
+// event_pipeline.rs — file.changed 이벤트 배치 전송
+// (감시 키, 경로)별 병합(coalesce) 큐 → 주기적 bulk insert (/rest/v1/cli_events)
+// 서버가 느리거나 실패하면 flush 간격을 늘려 backpressure
+// ===========================================
+
+use crate::git_discovery::split_watch_key;
+use crate::session::Severity;
+use crate::sync_client::SyncClient;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 큐에 보관할 최대 경로 수 (초과분은 개수만 집계)
+const MAX_PENDING_PATHS: usize = 2000;
+/// bulk insert 1회 최대 행 수
+const MAX_BATCH_ROWS: usize = 200;
+/// 기본 flush 간격
+const BASE_FLUSH_MS: u64 = 2000;
+/// backpressure 시 최대 flush 간격
+const MAX_FLUSH_MS: u64 = 30_000;
+/// 이 시간보다 오래 걸린 전송은 느린 것으로 판단
+const SLOW_SEND_MS: u64 = 3000;
+
+/// 병합 전 파일 변경 한 건
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    /// 감시 키 ("owner/repo" 또는 "owner/repo@worktree") — 레포마다 같은 상대 경로가 있을 수 있음
+    pub watch_key: String,
+    pub file: String,
+    pub event_type: String,
+    pub violation: Option<String>,
+    pub severity: Option<Severity>,
+    /// 변경이 귀속된 세션 (멀티 세션 작업 트리, 큐에 넣을 때 기준)
+    pub session_id: Option<String>,
+}
+
+/// 전송 대기 이벤트 (session_id는 flush 시점이 아닌 큐에 넣을 때 귀속된 세션)
+#[derive(Debug, Clone)]
+pub struct QueuedEvent {
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub session_id: Option<String>,
+}
+
+impl QueuedEvent {
+    /// 감시 키의 레포/worktree를 payload에 넣은 단건 이벤트 (watcher의 명령/커밋/예산 이벤트)
+    pub fn for_watch(
+        watch_key: &str,
+        event_type: &str,
+        mut payload: serde_json::Value,
+        session_id: Option<String>,
+    ) -> Self {
+        let (repo_full_name, worktree) = split_watch_key(watch_key);
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert("repo_full_name".to_string(), repo_full_name.into());
+            obj.insert("worktree".to_string(), worktree.into());
+        }
+        Self {
+            event_type: event_type.to_string(),
+            payload,
+            session_id,
+        }
+    }
+}
+
+/// 경로별 병합 결과
+#[derive(Debug, Clone)]
+struct PendingChange {
+    change: FileChange,
+    count: u64,
+    first_at: String,
+    last_at: String,
+}
+
+/// 병합 키: (감시 키, 상대 경로)
+type PathKey = (String, String);
+
+/// (감시 키, 경로)별 병합 큐 (삽입 순서 유지)
+pub struct CoalescingQueue {
+    order: Vec<PathKey>,
+    by_path: HashMap<PathKey, PendingChange>,
+    capacity: usize,
+    /// 용량 초과로 버려진 변경 수
+    dropped: u64,
+}
+
+impl CoalescingQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            order: Vec::new(),
+            by_path: HashMap::new(),
+            capacity,
+            dropped: 0,
+        }
+    }
+
+    /// 변경 추가 — 같은 레포의 같은 경로는 한 건으로 병합
+    pub fn push(&mut self, change: FileChange) {
+        let now = chrono::Utc::now().to_rfc3339();
+        let key = (change.watch_key.clone(), change.file.clone());
+
+        if let Some(pending) = self.by_path.get_mut(&key) {
+            pending.count += 1;
+            pending.last_at = now;
+            // add → change 는 add 유지, 그 외는 마지막 종류
+            if !(pending.change.event_type == "add" && change.event_type == "change") {
+                pending.change.event_type = change.event_type;
+            }
+            // 더 심각한 위반 유지
+            if change.severity > pending.change.severity {
+                pending.change.severity = change.severity;
+                pending.change.violation = change.violation;
+            }
+            // 귀속 세션은 마지막 변경 기준
+            if change.session_id.is_some() {
+                pending.change.session_id = change.session_id;
+            }
+            return;
+        }
+
+        if self.by_path.len() >= self.capacity {
+            self.dropped += 1;
+            return;
+        }
+
+        self.order.push(key.clone());
+        self.by_path.insert(
+            key,
+            PendingChange {
+                change,
+                count: 1,
+                first_at: now.clone(),
+                last_at: now,
+            },
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty() && self.dropped == 0
+    }
+
+    /// 큐 비우기 → (cli_events 행 목록, 버려진 변경 수)
+    pub fn drain(&mut self) -> (Vec<QueuedEvent>, u64) {
+        let mut rows = Vec::with_capacity(self.order.len());
+        for key in self.order.drain(..) {
+            if let Some(p) = self.by_path.remove(&key) {
+                let (repo_full_name, worktree) = split_watch_key(&p.change.watch_key);
+                rows.push(QueuedEvent {
+                    event_type: "file.changed".to_string(),
+                    payload: serde_json::json!({
+                        "repo_full_name": repo_full_name,
+                        "worktree": worktree,
+                        "file": p.change.file,
+                        "event_type": p.change.event_type,
+                        "violation": p.change.violation,
+                        "severity": p.change.severity,
+                        "session_id": p.change.session_id,
+                        "count": p.count,
+                        "first_at": p.first_at,
+                        "last_at": p.last_at,
+                    }),
+                    session_id: p.change.session_id,
+                });
+            }
+        }
+        let dropped = std::mem::take(&mut self.dropped);
+        (rows, dropped)
+    }
+}
+
+/// 전송 결과에 따른 flush 간격 조절
+pub struct FlushPacer {
+    interval: Duration,
+}
+
+impl Default for FlushPacer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlushPacer {
+    pub fn new() -> Self {
+        Self {
+            interval: Duration::from_millis(BASE_FLUSH_MS),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// 실패/지연 → 간격 2배, 정상 → 절반 (기본값까지)
+    pub fn record(&mut self, ok: bool, elapsed: Duration) {
+        if !ok || elapsed >= Duration::from_millis(SLOW_SEND_MS) {
+            self.interval = (self.interval * 2).min(Duration::from_millis(MAX_FLUSH_MS));
+        } else {
+            self.interval = (self.interval / 2).max(Duration::from_millis(BASE_FLUSH_MS));
+        }
+    }
+}
+
+/// SyncClient별 file.changed 파이프라인
+pub struct EventPipeline {
+    queue: Arc<Mutex<CoalescingQueue>>,
+    started: AtomicBool,
+}
+
+impl Default for EventPipeline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventPipeline {
+    pub fn new() -> Self {
+        Self {
+            queue: Arc::new(Mutex::new(CoalescingQueue::new(MAX_PENDING_PATHS))),
+            started: AtomicBool::new(false),
+        }
+    }
+
+    /// 큐에 추가 (첫 호출 시 flush 태스크 시작)
+    pub fn push(&self, client: &Arc<SyncClient>, change: FileChange) {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.push(change);
+        }
+        if !self.started.swap(true, Ordering::SeqCst) {
+            spawn_flusher(client.clone(), self.queue.clone());
+        }
+    }
+}
+
+/// 주기적으로 큐를 비워 bulk 전송 (한 번에 한 요청만 진행)
+fn spawn_flusher(client: Arc<SyncClient>, queue: Arc<Mutex<CoalescingQueue>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut pacer = FlushPacer::new();
+        loop {
+            tokio::time::sleep(pacer.interval()).await;
+
+            let (mut rows, dropped) = match queue.lock() {
+                Ok(mut q) if !q.is_empty() => q.drain(),
+                _ => continue,
+            };
+            if dropped > 0 {
+                log::warn!("⚠ 이벤트 큐 포화 → file.changed {}건 누락", dropped);
+                rows.push(QueuedEvent {
+                    event_type: "file.overflow".to_string(),
+                    payload: serde_json::json!({ "dropped": dropped }),
+                    session_id: None,
+                });
+            }
+
+            for chunk in rows.chunks(MAX_BATCH_ROWS) {
+                let started = Instant::now();
+                let result = client.send_events(chunk.to_vec()).await;
+                pacer.record(result.is_ok(), started.elapsed());
+                if let Err(e) = result {
+                    // 남은 chunk도 간격을 두고 전송
+                    log::warn!("  ❌ Supabase 배치 전송 실패: {}", e);
+                    tokio::time::sleep(pacer.interval()).await;
+                }
+            }
+
+            if pacer.interval() > Duration::from_millis(BASE_FLUSH_MS) {
+                log::info!("🐢 이벤트 flush 간격 {}ms", pacer.interval().as_millis());
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(file: &str, event_type: &str, severity: Option<Severity>) -> FileChange {
+        FileChange {
+            watch_key: "org/app".to_string(),
+            file: file.to_string(),
+            event_type: event_type.to_string(),
+            violation: severity.map(|s| format!("{:?}", s)),
+            severity,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn test_coalesces_per_path_and_bounds_queue() {
+        let mut queue = CoalescingQueue::new(2);
+        queue.push(change("a.ts", "add", None));
+        queue.push(change("a.ts", "change", Some(Severity::Warn)));
+        queue.push(change("a.ts", "change", Some(Severity::Info)));
+        queue.push(change("b.ts", "change", None));
+        queue.push(change("c.ts", "change", None));
+        queue.push(change("b.ts", "unlink", None));
+
+        let (rows, dropped) = queue.drain();
+        assert_eq!(dropped, 1);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].payload["file"], "a.ts");
+        assert_eq!(rows[0].payload["repo_full_name"], "org/app");
+        assert_eq!(rows[0].payload["event_type"], "add");
+        assert_eq!(rows[0].payload["count"], 3);
+        assert_eq!(rows[0].payload["severity"], "warn");
+        assert_eq!(rows[1].payload["event_type"], "unlink");
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_same_path_in_other_repo_is_kept_with_its_session() {
+        let mut queue = CoalescingQueue::new(10);
+        let in_repo = |key: &str, session: &str| FileChange {
+            watch_key: key.to_string(),
+            session_id: Some(session.to_string()),
+            ..change("src/main.rs", "change", None)
+        };
+        queue.push(in_repo("org/app", "s1"));
+        queue.push(in_repo("org/lib", "s2"));
+        queue.push(in_repo("org/app@feature", "s3"));
+
+        let (rows, _) = queue.drain();
+        assert_eq!(rows.len(), 3);
+        let owners: Vec<_> = rows
+            .iter()
+            .map(|r| (r.payload["repo_full_name"].clone(), r.session_id.as_deref()))
+            .collect();
+        assert_eq!(
+            owners,
+            [
+                (serde_json::json!("org/app"), Some("s1")),
+                (serde_json::json!("org/lib"), Some("s2")),
+                (serde_json::json!("org/app"), Some("s3")),
+            ]
+        );
+        assert_eq!(rows[2].payload["worktree"], "feature");
+    }
+
+    #[test]
+    fn test_single_event_carries_watch_repo_and_session() {
+        let event = QueuedEvent::for_watch(
+            "org/app@feature",
+            "commit.created",
+            serde_json::json!({ "sha": "abc" }),
+            Some("s1".to_string()),
+        );
+        assert_eq!(event.payload["repo_full_name"], "org/app");
+        assert_eq!(event.payload["worktree"], "feature");
+        assert_eq!(event.payload["sha"], "abc");
+        assert_eq!(event.session_id.as_deref(), Some("s1"));
+
+        let main =
+            QueuedEvent::for_watch("org/app", "command.executed", serde_json::json!({}), None);
+        assert_eq!(main.payload["repo_full_name"], "org/app");
+        assert!(main.payload["worktree"].is_null());
+        assert!(main.session_id.is_none());
+    }
+
+    #[test]
+    fn test_pacer_backs_off_and_recovers() {
+        let mut pacer = FlushPacer::new();
+        let fast = Duration::from_millis(100);
+        pacer.record(false, fast);
+        assert_eq!(pacer.interval(), Duration::from_millis(BASE_FLUSH_MS * 2));
+        pacer.record(true, Duration::from_millis(SLOW_SEND_MS));
+        assert_eq!(pacer.interval(), Duration::from_millis(BASE_FLUSH_MS * 4));
+        for _ in 0..10 {
+            pacer.record(false, fast);
+        }
+        assert_eq!(pacer.interval(), Duration::from_millis(MAX_FLUSH_MS));
+        for _ in 0..10 {
+            pacer.record(true, fast);
+        }
+        assert_eq!(pacer.interval(), Duration::from_millis(BASE_FLUSH_MS));
+    }
+}
@@ -0,0 +1,344 @@
+// ===========================================
+// git_discovery.rs — git 저장소 탐색 (resolve_local_paths / watcher / SyncClient 공용)
+// .git 디렉토리 또는 `gitdir:` 파일(linked worktree, submodule)을 따라
+// 작업 트리별 git 디렉토리 + 공유(common) 디렉토리 → origin → repo_full_name
+// ===========================================
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// 감시 키 구분자 — linked worktree는 "owner/repo@worktree" (GitHub 저장소 이름에 '@' 불가)
+const WORKTREE_KEY_SEPARATOR: char = '@';
+
+/// 작업 트리 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepoKind {
+    /// 일반 저장소 (.git 디렉토리)
+    Main,
+    /// `git worktree add` 작업 트리 (.git 파일 → <common>/worktrees/<name>)
+    Worktree,
+    /// 서브모듈 (.git 파일 → <superproject>/.git/modules/<name>)
+    Submodule,
+}
+
+/// 작업 트리 하나의 git 위치 정보
+#[derive(Debug, Clone, PartialEq)]
+pub struct GitRepo {
+    pub work_tree: PathBuf,
+    /// 작업 트리 전용 git 디렉토리 (HEAD, index)
+    pub git_dir: PathBuf,
+    /// 공유 git 디렉토리 (config, refs, objects — worktree면 메인 저장소 .git)
+    pub common_dir: PathBuf,
+    pub kind: RepoKind,
+}
+
+impl GitRepo {
+    /// 작업 트리 루트에서 탐색 (.git 디렉토리/파일 없으면 None)
+    pub fn discover(work_tree: &Path) -> Option<Self> {
+        let dot_git = work_tree.join(".git");
+        if dot_git.is_dir() {
+            return Some(GitRepo {
+                work_tree: work_tree.to_path_buf(),
+                git_dir: dot_git.clone(),
+                common_dir: dot_git,
+                kind: RepoKind::Main,
+            });
+        }
+
+        // `gitdir: <경로>` — 상대 경로는 .git 파일 위치 기준
+        let content = fs::read_to_string(&dot_git).ok()?;
+        let target = content
+            .lines()
+            .find_map(|l| l.strip_prefix("gitdir:"))?
+            .trim();
+        let git_dir = normalize(&work_tree.join(target));
+        if !git_dir.is_dir() {
+            return None;
+        }
+
+        // worktree: git 디렉토리의 commondir 파일이 공유 디렉토리를 가리킴
+        let (common_dir, kind) = match fs::read_to_string(git_dir.join("commondir")) {
+            Ok(common) => (normalize(&git_dir.join(common.trim())), RepoKind::Worktree),
+            Err(_) if superproject_of(&git_dir).is_some() => (git_dir.clone(), RepoKind::Submodule),
+            // --separate-git-dir 저장소
+            Err(_) => (git_dir.clone(), RepoKind::Main),
+        };
+        Some(GitRepo {
+            work_tree: work_tree.to_path_buf(),
+            git_dir,
+            common_dir,
+            kind,
+        })
+    }
+
+    /// linked worktree 이름 (<common>/worktrees/<name>)
+    pub fn worktree_name(&self) -> Option<&str> {
+        match self.kind {
+            RepoKind::Worktree => self.git_dir.file_name()?.to_str(),
+            _ => None,
+        }
+    }
+
+    /// 서브모듈이면 최상위 상위 저장소 작업 트리
+    pub fn superproject(&self) -> Option<PathBuf> {
+        match self.kind {
+            RepoKind::Submodule => superproject_of(&self.git_dir),
+            _ => None,
+        }
+    }
+
+    /// 프로젝트로 매칭할 저장소 — 서브모듈은 최상위 상위 저장소, 그 외는 자기 자신
+    /// (서브모듈 변경은 상위 저장소 watcher가 감시 → 상위 repo_full_name에 귀속)
+    pub fn owning_repo(&self) -> Option<GitRepo> {
+        match self.superproject() {
+            Some(root) => GitRepo::discover(&root),
+            None => Some(self.clone()),
+        }
+    }
+
+    /// 이 저장소에 연결된 linked worktree 목록 (<common>/worktrees/*/gitdir, 삭제된 작업 트리 제외)
+    pub fn linked_worktrees(&self) -> Vec<GitRepo> {
+        let entries = match fs::read_dir(self.common_dir.join("worktrees")) {
+            Ok(e) => e,
+            Err(_) => return Vec::new(),
+        };
+        let mut worktrees: Vec<GitRepo> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| fs::read_to_string(e.path().join("gitdir")).ok())
+            .filter_map(|dot_git| Path::new(dot_git.trim()).parent().map(Path::to_path_buf))
+            .filter_map(|work_tree| GitRepo::discover(&work_tree))
+            .filter(|repo| repo.kind == RepoKind::Worktree && repo.common_dir == self.common_dir)
+            .collect();
+        worktrees.sort_by(|a, b| a.work_tree.cmp(&b.work_tree));
+        worktrees
+    }
+
+    /// origin remote URL (공유 디렉토리 config — worktree는 메인 저장소 설정)
+    pub fn origin_url(&self) -> Option<String> {
+        let content = fs::read_to_string(self.common_dir.join("config")).ok()?;
+        content
+            .lines()
+            .skip_while(|l| l.trim() != "[remote \"origin\"]")
+            .skip(1)
+            .take_while(|l| !l.trim_start().starts_with('['))
+            .filter_map(|l| l.split_once('='))
+            .find(|(key, _)| key.trim() == "url")
+            .map(|(_, url)| url.trim().to_string())
+    }
+
+    /// 감시 키 — linked worktree는 작업 트리별로 따로 감시
+    pub fn watch_key(&self, repo_full_name: &str) -> String {
+        watch_key(repo_full_name, self.worktree_name())
+    }
+
+    /// 작업 트리 밖에 있어 따로 감시해야 하는 git 경로 (경로, 재귀 여부)
+    /// HEAD(작업 트리별) + packed-refs/refs/heads(공유) — 일반 저장소는 작업 트리 안이라 없음
+    pub fn external_ref_watches(&self) -> Vec<(PathBuf, bool)> {
+        if self.git_dir.starts_with(&self.work_tree) {
+            return Vec::new();
+        }
+        let mut watches = vec![(self.git_dir.clone(), false)];
+        if self.common_dir != self.git_dir {
+            watches.push((self.common_dir.clone(), false));
+        }
+        watches.push((self.common_dir.join("refs").join("heads"), true));
+        watches
+    }
+
+    /// 감지된 경로 → git 디렉토리 기준 상대 경로 (HEAD, packed-refs, refs/heads/**)
+    /// 다른 worktree의 HEAD(<common>/worktrees/...)는 None
+    pub fn git_relative<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        if let Ok(relative) = path.strip_prefix(&self.git_dir) {
+            return Some(relative);
+        }
+        path.strip_prefix(&self.common_dir)
+            .ok()
+            .filter(|relative| !relative.starts_with("worktrees"))
+    }
+}
+
+/// 감시 키 생성 ("owner/repo" 또는 "owner/repo@worktree")
+pub fn watch_key(repo_full_name: &str, worktree: Option<&str>) -> String {
+    match worktree {
+        Some(name) => format!("{}{}{}", repo_full_name, WORKTREE_KEY_SEPARATOR, name),
+        None => repo_full_name.to_string(),
+    }
+}
+
+/// 감시 키 → (repo_full_name, worktree 이름)
+pub fn split_watch_key(key: &str) -> (&str, Option<&str>) {
+    match key.split_once(WORKTREE_KEY_SEPARATOR) {
+        Some((repo, worktree)) => (repo, Some(worktree)),
+        None => (key, None),
+    }
+}
+
+/// remote URL → owner/repo
+/// SSH: git@github.com:owner/repo.git, HTTPS: https://github.com/owner/repo.git
+pub fn parse_repo_full_name(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/').trim_end_matches(".git");
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => url.split_once(':')?.1,
+    };
+    let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+    if parts.len() < 2 {
+        return None;
+    }
+    Some(format!(
+        "{}/{}",
+        parts[parts.len() - 2],
+        parts[parts.len() - 1]
+    ))
+}
+
+/// <super>/.git/modules/... → <super> (중첩 서브모듈은 최상위)
+fn superproject_of(git_dir: &Path) -> Option<PathBuf> {
+    git_dir
+        .ancestors()
+        .filter(|a| a.file_name().map(|n| n == "modules").unwrap_or(false))
+        .filter_map(|modules| modules.parent())
+        .filter(|dot_git| dot_git.file_name().map(|n| n == ".git").unwrap_or(false))
+        .last()
+        .and_then(|dot_git| dot_git.parent().map(Path::to_path_buf))
+}
+
+/// `..`/`.` 정리 (심볼릭 링크는 풀지 않음 — watcher 이벤트 경로와 비교하기 위함)
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_discovers_worktrees_and_submodules() {
+        let root = std::env::temp_dir().join("orchestrator_git_discovery_test");
+        let _ = fs::remove_dir_all(&root);
+
+        // 메인 저장소 + linked worktree (git worktree add ../feature)
+        let main = root.join("app");
+        write(
+            &main.join(".git/config"),
+            "[core]\n\tbare = false\n[remote \"origin\"]\n\tfetch = +refs/heads/*:refs/remotes/origin/*\n\turl = git@github.com:acme/App.git\n",
+        );
+        fs::create_dir_all(main.join(".git/refs/heads")).unwrap();
+        let feature = root.join("app-feature");
+        write(
+            &feature.join(".git"),
+            &format!(
+                "gitdir: {}\n",
+                main.join(".git/worktrees/feature").display()
+            ),
+        );
+        write(&main.join(".git/worktrees/feature/commondir"), "../..\n");
+        write(
+            &main.join(".git/worktrees/feature/gitdir"),
+            &format!("{}\n", feature.join(".git").display()),
+        );
+        // 삭제된 작업 트리 (prunable)
+        write(
+            &main.join(".git/worktrees/gone/gitdir"),
+            "/nonexistent/.git\n",
+        );
+
+        // 서브모듈 (상대 gitdir)
+        let lib = main.join("vendor/lib");
+        write(&lib.join(".git"), "gitdir: ../../.git/modules/vendor/lib\n");
+        write(
+            &main.join(".git/modules/vendor/lib/config"),
+            "[remote \"origin\"]\n\turl = https://github.com/acme/lib.git\n",
+        );
+
+        let repo = GitRepo::discover(&main).unwrap();
+        assert_eq!(repo.kind, RepoKind::Main);
+        assert_eq!(
+            repo.origin_url().as_deref(),
+            Some("git@github.com:acme/App.git")
+        );
+        assert_eq!(repo.watch_key("acme/App"), "acme/App");
+        assert!(repo.external_ref_watches().is_empty());
+
+        let worktrees = repo.linked_worktrees();
+        assert_eq!(worktrees.len(), 1);
+        let wt = &worktrees[0];
+        assert_eq!(wt.kind, RepoKind::Worktree);
+        assert_eq!(wt.work_tree, feature);
+        assert_eq!(wt.common_dir, main.join(".git"));
+        // worktree에는 .git/config가 없음 → 공유 디렉토리 설정
+        assert_eq!(wt.origin_url(), repo.origin_url());
+        assert_eq!(wt.watch_key("acme/App"), "acme/App@feature");
+        assert_eq!(
+            split_watch_key("acme/App@feature"),
+            ("acme/App", Some("feature"))
+        );
+        assert_eq!(split_watch_key("acme/App"), ("acme/App", None));
+
+        // 커밋 감지: HEAD는 작업 트리별, refs는 공유, 다른 worktree HEAD는 무시
+        assert_eq!(wt.external_ref_watches().len(), 3);
+        let head = main.join(".git/worktrees/feature/HEAD");
+        assert_eq!(wt.git_relative(&head), Some(Path::new("HEAD")));
+        let branch = main.join(".git/refs/heads/feature");
+        assert_eq!(
+            wt.git_relative(&branch),
+            Some(Path::new("refs/heads/feature"))
+        );
+        let other = main.join(".git/worktrees/other/HEAD");
+        assert_eq!(wt.git_relative(&other), None);
+
+        let sub = GitRepo::discover(&lib).unwrap();
+        assert_eq!(sub.kind, RepoKind::Submodule);
+        assert_eq!(sub.git_dir, main.join(".git/modules/vendor/lib"));
+        assert_eq!(sub.superproject(), Some(main.clone()));
+        assert_eq!(
+            sub.origin_url().as_deref(),
+            Some("https://github.com/acme/lib.git")
+        );
+        // 서브모듈 → 상위 저장소의 repo_full_name / 작업 트리로 매칭
+        let owner = sub.owning_repo().unwrap();
+        assert_eq!(owner, repo);
+        let owner_name = parse_repo_full_name(&owner.origin_url().unwrap()).unwrap();
+        assert_eq!(owner.watch_key(&owner_name), "acme/App");
+        assert_eq!(wt.owning_repo().as_ref(), Some(wt));
+
+        assert!(GitRepo::discover(&root).is_none());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_parse_repo_full_name() {
+        for url in [
+            "git@github.com:owner/repo.git",
+            "https://github.com/owner/repo.git",
+            "https://github.com/owner/repo/",
+            "ssh://git@github.com/owner/repo",
+        ] {
+            assert_eq!(
+                parse_repo_full_name(url).as_deref(),
+                Some("owner/repo"),
+                "{}",
+                url
+            );
+        }
+        assert_eq!(parse_repo_full_name("https://github.com/owner"), None);
+        assert_eq!(parse_repo_full_name("/local/path"), None);
+    }
+}
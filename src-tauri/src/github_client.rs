@@ -0,0 +1,679 @@
+// ===========================================
+// github_client.rs — GitHub REST API 클라이언트
+// github_connections 토큰으로 접근 가능한 레포 목록 / 프로젝트 메타데이터 갱신
+// Link 헤더 페이지네이션, ETag 조건부 요청(304 → 캐시 재사용), rate limit 헤더 준수
+// repo_id 기준 조회(/repositories/{id})로 이름 변경·이전된 레포 추적
+// ===========================================
+
+use crate::auth_session::unix_now;
+use crate::local_db::LocalDb;
+use crate::models::Project;
+use reqwest::header::{HeaderMap, ACCEPT, ETAG, IF_NONE_MATCH, LINK, RETRY_AFTER, USER_AGENT};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+pub const DEFAULT_API_URL: &str = "https://api.github.com";
+pub const RATE_LIMITED: &str = "GitHub rate limit 초과";
+const API_VERSION: &str = "2022-11-28";
+const CLIENT_NAME: &str = "orchestrator";
+const PER_PAGE: u32 = 100;
+/// 레포 목록 최대 페이지 수 (100 × 50)
+const MAX_PAGES: usize = 50;
+/// ETag 캐시 최대 항목 수 (초과 시 가장 오래 안 쓴 항목부터 제거)
+const CACHE_CAPACITY: usize = 256;
+
+/// GitHub 레포 (projects 테이블에 반영하는 필드만)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GithubRepo {
+    pub id: i64,
+    pub name: String,
+    pub full_name: String,
+    pub description: Option<String>,
+    pub html_url: String,
+    pub private: bool,
+    pub language: Option<String>,
+    pub default_branch: String,
+}
+
+/// X-RateLimit-* 헤더 (reset: unix 초)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RateLimit {
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset: i64,
+}
+
+impl RateLimit {
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        Some(Self {
+            limit: header_str(headers, "x-ratelimit-limit")?.parse().ok()?,
+            remaining: header_str(headers, "x-ratelimit-remaining")?.parse().ok()?,
+            reset: header_str(headers, "x-ratelimit-reset")?.parse().ok()?,
+        })
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+/// Link 헤더에서 rel="next" URL 추출
+pub fn next_link(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (url, params) = part.split_once(';')?;
+        params
+            .split(';')
+            .any(|p| p.trim() == r#"rel="next""#)
+            .then(|| {
+                url.trim()
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string()
+            })
+    })
+}
+
+/// base URL과 같은 origin(scheme/host/port) + 경로 하위인지 (Link 헤더 URL 검증)
+fn is_api_url(base_url: &str, url: &str) -> bool {
+    let (Ok(base), Ok(target)) = (url::Url::parse(base_url), url::Url::parse(url)) else {
+        return false;
+    };
+    let base_path = base.path().trim_end_matches('/');
+    base.scheme() == target.scheme()
+        && base.host() == target.host()
+        && base.port_or_known_default() == target.port_or_known_default()
+        && (target.path() == base_path || target.path().starts_with(&format!("{}/", base_path)))
+}
+
+/// 토큰별 캐시 키 (다른 계정의 캐시 응답을 재사용하지 않도록)
+fn cache_key(token: &str, url: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    let fingerprint: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}:{}", fingerprint, url)
+}
+
+/// ETag 캐시 항목
+struct CachedPage {
+    etag: String,
+    body: serde_json::Value,
+    next: Option<String>,
+}
+
+/// 용량 제한 ETag 캐시 (LRU)
+#[derive(Default)]
+struct EtagCache {
+    entries: HashMap<String, (u64, CachedPage)>,
+    tick: u64,
+}
+
+impl EtagCache {
+    fn get(&mut self, key: &str) -> Option<&CachedPage> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|(used, page)| {
+            *used = tick;
+            &*page
+        })
+    }
+
+    fn insert(&mut self, key: String, page: CachedPage) {
+        self.tick += 1;
+        self.entries.insert(key, (self.tick, page));
+        while self.entries.len() > CACHE_CAPACITY {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(k, _)| k.clone());
+            match oldest {
+                Some(k) => self.entries.remove(&k),
+                None => break,
+            };
+        }
+    }
+
+    fn remove(&mut self, key: &str) {
+        self.entries.remove(key);
+    }
+}
+
+/// GET 1회 결과 (body: None → 404)
+struct Page {
+    body: Option<serde_json::Value>,
+    next: Option<String>,
+}
+
+/// 프로젝트 메타데이터 갱신 결과
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RefreshSummary {
+    pub checked: usize,
+    pub updated: usize,
+    /// (이전 repo_full_name, 현재 repo_full_name)
+    pub renamed: Vec<(String, String)>,
+    /// 삭제되었거나 접근 권한이 없는 레포
+    pub missing: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+pub struct GithubClient {
+    client: reqwest::Client,
+    base_url: String,
+    cache: Mutex<EtagCache>,
+    rate_limit: Mutex<Option<RateLimit>>,
+}
+
+impl GithubClient {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            cache: Mutex::new(EtagCache::default()),
+            rate_limit: Mutex::new(None),
+        }
+    }
+
+    /// 마지막 응답 기준 rate limit
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit.lock().ok().and_then(|r| *r)
+    }
+
+    fn set_rate_limit(&self, limit: RateLimit) {
+        if let Ok(mut r) = self.rate_limit.lock() {
+            *r = Some(limit);
+        }
+    }
+
+    /// 한도 소진 상태면 reset 전까지 요청하지 않음
+    fn check_rate_limit(&self, now: i64) -> Result<(), String> {
+        match self.rate_limit() {
+            Some(limit) if limit.remaining == 0 && limit.reset > now => Err(format!(
+                "{}: {}초 후 재시도",
+                RATE_LIMITED,
+                limit.reset - now
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    async fn get(&self, token: &str, url: &str) -> Result<Page, String> {
+        // 응답의 Link가 다른 호스트를 가리키면 토큰을 보내지 않음
+        if !is_api_url(&self.base_url, url) {
+            return Err(format!("GitHub API 외부 URL 거부: {}", url));
+        }
+        self.check_rate_limit(unix_now())?;
+
+        let key = cache_key(token, url);
+        let etag = self
+            .cache
+            .lock()
+            .map_err(|e| e.to_string())?
+            .get(&key)
+            .map(|c| c.etag.clone());
+
+        let mut request = self
+            .client
+            .get(url)
+            .bearer_auth(token)
+            .header(ACCEPT, "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", API_VERSION)
+            .header(USER_AGENT, CLIENT_NAME);
+        if let Some(etag) = etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| format!("GitHub 요청 실패: {}", e))?;
+
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        if let Some(limit) = RateLimit::from_headers(&headers) {
+            self.set_rate_limit(limit);
+        }
+
+        match status {
+            // 304는 rate limit에 포함되지 않음 → 캐시 재사용
+            StatusCode::NOT_MODIFIED => {
+                let mut cache = self.cache.lock().map_err(|e| e.to_string())?;
+                let cached = cache
+                    .get(&key)
+                    .ok_or_else(|| format!("GitHub 304 응답에 대응하는 캐시 없음: {}", url))?;
+                return Ok(Page {
+                    body: Some(cached.body.clone()),
+                    next: cached.next.clone(),
+                });
+            }
+            StatusCode::NOT_FOUND => {
+                if let Ok(mut cache) = self.cache.lock() {
+                    cache.remove(&key);
+                }
+                return Ok(Page {
+                    body: None,
+                    next: None,
+                });
+            }
+            StatusCode::UNAUTHORIZED => {
+                return Err("GitHub 토큰 거부 (401): 재연결 필요".to_string());
+            }
+            StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => {
+                // secondary rate limit은 Retry-After, primary는 remaining = 0
+                let retry_after =
+                    header_str(&headers, RETRY_AFTER.as_str()).and_then(|v| v.parse::<i64>().ok());
+                if let Some(secs) = retry_after {
+                    let mut limit = self.rate_limit().unwrap_or(RateLimit {
+                        limit: 0,
+                        remaining: 0,
+                        reset: 0,
+                    });
+                    limit.remaining = 0;
+                    limit.reset = limit.reset.max(unix_now() + secs);
+                    self.set_rate_limit(limit);
+                }
+                self.check_rate_limit(unix_now())?;
+            }
+            _ => {}
+        }
+
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(format!("GitHub API 오류 ({}): {}", status, text));
+        }
+
+        let next = header_str(&headers, LINK.as_str()).and_then(next_link);
+        let body: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| format!("GitHub 응답 파싱 실패: {}", e))?;
+
+        if let Some(etag) = header_str(&headers, ETAG.as_str()) {
+            if let Ok(mut cache) = self.cache.lock() {
+                cache.insert(
+                    key,
+                    CachedPage {
+                        etag: etag.to_string(),
+                        body: body.clone(),
+                        next: next.clone(),
+                    },
+                );
+            }
+        }
+        Ok(Page {
+            body: Some(body),
+            next,
+        })
+    }
+
+    /// 토큰으로 접근 가능한 전체 레포 (소유 + collaborator + organization)
+    pub async fn list_repos(&self, token: &str) -> Result<Vec<GithubRepo>, String> {
+        let mut url = Some(format!(
+            "{}/user/repos?per_page={}&sort=full_name",
+            self.base_url, PER_PAGE
+        ));
+        let mut repos = Vec::new();
+        let mut pages = 0;
+
+        while let Some(current) = url.take() {
+            if pages == MAX_PAGES {
+                log::warn!("⚠ GitHub 레포 목록 {}페이지 초과 → 중단", MAX_PAGES);
+                break;
+            }
+            let page = self.get(token, &current).await?;
+            pages += 1;
+            let body = page
+                .body
+                .ok_or_else(|| "GitHub 레포 목록 조회 실패 (404)".to_string())?;
+            let batch: Vec<GithubRepo> = serde_json::from_value(body)
+                .map_err(|e| format!("GitHub 레포 목록 파싱 실패: {}", e))?;
+            repos.extend(batch);
+            url = page.next;
+        }
+        Ok(repos)
+    }
+
+    /// repo_id → 현재 레포 (이름 변경·이전 후에도 id 유지). 삭제/권한 없음 → None
+    pub async fn get_repo(&self, token: &str, repo_id: i64) -> Result<Option<GithubRepo>, String> {
+        let url = format!("{}/repositories/{}", self.base_url, repo_id);
+        self.get_repo_at(token, &url).await
+    }
+
+    /// "owner/repo" → 레포 (repo_id가 없는 프로젝트용)
+    pub async fn get_repo_by_name(
+        &self,
+        token: &str,
+        full_name: &str,
+    ) -> Result<Option<GithubRepo>, String> {
+        let url = format!("{}/repos/{}", self.base_url, full_name);
+        self.get_repo_at(token, &url).await
+    }
+
+    async fn get_repo_at(&self, token: &str, url: &str) -> Result<Option<GithubRepo>, String> {
+        match self.get(token, url).await?.body {
+            Some(body) => serde_json::from_value(body)
+                .map(Some)
+                .map_err(|e| format!("GitHub 레포 파싱 실패: {}", e)),
+            None => Ok(None),
+        }
+    }
+
+    /// projects 테이블의 레포 메타데이터 갱신 (repo_id 기준으로 이름 변경·이전 감지)
+    /// rate limit 소진 시 남은 프로젝트는 다음 호출로 미룸
+    pub async fn refresh_projects(
+        &self,
+        token: &str,
+        db: &LocalDb,
+    ) -> Result<RefreshSummary, String> {
+        let projects = db.get_all_projects().map_err(|e| e.to_string())?;
+        let mut summary = RefreshSummary::default();
+
+        for project in &projects {
+            let full_name = project.repo_full_name.as_str();
+            let fetched = if project.repo_id > 0 {
+                self.get_repo(token, project.repo_id).await
+            } else {
+                self.get_repo_by_name(token, full_name).await
+            };
+            let repo = match fetched {
+                Ok(Some(repo)) => repo,
+                Ok(None) => {
+                    log::warn!("⚠ GitHub 레포 없음 (삭제 또는 권한 없음): {}", full_name);
+                    summary.missing.push(full_name.to_string());
+                    summary.checked += 1;
+                    continue;
+                }
+                Err(e) if e.starts_with(RATE_LIMITED) => {
+                    summary.errors.push(e);
+                    break;
+                }
+                Err(e) => {
+                    summary.errors.push(format!("{}: {}", full_name, e));
+                    continue;
+                }
+            };
+            summary.checked += 1;
+
+            if !project_differs(project, &repo) {
+                continue;
+            }
+            if let Err(e) = db.apply_github_repo(&project.id, &repo) {
+                summary.errors.push(format!("{}: {}", full_name, e));
+                continue;
+            }
+            summary.updated += 1;
+            if repo.full_name != full_name {
+                log::info!(
+                    "🔀 GitHub 레포 이름 변경/이전: {} → {}",
+                    full_name,
+                    repo.full_name
+                );
+                summary
+                    .renamed
+                    .push((full_name.to_string(), repo.full_name.clone()));
+            }
+        }
+        Ok(summary)
+    }
+}
+
+/// 로컬 프로젝트 행과 GitHub 레포 필드 비교
+fn project_differs(project: &Project, repo: &GithubRepo) -> bool {
+    project.repo_id != repo.id
+        || project.repo_name != repo.name
+        || project.repo_full_name != repo.full_name
+        || project.repo_url != repo.html_url
+        || project.description != repo.description
+        || project.default_branch != repo.default_branch
+        || project.language != repo.language
+        || project.is_private != repo.private
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    /// 순서대로 (status, 추가 헤더, body) 응답, 요청 원문 목록 반환
+    async fn serve(
+        listener: tokio::net::TcpListener,
+        responses: Vec<(&'static str, String, String)>,
+    ) -> Vec<String> {
+        let mut requests = Vec::new();
+        for (status, headers, body) in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !String::from_utf8_lossy(&buf).contains("\r\n\r\n") {
+                let n = socket.read(&mut chunk).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                buf.extend_from_slice(&chunk[..n]);
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                headers,
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            requests.push(String::from_utf8_lossy(&buf).to_string());
+        }
+        requests
+    }
+
+    fn repo_json(id: i64, full_name: &str) -> serde_json::Value {
+        let name = full_name.rsplit('/').next().unwrap();
+        serde_json::json!({
+            "id": id,
+            "name": name,
+            "full_name": full_name,
+            "description": null,
+            "html_url": format!("https://github.com/{}", full_name),
+            "private": false,
+            "language": "Rust",
+            "default_branch": "main",
+        })
+    }
+
+    #[test]
+    fn test_next_link() {
+        let link = r#"<https://api.github.com/user/repos?page=2>; rel="next", <https://api.github.com/user/repos?page=5>; rel="last""#;
+        assert_eq!(
+            next_link(link).as_deref(),
+            Some("https://api.github.com/user/repos?page=2")
+        );
+        let last = r#"<https://api.github.com/user/repos?page=1>; rel="prev""#;
+        assert_eq!(next_link(last), None);
+    }
+
+    #[test]
+    fn test_is_api_url_requires_same_origin() {
+        let base = "https://api.github.com";
+        assert!(is_api_url(base, "https://api.github.com/user/repos?page=2"));
+        assert!(is_api_url(base, "https://api.github.com:443/user/repos"));
+        assert!(!is_api_url(
+            base,
+            "https://api.github.com.evil.com/user/repos"
+        ));
+        assert!(!is_api_url(
+            base,
+            "https://api.github.com@evil.com/user/repos"
+        ));
+        assert!(!is_api_url(base, "http://api.github.com/user/repos"));
+        assert!(!is_api_url(base, "https://api.github.com:8443/user/repos"));
+        assert!(!is_api_url(base, "not a url"));
+
+        // GitHub Enterprise: 경로 하위만 허용
+        let ghe = "https://ghe.example.com/api/v3";
+        assert!(is_api_url(ghe, "https://ghe.example.com/api/v3/user/repos"));
+        assert!(!is_api_url(
+            ghe,
+            "https://ghe.example.com/api/v30/user/repos"
+        ));
+        assert!(!is_api_url(ghe, "https://ghe.example.com/login"));
+    }
+
+    #[test]
+    fn test_etag_cache_evicts_least_recently_used() {
+        let page = |etag: &str| CachedPage {
+            etag: etag.to_string(),
+            body: serde_json::json!([]),
+            next: None,
+        };
+        let mut cache = EtagCache::default();
+        for i in 0..CACHE_CAPACITY {
+            cache.insert(format!("k{}", i), page(&i.to_string()));
+        }
+        // k0 사용 → k1이 가장 오래됨
+        assert!(cache.get("k0").is_some());
+        cache.insert("new".to_string(), page("new"));
+        assert_eq!(cache.entries.len(), CACHE_CAPACITY);
+        assert!(cache.get("k0").is_some());
+        assert!(cache.get("k1").is_none());
+        assert_eq!(cache.get("new").map(|p| p.etag.as_str()), Some("new"));
+    }
+
+    #[tokio::test]
+    async fn test_list_repos_paginates_and_revalidates_with_etag() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let page1 = serde_json::json!([repo_json(1, "me/a"), repo_json(2, "me/b")]).to_string();
+        let page2 = serde_json::json!([repo_json(3, "org/c")]).to_string();
+        let link = format!(
+            "Link: <{}/user/repos?per_page=100&sort=full_name&page=2>; rel=\"next\"\r\n",
+            base
+        );
+        let server = tokio::spawn(serve(
+            listener,
+            vec![
+                ("200 OK", format!("{}ETag: \"p1\"\r\n", link), page1),
+                ("200 OK", String::new(), page2.clone()),
+                ("304 Not Modified", String::new(), String::new()),
+                ("200 OK", String::new(), page2),
+            ],
+        ));
+
+        let client = GithubClient::new(&base);
+        let first = client.list_repos("gho_x").await.unwrap();
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[2].full_name, "org/c");
+
+        // 두 번째 조회: 1페이지는 304 → 캐시된 목록과 Link 재사용
+        let second = client.list_repos("gho_x").await.unwrap();
+        assert_eq!(second, first);
+
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /user/repos?per_page=100&sort=full_name HTTP/1.1"));
+        assert!(requests[0].contains("authorization: Bearer gho_x"));
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("page=2"));
+        assert!(requests[2].contains("if-none-match: \"p1\""));
+        assert!(requests[3].contains("page=2"));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_blocks_until_reset() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let reset = unix_now() + 120;
+        let server = tokio::spawn(serve(
+            listener,
+            vec![
+                (
+                    "404 Not Found",
+                    format!(
+                        "X-RateLimit-Limit: 5000\r\nX-RateLimit-Remaining: 1\r\nX-RateLimit-Reset: {}\r\n",
+                        reset
+                    ),
+                    r#"{"message":"Not Found"}"#.to_string(),
+                ),
+                (
+                    "200 OK",
+                    format!(
+                        "X-RateLimit-Limit: 5000\r\nX-RateLimit-Remaining: 0\r\nX-RateLimit-Reset: {}\r\n",
+                        reset
+                    ),
+                    repo_json(7, "me/seven").to_string(),
+                ),
+            ],
+        ));
+
+        let client = GithubClient::new(&base);
+        assert_eq!(client.get_repo("t", 2).await.unwrap(), None);
+        let repo = client.get_repo("t", 7).await.unwrap().unwrap();
+        assert_eq!(repo.full_name, "me/seven");
+        assert_eq!(client.rate_limit().unwrap().remaining, 0);
+
+        // 한도 소진 → 요청 없이 에러
+        let err = client.get_repo("t", 7).await.unwrap_err();
+        assert!(err.starts_with(RATE_LIMITED));
+        assert_eq!(server.await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_projects_follows_renamed_repo() {
+        let tmp = std::env::temp_dir().join("orchestrator_github_refresh_test.db");
+        let _ = std::fs::remove_file(&tmp);
+        let db = LocalDb::open_at(&tmp).unwrap();
+        db.upsert_project(
+            &crate::models::decode(serde_json::json!({
+                "repo_id": 42,
+                "repo_name": "old",
+                "repo_full_name": "me/old",
+                "repo_url": "https://github.com/me/old",
+                "default_branch": "master",
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+        db.upsert_watcher_path("me/old", "/tmp/old").unwrap();
+        db.upsert_watcher_path("me/old@feature", "/tmp/old-feature")
+            .unwrap();
+        db.upsert_watcher_path("me/older", "/tmp/older").unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(serve(
+            listener,
+            vec![(
+                "200 OK",
+                String::new(),
+                repo_json(42, "org/new").to_string(),
+            )],
+        ));
+
+        let client = GithubClient::new(&base);
+        let summary = client.refresh_projects("t", &db).await.unwrap();
+        assert_eq!(summary.checked, 1);
+        assert_eq!(summary.updated, 1);
+        assert_eq!(
+            summary.renamed,
+            vec![("me/old".to_string(), "org/new".to_string())]
+        );
+        let requests = server.await.unwrap();
+        assert!(requests[0].starts_with("GET /repositories/42 "));
+
+        let projects = db.get_all_projects().unwrap();
+        assert_eq!(projects[0].repo_full_name, "org/new");
+        assert_eq!(projects[0].default_branch, "main");
+        assert_eq!(projects[0].language.as_deref(), Some("Rust"));
+        let mut paths = db.get_all_watcher_paths().unwrap();
+        paths.sort();
+        assert_eq!(
+            paths,
+            vec![
+                ("me/older".to_string(), "/tmp/older".to_string()),
+                ("org/new".to_string(), "/tmp/old".to_string()),
+                (
+                    "org/new@feature".to_string(),
+                    "/tmp/old-feature".to_string()
+                ),
+            ]
+        );
+
+        let _ = std::fs::remove_file(&tmp);
+    }
+}
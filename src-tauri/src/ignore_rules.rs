@@ -0,0 +1,298 @@
+// ===========================================
+// ignore_rules.rs — 감시 제외 규칙 (watcher + offline_tracker 공용)
+// .gitignore (디렉토리별) / .git/info/exclude / 전역 excludesFile
+// / .orchestrator/ignore 를 하나의 matcher로 통합
+// ===========================================
+
+use crate::git_discovery::GitRepo;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+/// 규칙과 무관하게 항상 무시하는 디렉토리
+const ALWAYS_IGNORED: &[&str] = &[".git", ".orchestrator", "node_modules"];
+
+/// 디렉토리별 .gitignore 캐시 상한 (넘으면 .gitignore 없는 항목부터 비움)
+const DIR_CACHE_CAPACITY: usize = 4096;
+
+/// 기본 제외 패턴 (가장 낮은 우선순위 — gitignore의 `!pattern`으로 재포함 가능)
+const DEFAULT_PATTERNS: &[&str] = &[
+    "dist/",
+    "build/",
+    ".next/",
+    "target/",
+    ".tauri/",
+    ".turbo/",
+    "coverage/",
+    ".venv/",
+    "venv/",
+    "__pycache__/",
+];
+
+/// 프로젝트 단위 제외 파일 경로
+pub fn project_ignore_path(project_root: &Path) -> PathBuf {
+    project_root.join(".orchestrator").join("ignore")
+}
+
+/// 루트 단위 규칙 (파일 변경 시 reload)
+struct RootRules {
+    project: Gitignore,
+    exclude: Gitignore,
+    global: Gitignore,
+    defaults: Gitignore,
+}
+
+/// gitignore 기반 경로 제외 matcher
+pub struct IgnoreMatcher {
+    root: PathBuf,
+    rules: Mutex<RootRules>,
+    /// 디렉토리 → 해당 디렉토리의 .gitignore (없으면 None)
+    dir_ignores: Mutex<HashMap<PathBuf, Option<Gitignore>>>,
+}
+
+impl IgnoreMatcher {
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            root: project_root.to_path_buf(),
+            rules: Mutex::new(load_root_rules(project_root)),
+            dir_ignores: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 경로가 감시/스캔 대상에서 제외되는지
+    /// 우선순위: 항상 제외 > .orchestrator/ignore > .gitignore(깊은 것 우선)
+    /// > .git/info/exclude > 전역 excludesFile > 기본 패턴
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let relative = match path.strip_prefix(&self.root) {
+            Ok(r) => r,
+            Err(_) => return false,
+        };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+
+        let always = relative.components().any(|c| match c {
+            Component::Normal(name) => ALWAYS_IGNORED.iter().any(|d| name == *d),
+            _ => false,
+        });
+        if always {
+            return true;
+        }
+
+        let rules = match self.rules.lock() {
+            Ok(r) => r,
+            Err(_) => return false,
+        };
+
+        if let Some(ignored) = decide(&rules.project, path, is_dir) {
+            return ignored;
+        }
+
+        // 가장 가까운 디렉토리의 .gitignore부터
+        if let Some(parent) = relative.parent() {
+            for dir in parent.ancestors() {
+                if let Some(ignored) = self.check_dir_gitignore(&self.root.join(dir), path, is_dir) {
+                    return ignored;
+                }
+            }
+        }
+
+        for gitignore in [&rules.exclude, &rules.global, &rules.defaults] {
+            if let Some(ignored) = decide(gitignore, path, is_dir) {
+                return ignored;
+            }
+        }
+        false
+    }
+
+    /// 디렉토리의 .gitignore 변경 → 캐시 무효화
+    pub fn invalidate_dir(&self, dir: &Path) {
+        if let Ok(mut cache) = self.dir_ignores.lock() {
+            cache.remove(dir);
+        }
+    }
+
+    /// .orchestrator/ignore, .git/info/exclude, 전역 규칙 다시 읽기
+    pub fn reload(&self) {
+        if let Ok(mut rules) = self.rules.lock() {
+            *rules = load_root_rules(&self.root);
+        }
+        if let Ok(mut cache) = self.dir_ignores.lock() {
+            cache.clear();
+        }
+    }
+
+    /// 삭제된 디렉토리 → 그 아래 캐시 항목 제거
+    fn forget_removed(&self, path: &Path) {
+        if let Ok(mut cache) = self.dir_ignores.lock() {
+            cache.retain(|dir, _| !dir.starts_with(path));
+        }
+    }
+
+    /// 제외 규칙 파일 자체가 변경된 경우 reload 처리, 처리했으면 true
+    /// (삭제된 경로는 캐시에서도 정리)
+    pub fn handle_rule_file_change(&self, path: &Path) -> bool {
+        if !path.exists() {
+            self.forget_removed(path);
+        }
+        if path.file_name().map(|n| n == ".gitignore").unwrap_or(false) {
+            if let Some(dir) = path.parent() {
+                self.invalidate_dir(dir);
+            }
+            return true;
+        }
+        if path == project_ignore_path(&self.root) || path == exclude_file(&self.root) {
+            self.reload();
+            return true;
+        }
+        false
+    }
+
+    fn check_dir_gitignore(&self, dir: &Path, path: &Path, is_dir: bool) -> Option<bool> {
+        let mut cache = self.dir_ignores.lock().ok()?;
+        if cache.len() >= DIR_CACHE_CAPACITY && !cache.contains_key(dir) {
+            cache.retain(|_, g| g.is_some());
+            if cache.len() >= DIR_CACHE_CAPACITY {
+                cache.clear();
+            }
+        }
+        let gitignore = cache
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let file = dir.join(".gitignore");
+                if file.is_file() {
+                    Some(build_from_file(dir, &file))
+                } else {
+                    None
+                }
+            })
+            .as_ref()?;
+        decide(gitignore, path, is_dir)
+    }
+}
+
+/// ignore → Some(true), whitelist(`!pattern`) → Some(false), 매칭 없음 → None
+fn decide(gitignore: &Gitignore, path: &Path, is_dir: bool) -> Option<bool> {
+    if gitignore.is_empty() {
+        return None;
+    }
+    let m = gitignore.matched_path_or_any_parents(path, is_dir);
+    if m.is_ignore() {
+        Some(true)
+    } else if m.is_whitelist() {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+fn build_from_file(root: &Path, file: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(root);
+    if let Some(e) = builder.add(file) {
+        log::warn!("⚠ 제외 규칙 파싱 오류 ({}): {}", file.display(), e);
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// info/exclude 경로 (worktree는 메인 저장소 공유 디렉토리)
+fn exclude_file(root: &Path) -> PathBuf {
+    GitRepo::discover(root)
+        .map(|repo| repo.common_dir)
+        .unwrap_or_else(|| root.join(".git"))
+        .join("info")
+        .join("exclude")
+}
+
+fn load_root_rules(root: &Path) -> RootRules {
+    let project_file = project_ignore_path(root);
+    let project = if project_file.is_file() {
+        build_from_file(root, &project_file)
+    } else {
+        Gitignore::empty()
+    };
+
+    let exclude_file = exclude_file(root);
+    let exclude = if exclude_file.is_file() {
+        build_from_file(root, &exclude_file)
+    } else {
+        Gitignore::empty()
+    };
+
+    // core.excludesFile (없으면 $XDG_CONFIG_HOME/git/ignore)
+    let mut global_builder = GitignoreBuilder::new(root);
+    if let Some(file) = ignore::gitignore::gitconfig_excludes_path() {
+        if file.is_file() {
+            global_builder.add(file);
+        }
+    }
+    let global = global_builder.build().unwrap_or_else(|_| Gitignore::empty());
+
+    let mut defaults_builder = GitignoreBuilder::new(root);
+    for pattern in DEFAULT_PATTERNS {
+        let _ = defaults_builder.add_line(None, pattern);
+    }
+    let defaults = defaults_builder.build().unwrap_or_else(|_| Gitignore::empty());
+
+    RootRules {
+        project,
+        exclude,
+        global,
+        defaults,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gitignore_layers() {
+        let root = std::env::temp_dir().join("orchestrator_ignore_rules_test");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join(".git/info")).unwrap();
+        std::fs::create_dir_all(root.join(".orchestrator")).unwrap();
+        std::fs::create_dir_all(root.join("packages/web")).unwrap();
+
+        std::fs::write(root.join(".gitignore"), "*.log\n.venv/\n!build/\n").unwrap();
+        std::fs::write(root.join("packages/web/.gitignore"), "generated/\n!keep.log\n").unwrap();
+        std::fs::write(root.join(".git/info/exclude"), "scratch.txt\n").unwrap();
+        std::fs::write(root.join(".orchestrator/ignore"), "fixtures/\n").unwrap();
+
+        let m = IgnoreMatcher::new(&root);
+        assert!(m.is_ignored(&root.join(".git/HEAD"), false));
+        assert!(m.is_ignored(&root.join("node_modules/x/index.js"), false));
+        assert!(m.is_ignored(&root.join("debug.log"), false));
+        assert!(m.is_ignored(&root.join(".venv/lib/site.py"), false));
+        assert!(m.is_ignored(&root.join("packages/web/generated/a.ts"), false));
+        assert!(!m.is_ignored(&root.join("packages/web/keep.log"), false));
+        assert!(m.is_ignored(&root.join("scratch.txt"), false));
+        assert!(m.is_ignored(&root.join("fixtures/data.json"), false));
+        assert!(m.is_ignored(&root.join(".turbo/cache"), false));
+        // 기본 패턴은 .gitignore의 `!build/`로 재포함
+        assert!(!m.is_ignored(&root.join("build/main.rs"), false));
+        assert!(!m.is_ignored(&root.join("src/main.rs"), false));
+
+        // .gitignore 변경 → 캐시 무효화
+        std::fs::write(root.join("packages/web/.gitignore"), "").unwrap();
+        assert!(m.handle_rule_file_change(&root.join("packages/web/.gitignore")));
+        assert!(!m.is_ignored(&root.join("packages/web/generated/a.ts"), false));
+
+        // 디렉토리 삭제 → 그 아래 캐시 항목 제거
+        let cached = |dir: &str| m.dir_ignores.lock().unwrap().contains_key(&root.join(dir));
+        assert!(cached("packages/web"));
+        std::fs::remove_dir_all(root.join("packages")).unwrap();
+        assert!(!m.handle_rule_file_change(&root.join("packages")));
+        assert!(!cached("packages/web"));
+        assert!(cached(""));
+
+        // 캐시 상한 → .gitignore 없는 디렉토리 항목부터 비움
+        for i in 0..DIR_CACHE_CAPACITY + 10 {
+            m.is_ignored(&root.join(format!("d{}/a.rs", i)), false);
+        }
+        assert!(m.dir_ignores.lock().unwrap().len() <= DIR_CACHE_CAPACITY);
+        assert!(cached(""));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
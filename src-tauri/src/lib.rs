@@ -1,45 +1,80 @@
 mod oauth;
 mod session;
 mod contract;
+mod command_log;
+mod budget;
+mod ignore_rules;
+mod commit_detector;
 mod watcher;
+mod watch_supervisor;
 mod sync_client;
+mod auth_session;
+mod realtime;
+mod event_pipeline;
+mod sync_engine;
+mod sync_conflict;
+mod sync_worker;
 mod offline_tracker;
+mod git_discovery;
 mod local_db;
+mod credential_store;
+mod github_client;
+mod models;
+mod db_migrations;
 
+use models::DbError;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::TrayIconBuilder;
 
 /// 앱 전역 상태: 멀티 프로젝트 감시 + 로컬 DB
 struct AppState {
-    /// repo_full_name → WatcherState 매핑
-    watchers: Mutex<HashMap<String, watcher::WatcherState>>,
+    /// repo_full_name별 watcher 수명 관리
+    watchers: Arc<watch_supervisor::WatchSupervisor>,
     /// repo_full_name → 로컬 경로 매핑
     project_paths: Mutex<HashMap<String, PathBuf>>,
     /// 전체 감시 활성화 여부
     watching_enabled: Mutex<bool>,
     /// 로컬 SQLite DB
-    db: local_db::LocalDb,
+    db: Arc<local_db::LocalDb>,
+    /// 토큰 저장소 (OS 키링 / 암호화 파일) — DB에는 참조만 저장
+    credentials: Arc<credential_store::Credentials>,
+    /// GitHub REST 클라이언트 (ETag 캐시 + rate limit 상태 유지)
+    github: Arc<github_client::GithubClient>,
     /// Supabase 이벤트 전송 클라이언트
     sync_client: Option<Arc<sync_client::SyncClient>>,
+    /// local_db ↔ Supabase 테이블 동기화
+    sync_engine: Option<Arc<sync_engine::SyncEngine>>,
 }
 
+/// OAuth 로그인 시작 → authorize URL 반환 (프론트엔드가 외부 브라우저로 열기)
+/// 콜백 대기 + 토큰 교환은 백그라운드 → oauth-session / oauth-error emit
 #[tauri::command]
-async fn start_oauth_server(app: tauri::AppHandle) -> Result<String, String> {
-    let (server, listener) = oauth::OAuthServer::new()
-        .await
-        .map_err(|e| format!("서버 시작 실패: {}", e))?;
-
-    let callback_url = server.callback_url();
+async fn start_oauth_login(
+    app: tauri::AppHandle,
+    provider: Option<String>,
+    scopes: Option<String>,
+) -> Result<String, String> {
+    let sc = app
+        .state::<AppState>()
+        .sync_client
+        .clone()
+        .ok_or_else(|| "Supabase 설정 없음".to_string())?;
+    let login = oauth::OAuthLogin::bind().await?;
+    let url = login.authorize_url(
+        sc.config(),
+        provider.as_deref().unwrap_or("github"),
+        scopes.as_deref(),
+    );
 
     tauri::async_runtime::spawn(async move {
-        oauth::start_callback_server(listener, app).await;
+        oauth::complete_login(app, login, sc).await;
     });
 
-    Ok(callback_url)
+    Ok(url)
 }
 
 /// 프로젝트 감시 추가 (import된 프로젝트에서 호출)
@@ -61,12 +96,7 @@ async fn add_watch_project(
     let enabled = *state.watching_enabled.lock().map_err(|e| e.to_string())?;
 
     // 기존 watcher가 있으면 중지
-    {
-        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-        if let Some(w) = watchers.remove(&repo_full_name) {
-            watcher::stop_watcher(&w);
-        }
-    }
+    state.watchers.stop(&repo_full_name);
 
     // 경로 저장 (메모리 + DB)
     {
@@ -78,9 +108,7 @@ async fn add_watch_project(
     // 감시 활성화 상태면 watcher 시작
     if enabled {
         let sc = state.sync_client.clone();
-        let watcher_state = watcher::start_watcher(project_path, app.clone(), sc)?;
-        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-        watchers.insert(repo_full_name.clone(), watcher_state);
+        state.watchers.start(&repo_full_name, project_path, &app, sc)?;
     }
 
     Ok(format!("👁 {}: watching {}", repo_full_name, path))
@@ -95,12 +123,7 @@ async fn remove_watch_project(
     let state = app.state::<AppState>();
 
     // watcher 중지
-    {
-        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-        if let Some(w) = watchers.remove(&repo_full_name) {
-            watcher::stop_watcher(&w);
-        }
-    }
+    state.watchers.stop(&repo_full_name);
 
     // 경로 제거 (메모리 + DB)
     {
@@ -120,25 +143,16 @@ async fn toggle_watch_all(app: tauri::AppHandle) -> Result<serde_json::Value, St
 
     if *enabled {
         // 전체 중지
-        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
-        for (name, w) in watchers.iter() {
-            watcher::stop_watcher(w);
-            log::info!("⏸ {} 감시 중지", name);
-        }
-        watchers.clear();
+        state.watchers.stop_all();
         *enabled = false;
     } else {
         // 전체 시작
         let paths = state.project_paths.lock().map_err(|e| e.to_string())?;
-        let mut watchers = state.watchers.lock().map_err(|e| e.to_string())?;
 
         for (name, path) in paths.iter() {
             let sc = state.sync_client.clone();
-            match watcher::start_watcher(path.clone(), app.clone(), sc) {
-                Ok(ws) => {
-                    watchers.insert(name.clone(), ws);
-                    log::info!("👁 {} 감시 시작", name);
-                }
+            match state.watchers.start(name, path.clone(), &app, sc) {
+                Ok(()) => log::info!("👁 {} 감시 시작", name),
                 Err(e) => log::error!("❌ {} 감시 실패: {}", name, e),
             }
         }
@@ -157,16 +171,16 @@ async fn get_watch_status(app: tauri::AppHandle) -> Result<serde_json::Value, St
     let state = app.state::<AppState>();
     let enabled = *state.watching_enabled.lock().map_err(|e| e.to_string())?;
     let paths = state.project_paths.lock().map_err(|e| e.to_string())?;
-    let watchers = state.watchers.lock().map_err(|e| e.to_string())?;
 
     let projects: Vec<serde_json::Value> = paths
         .iter()
         .map(|(name, path)| {
-            let is_watching = watchers.contains_key(name);
+            let status = state.watchers.status(name);
             serde_json::json!({
                 "repo_full_name": name,
                 "path": path.to_string_lossy(),
-                "watching": is_watching,
+                "watching": status.is_some(),
+                "status": status,
             })
         })
         .collect();
@@ -177,6 +191,29 @@ async fn get_watch_status(app: tauri::AppHandle) -> Result<serde_json::Value, St
     }))
 }
 
+/// 프로젝트 감시 일시 정지
+#[tauri::command]
+async fn pause_watch_project(app: tauri::AppHandle, repo_full_name: String) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    state.watchers.pause(&repo_full_name)?;
+    Ok(format!("⏸ {} 감시 일시 정지", repo_full_name))
+}
+
+/// 프로젝트 감시 재개
+#[tauri::command]
+async fn resume_watch_project(app: tauri::AppHandle, repo_full_name: String) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    state.watchers.resume(&repo_full_name)?;
+    Ok(format!("▶ {} 감시 재개", repo_full_name))
+}
+
+/// watcher별 상태 지표 (events/sec, 마지막 이벤트, 에러, 재시작 횟수)
+#[tauri::command]
+async fn get_watcher_health(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    let state = app.state::<AppState>();
+    serde_json::to_value(state.watchers.health()).map_err(|e| e.to_string())
+}
+
 /// 앱 재시작 시 오프라인 변경 감지 (모든 등록 프로젝트)
 #[tauri::command]
 async fn get_offline_changes(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
@@ -195,6 +232,141 @@ async fn get_offline_changes(app: tauri::AppHandle) -> Result<serde_json::Value,
     Ok(serde_json::json!(all_changes))
 }
 
+// ─── 세션 수명주기 (orchx session start/end 대체) ───
+
+/// 감시 등록된 프로젝트 경로
+fn project_path_of(state: &AppState, repo_full_name: &str) -> Result<PathBuf, String> {
+    let paths = state.project_paths.lock().map_err(|e| e.to_string())?;
+    paths
+        .get(repo_full_name)
+        .cloned()
+        .ok_or_else(|| format!("등록되지 않은 프로젝트: {}", repo_full_name))
+}
+
+/// repo_full_name → projects.id (없으면 None)
+/// worktree 감시 키("owner/repo@이름")는 메인 저장소 프로젝트
+fn project_id_of(state: &AppState, repo_full_name: &str) -> Option<String> {
+    let (repo_full_name, _) = git_discovery::split_watch_key(repo_full_name);
+    state
+        .db
+        .get_all_projects()
+        .ok()?
+        .into_iter()
+        .find(|p| p.repo_full_name == repo_full_name)
+        .map(|p| p.id)
+}
+
+/// 종료된 세션 → session_logs (동기화 큐 포함)
+fn archive_session(
+    state: &AppState,
+    project_id: Option<String>,
+    finished: &session::FinishedSession,
+) -> Result<(), String> {
+    let log = models::SyncRecord::SessionLog(finished.to_session_log(project_id));
+    state
+        .db
+        .upsert_syncable(&log)
+        .map(|_| ())
+        .map_err(|e| format!("세션 기록 실패: {}", e))
+}
+
+/// 세션 시작 (.orchestrator/sessions/<id>.json 생성)
+/// 작업 범위가 겹치는 다른 활성 세션은 overlaps로 반환
+#[tauri::command]
+async fn session_start(
+    app: tauri::AppHandle,
+    repo_full_name: String,
+    agent_type: String,
+    task_name: String,
+    contract: Option<session::ExecutionContract>,
+    budget: Option<session::Budget>,
+) -> Result<session::StartedSession, String> {
+    let state = app.state::<AppState>();
+    let path = project_path_of(&state, &repo_full_name)?;
+    let started = session::start_session(&path, &agent_type, &task_name, contract, budget)?;
+    log::info!("🎬 세션 시작: {} / {} ({})", started.session.agent_type, started.session.task_name, repo_full_name);
+    if !started.overlaps.is_empty() {
+        log::warn!("⚠ 작업 범위가 겹치는 세션: {}", started.overlaps.join(", "));
+    }
+    Ok(started)
+}
+
+/// 세션 종료 → session_logs 보관 후 세션 파일 삭제
+/// session_id: 활성 세션이 여럿이면 필수
+/// result: success | failure | partial | timeout (기본 success)
+#[tauri::command]
+async fn session_end(
+    app: tauri::AppHandle,
+    repo_full_name: String,
+    session_id: Option<String>,
+    result: Option<String>,
+    summary: Option<String>,
+) -> Result<session::FinishedSession, String> {
+    let state = app.state::<AppState>();
+    let path = project_path_of(&state, &repo_full_name)?;
+    let project_id = project_id_of(&state, &repo_full_name);
+    let finished = session::end_session(
+        &path,
+        session_id.as_deref(),
+        result.as_deref().unwrap_or("success"),
+        summary,
+        |f| archive_session(&state, project_id, f),
+    )?;
+    log::info!("🏁 세션 종료: {} ({}분, {})", finished.session.task_name, finished.duration_min, finished.result);
+    Ok(finished)
+}
+
+/// 다른 에이전트로 인계 → 이전 세션 보관 + 새 세션 반환
+/// session_id: 활성 세션이 여럿이면 필수
+#[tauri::command]
+async fn session_handoff(
+    app: tauri::AppHandle,
+    repo_full_name: String,
+    session_id: Option<String>,
+    to_agent: String,
+    note: Option<String>,
+) -> Result<session::Session, String> {
+    let state = app.state::<AppState>();
+    let path = project_path_of(&state, &repo_full_name)?;
+    let project_id = project_id_of(&state, &repo_full_name);
+    let (finished, next) = session::handoff_session(&path, session_id.as_deref(), &to_agent, note, |f| {
+        archive_session(&state, project_id, f)
+    })?;
+    log::info!("🤝 세션 인계: {} → {} ({})", finished.session.agent_type, next.agent_type, next.task_name);
+    Ok(next)
+}
+
+/// 현재 활성 세션 목록 (시작 시각 순, 손상된 세션 파일이 있으면 에러)
+#[tauri::command]
+async fn session_current(
+    app: tauri::AppHandle,
+    repo_full_name: String,
+) -> Result<Vec<session::Session>, String> {
+    let state = app.state::<AppState>();
+    let path = project_path_of(&state, &repo_full_name)?;
+    let listing = session::list_sessions(&path);
+    if let Some((_, e)) = listing.errors.into_iter().next() {
+        return Err(e);
+    }
+    Ok(listing.sessions.into_iter().map(|(_, s)| s).collect())
+}
+
+/// 종료된 세션 기록 (repo_full_name 지정 시 해당 프로젝트만, 최신순)
+#[tauri::command]
+async fn session_history(
+    app: tauri::AppHandle,
+    repo_full_name: Option<String>,
+) -> Result<Vec<models::SyncRecord>, DbError> {
+    let state = app.state::<AppState>();
+    let logs = state.db.get_all_syncable("session_logs", false)?;
+    let Some(repo) = repo_full_name else { return Ok(logs) };
+    let project_id = project_id_of(&state, &repo);
+    Ok(logs
+        .into_iter()
+        .filter(|r| matches!(r, models::SyncRecord::SessionLog(l) if project_id.is_some() && l.project_id.as_ref() == Some(&project_id)))
+        .collect())
+}
+
 /// 로컬 디스크에서 git 레포 위치 자동 탐색
 /// repo_urls: ["https://github.com/owner/repo.git", ...]
 /// → { "owner/repo": "/Users/.../path" }
@@ -245,6 +417,7 @@ async fn resolve_local_paths(repo_urls: Vec<String>) -> Result<serde_json::Value
     );
 
     // ─── 2단계: 각 디렉토리별 find 실행 (depth 6) ───
+    // .git은 디렉토리(일반 저장소) 또는 `gitdir:` 파일(worktree, 서브모듈)
     let mut all_git_dirs: Vec<String> = Vec::new();
 
     for search_dir in &home_children {
@@ -252,7 +425,6 @@ async fn resolve_local_paths(repo_urls: Vec<String>) -> Result<serde_json::Value
             search_dir.as_str(),
             "-maxdepth", "6",
             "-name", ".git",
-            "-type", "d",
         ];
         args.extend_from_slice(&find_excludes);
 
@@ -282,42 +454,50 @@ async fn resolve_local_paths(repo_urls: Vec<String>) -> Result<serde_json::Value
     // ─── URL 정규화 ───
     let normalized_urls: Vec<(String, String, String)> = repo_urls
         .iter()
-        .map(|url| {
+        .filter_map(|url| {
             let normalized = url
                 .trim_end_matches(".git")
                 .replace("git@github.com:", "https://github.com/")
                 .to_lowercase();
             
-            // 원본에서 https://github.com/ 부분만 제거하여 repo_full_name 추출 (대소문자 유지)
-            let original_repo_name = url
-                .trim_end_matches(".git")
-                .replace("git@github.com:", "https://github.com/")
-                .replace("https://github.com/", "");
+            // 원본 URL 기준 repo_full_name (대소문자 유지)
+            let original_repo_name = git_discovery::parse_repo_full_name(url)?;
                 
-            (url.clone(), normalized, original_repo_name)
+            Some((url.clone(), normalized, original_repo_name))
         })
         .collect();
 
+    // ─── 작업 트리 목록 (메인 저장소의 linked worktree 포함, 중복 제거) ───
+    // 서브모듈은 상위 저장소로 대체 → 상위 repo_full_name + 상위 작업 트리로 매칭
+    let mut repos: Vec<git_discovery::GitRepo> = Vec::new();
+    let mut seen: std::collections::HashSet<PathBuf> = std::collections::HashSet::new();
+    for git_dir in &all_git_dirs {
+        let Some(repo) = Path::new(git_dir)
+            .parent()
+            .and_then(git_discovery::GitRepo::discover)
+            .and_then(|r| r.owning_repo())
+        else {
+            continue;
+        };
+        let worktrees = repo.linked_worktrees();
+        for r in std::iter::once(repo).chain(worktrees) {
+            if seen.insert(r.work_tree.clone()) {
+                repos.push(r);
+            }
+        }
+    }
+
     let mut result: HashMap<String, String> = HashMap::new();
     let mut timestamps: HashMap<String, i64> = HashMap::new();
 
     // ─── 매칭 ───
-    for git_dir in &all_git_dirs {
-        let project_dir = match Path::new(git_dir).parent() {
-            Some(p) => p,
-            None => continue,
-        };
-
-        let remote = Command::new("git")
-            .args(["remote", "get-url", "origin"])
-            .current_dir(project_dir)
-            .output();
+    for repo in &repos {
+        let project_dir = repo.work_tree.as_path();
 
-        let remote_url = match remote {
-            Ok(o) if o.status.success() => {
-                String::from_utf8_lossy(&o.stdout).trim().to_string()
-            }
-            _ => continue,
+        // worktree는 메인 저장소 설정의 origin
+        let remote_url = match repo.origin_url() {
+            Some(url) => url,
+            None => continue,
         };
 
         let normalized_remote = remote_url
@@ -327,7 +507,8 @@ async fn resolve_local_paths(repo_urls: Vec<String>) -> Result<serde_json::Value
 
         for (_original_url, normalized, original_repo_name) in &normalized_urls {
             if normalized_remote == *normalized {
-                let repo_full_name = original_repo_name.clone();
+                // linked worktree는 "owner/repo@이름"으로 따로 감시
+                let repo_full_name = repo.watch_key(original_repo_name);
 
                 // 최근 커밋 타임스탬프 (중복 경로 → 최근 작업한 것 우선)
                 let last_commit_ts = Command::new("git")
@@ -366,12 +547,12 @@ async fn resolve_local_paths(repo_urls: Vec<String>) -> Result<serde_json::Value
 }
 
 // ─── 로컬 DB Tauri 커맨드 ───
+// 입력은 models::decode로 검증 → 잘못된 값은 { kind: "invalid", field, message }로 거부
 
 #[tauri::command]
-async fn db_get_model_scores(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+async fn db_get_model_scores(app: tauri::AppHandle) -> Result<Vec<models::ModelScore>, DbError> {
     let state = app.state::<AppState>();
-    let scores = state.db.get_all_model_scores().map_err(|e| e.to_string())?;
-    Ok(serde_json::json!(scores))
+    state.db.get_all_model_scores()
 }
 
 #[tauri::command]
@@ -382,18 +563,24 @@ async fn db_upsert_model_score(
     analysis: f64,
     documentation: f64,
     speed: f64,
-) -> Result<String, String> {
+) -> Result<String, DbError> {
     let state = app.state::<AppState>();
-    state.db.upsert_model_score(&model_key, coding, analysis, documentation, speed)
-        .map_err(|e| e.to_string())?;
+    state.db.upsert_model_score(&models::ModelScore {
+        id: String::new(),
+        model_key,
+        coding,
+        analysis,
+        documentation,
+        speed,
+        updated_at: None,
+    })?;
     Ok("ok".to_string())
 }
 
 #[tauri::command]
-async fn db_get_editor_models(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+async fn db_get_editor_models(app: tauri::AppHandle) -> Result<Vec<models::EditorModels>, DbError> {
     let state = app.state::<AppState>();
-    let models = state.db.get_all_editor_models().map_err(|e| e.to_string())?;
-    Ok(serde_json::json!(models))
+    state.db.get_all_editor_models()
 }
 
 #[tauri::command]
@@ -401,104 +588,454 @@ async fn db_upsert_editor_models(
     app: tauri::AppHandle,
     editor_type: String,
     supported_models: Vec<String>,
-) -> Result<String, String> {
+) -> Result<String, DbError> {
     let state = app.state::<AppState>();
-    state.db.upsert_editor_models(&editor_type, &supported_models)
-        .map_err(|e| e.to_string())?;
+    state.db.upsert_editor_models(&models::EditorModels {
+        id: String::new(),
+        editor_type,
+        supported_models,
+        updated_at: None,
+    })?;
     Ok("ok".to_string())
 }
 
 #[tauri::command]
-async fn db_get_projects(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+async fn db_get_projects(app: tauri::AppHandle) -> Result<Vec<models::Project>, DbError> {
     let state = app.state::<AppState>();
-    let projects = state.db.get_all_projects().map_err(|e| e.to_string())?;
-    Ok(serde_json::json!(projects))
+    state.db.get_all_projects()
 }
 
+/// 프로젝트 저장 → 저장된 ID (같은 repo_full_name이 있으면 기존 ID)
 #[tauri::command]
 async fn db_upsert_project(
     app: tauri::AppHandle,
     project: serde_json::Value,
-) -> Result<String, String> {
+) -> Result<String, DbError> {
+    let project: models::Project = models::decode(project)?;
     let state = app.state::<AppState>();
-    state.db.upsert_project(&project).map_err(|e| e.to_string())?;
-    Ok("ok".to_string())
+    state.db.upsert_project(&project)
 }
 
 #[tauri::command]
 async fn db_delete_project(
     app: tauri::AppHandle,
     id: String,
-) -> Result<String, String> {
+) -> Result<String, DbError> {
     let state = app.state::<AppState>();
     log::info!("🗑 프로젝트 삭제 요청: {}", id);
     state.db.delete_project(&id).map_err(|e| {
         log::error!("❌ 프로젝트 삭제 실패: {} - {}", id, e);
-        e.to_string()
+        e
     })?;
     Ok("ok".to_string())
 }
 
 #[tauri::command]
-async fn db_get_preference(app: tauri::AppHandle, key: String) -> Result<Option<String>, String> {
+async fn db_get_preference(app: tauri::AppHandle, key: String) -> Result<Option<String>, DbError> {
     let state = app.state::<AppState>();
-    state.db.get_preference(&key).map_err(|e| e.to_string())
+    Ok(state.db.get_preference(&key)?)
 }
 
 #[tauri::command]
-async fn db_set_preference(app: tauri::AppHandle, key: String, value: String) -> Result<String, String> {
+async fn db_set_preference(app: tauri::AppHandle, key: String, value: String) -> Result<String, DbError> {
+    if key.trim().is_empty() {
+        return Err(models::invalid("key", "필수 값 누락"));
+    }
     let state = app.state::<AppState>();
-    state.db.set_preference(&key, &value).map_err(|e| e.to_string())?;
+    state.db.set_preference(&key, &value)?;
     Ok("ok".to_string())
 }
 
 #[tauri::command]
-async fn db_get_pending_sync(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+async fn db_get_pending_sync(app: tauri::AppHandle) -> Result<serde_json::Value, DbError> {
     let state = app.state::<AppState>();
-    let items = state.db.get_pending_sync().map_err(|e| e.to_string())?;
+    let items = state.db.get_pending_sync()?;
     Ok(serde_json::json!(items))
 }
 
 #[tauri::command]
-async fn db_mark_synced(app: tauri::AppHandle, queue_ids: Vec<i64>) -> Result<String, String> {
+async fn db_mark_synced(app: tauri::AppHandle, queue_ids: Vec<i64>) -> Result<String, DbError> {
     let state = app.state::<AppState>();
-    state.db.mark_synced(&queue_ids).map_err(|e| e.to_string())?;
+    state.db.mark_synced(&queue_ids)?;
     Ok("ok".to_string())
 }
 
+/// 동기화 대상 레코드 저장 (테이블별 타입으로 검증) → 레코드 ID
 #[tauri::command]
 async fn db_upsert_syncable(
     app: tauri::AppHandle,
     table_name: String,
     record: serde_json::Value,
-) -> Result<String, String> {
+) -> Result<String, DbError> {
     // 테이블명 화이트리스트
-    let allowed = ["work_items", "plans", "goals", "session_logs"];
-    if !allowed.contains(&table_name.as_str()) {
-        return Err(format!("허용되지 않은 테이블: {}", table_name));
+    if !sync_engine::is_sync_table(&table_name) {
+        return Err(models::invalid("table_name", format!("허용되지 않은 테이블: {}", table_name)));
     }
+    let record = models::SyncRecord::decode(&table_name, record)?;
     let state = app.state::<AppState>();
-    let id = state.db.upsert_syncable(&table_name, &record).map_err(|e| e.to_string())?;
-    Ok(id)
+    state.db.upsert_syncable(&record)
 }
 
+/// include_deleted: soft delete된 work_items 포함 여부 (기본 제외)
 #[tauri::command]
 async fn db_get_syncable(
     app: tauri::AppHandle,
     table_name: String,
-) -> Result<serde_json::Value, String> {
-    let allowed = ["work_items", "plans", "goals", "session_logs"];
-    if !allowed.contains(&table_name.as_str()) {
-        return Err(format!("허용되지 않은 테이블: {}", table_name));
+    include_deleted: Option<bool>,
+) -> Result<Vec<models::SyncRecord>, DbError> {
+    if !sync_engine::is_sync_table(&table_name) {
+        return Err(models::invalid("table_name", format!("허용되지 않은 테이블: {}", table_name)));
     }
     let state = app.state::<AppState>();
-    let items = state.db.get_all_syncable(&table_name).map_err(|e| e.to_string())?;
+    state.db.get_all_syncable(&table_name, include_deleted.unwrap_or(false))
+}
+
+/// work_item soft delete (deleted_at 기록 → 동기화), 해당 항목이 없으면 false
+#[tauri::command]
+async fn db_soft_delete_work_item(app: tauri::AppHandle, id: String) -> Result<bool, DbError> {
+    let state = app.state::<AppState>();
+    Ok(state.db.soft_delete_work_item(&id)?)
+}
+
+/// soft delete 취소
+#[tauri::command]
+async fn db_restore_work_item(app: tauri::AppHandle, id: String) -> Result<bool, DbError> {
+    let state = app.state::<AppState>();
+    Ok(state.db.restore_work_item(&id)?)
+}
+
+/// 테이블 동기화 즉시 실행 (push → pull)
+#[tauri::command]
+async fn sync_now(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    let engine = app
+        .state::<AppState>()
+        .sync_engine
+        .clone()
+        .ok_or_else(|| "Supabase 설정 없음".to_string())?;
+    let summary = engine.sync_once().await;
+    serde_json::to_value(summary).map_err(|e| e.to_string())
+}
+
+/// 로그인 세션 전달 (로그인/토큰 갱신 시) → 사용자 JWT로 동기화 + Realtime 구독
+/// expires_at: unix 초
+#[tauri::command]
+async fn set_auth_session(
+    app: tauri::AppHandle,
+    user_id: String,
+    access_token: String,
+    refresh_token: String,
+    expires_at: i64,
+) -> Result<String, String> {
+    let sc = app
+        .state::<AppState>()
+        .sync_client
+        .clone()
+        .ok_or_else(|| "Supabase 설정 없음".to_string())?;
+    sc.sign_in(
+        app.clone(),
+        auth_session::AuthTokens {
+            user_id,
+            access_token,
+            refresh_token,
+            expires_at,
+        },
+    );
+    Ok("ok".to_string())
+}
+
+/// 로그아웃 → 세션 폐기 + Realtime 중지 (이벤트 전송 보류)
+#[tauri::command]
+async fn clear_auth_session(app: tauri::AppHandle) -> Result<String, String> {
+    if let Some(sc) = app.state::<AppState>().sync_client.clone() {
+        sc.sign_out();
+    }
+    Ok("ok".to_string())
+}
+
+/// 동기화 충돌 조회 (기본: 미해결만)
+#[tauri::command]
+async fn db_get_conflicts(
+    app: tauri::AppHandle,
+    include_resolved: Option<bool>,
+) -> Result<serde_json::Value, DbError> {
+    let state = app.state::<AppState>();
+    let conflicts = state.db.get_conflicts(include_resolved.unwrap_or(false))?;
+    Ok(serde_json::json!(conflicts))
+}
+
+/// 미해결 충돌 수동 해결 (keep: "local" | "remote")
+#[tauri::command]
+async fn db_resolve_conflict(
+    app: tauri::AppHandle,
+    conflict_id: i64,
+    keep: String,
+) -> Result<String, DbError> {
+    let keep_local = match keep.as_str() {
+        "local" => true,
+        "remote" => false,
+        other => return Err(models::invalid("keep", format!("알 수 없는 선택: {}", other))),
+    };
+    let state = app.state::<AppState>();
+    if !state.db.resolve_conflict(conflict_id, keep_local)? {
+        return Err(models::invalid("conflict_id", format!("미해결 충돌 없음: {}", conflict_id)));
+    }
+    Ok("ok".to_string())
+}
+
+/// 테이블별 충돌 해결 정책 (last_writer_wins | merge_metadata | manual)
+#[tauri::command]
+async fn db_set_conflict_policy(
+    app: tauri::AppHandle,
+    table_name: String,
+    policy: String,
+) -> Result<String, DbError> {
+    if !sync_engine::is_sync_table(&table_name) {
+        return Err(models::invalid("table_name", format!("허용되지 않은 테이블: {}", table_name)));
+    }
+    let policy = sync_conflict::ConflictPolicy::parse(&policy)
+        .ok_or_else(|| models::invalid("policy", format!("알 수 없는 정책: {}", policy)))?;
+    let state = app.state::<AppState>();
+    state.db.set_preference(
+        &format!("{}{}", sync_conflict::POLICY_PREF_PREFIX, table_name),
+        policy.as_str(),
+    )?;
+    Ok("ok".to_string())
+}
+
+/// 이벤트 outbox 조회 (status: pending | dead | delivered, 미지정 시 미전송 전체)
+#[tauri::command]
+async fn db_get_outbox_events(
+    app: tauri::AppHandle,
+    status: Option<String>,
+) -> Result<serde_json::Value, DbError> {
+    let state = app.state::<AppState>();
+    let items = state.db.outbox_list(status.as_deref())?;
     Ok(serde_json::json!(items))
 }
 
+/// dead/pending 이벤트 즉시 재시도 예약 (기록 사용자가 없는 이벤트는 현재 로그인 사용자로 귀속)
+#[tauri::command]
+async fn db_requeue_outbox_events(app: tauri::AppHandle, event_ids: Vec<String>) -> Result<usize, DbError> {
+    let state = app.state::<AppState>();
+    let user_id = state.sync_client.as_ref().and_then(|sc| sc.auth().user_id());
+    Ok(state.db.outbox_requeue(&event_ids, user_id.as_deref())?)
+}
+
+/// outbox 이벤트 삭제 (event_ids 미지정 시 dead 전체)
+#[tauri::command]
+async fn db_purge_outbox_events(
+    app: tauri::AppHandle,
+    event_ids: Option<Vec<String>>,
+) -> Result<usize, DbError> {
+    let state = app.state::<AppState>();
+    Ok(state.db.outbox_purge(event_ids.as_deref())?)
+}
+
+// ─── 자격 증명 Tauri 커맨드 ───
+
+#[tauri::command]
+async fn credentials_status(app: tauri::AppHandle) -> Result<serde_json::Value, String> {
+    let state = app.state::<AppState>();
+    Ok(serde_json::json!(state.credentials.status()))
+}
+
+/// 암호화 파일 저장소 잠금 해제 (최초 호출 시 해당 암호로 생성) → 남은 평문 토큰 이전
+#[tauri::command]
+async fn credentials_unlock(app: tauri::AppHandle, passphrase: String) -> Result<usize, String> {
+    let state = app.state::<AppState>();
+    state.credentials.unlock(&passphrase)?;
+    state.db.move_plaintext_credentials(&state.credentials)
+}
+
+#[tauri::command]
+async fn credentials_lock(app: tauri::AppHandle) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    state.credentials.lock();
+    Ok("ok".to_string())
+}
+
+/// GitHub 연결 저장: 토큰은 자격 증명 저장소, DB에는 참조만
+#[tauri::command]
+async fn db_save_github_connection(
+    app: tauri::AppHandle,
+    id: String,
+    github_username: Option<String>,
+    access_token: String,
+    refresh_token: Option<String>,
+    token_expires_at: Option<String>,
+) -> Result<String, DbError> {
+    let state = app.state::<AppState>();
+    let access_ref = state
+        .credentials
+        .put(&format!("github:{}:access", id), &access_token)
+        .map_err(DbError::Credentials)?;
+    let refresh_ref = match refresh_token.as_deref().filter(|t| !t.is_empty()) {
+        Some(token) => Some(
+            state
+                .credentials
+                .put(&format!("github:{}:refresh", id), token)
+                .map_err(DbError::Credentials)?,
+        ),
+        None => None,
+    };
+    state.db.upsert_github_connection(
+        &id,
+        github_username.as_deref(),
+        &access_ref,
+        refresh_ref.as_deref(),
+        token_expires_at.as_deref(),
+    )?;
+    Ok("ok".to_string())
+}
+
+/// 연결 목록 (토큰 참조만 포함)
+#[tauri::command]
+async fn db_get_github_connections(app: tauri::AppHandle) -> Result<serde_json::Value, DbError> {
+    let state = app.state::<AppState>();
+    let connections = state.db.get_github_connections()?;
+    Ok(serde_json::json!(connections))
+}
+
+/// 연결의 access token 조회 (저장소가 잠겨 있으면 에러)
+#[tauri::command]
+async fn db_get_github_token(app: tauri::AppHandle, id: String) -> Result<Option<String>, DbError> {
+    let state = app.state::<AppState>();
+    let connection = state.db.get_github_connections()?
+        .into_iter()
+        .find(|c| c.id == id);
+    match connection {
+        Some(c) => state.credentials.resolve(&c.access_token_ref).map_err(DbError::Credentials),
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+async fn db_delete_github_connection(app: tauri::AppHandle, id: String) -> Result<String, DbError> {
+    let state = app.state::<AppState>();
+    let removed = state.db.delete_github_connection(&id)?;
+    if let Some(c) = removed {
+        for reference in std::iter::once(&c.access_token_ref).chain(c.refresh_token_ref.as_ref()) {
+            if let Err(e) = state.credentials.remove(reference) {
+                log::warn!("⚠ 자격 증명 삭제 실패: {} - {}", reference, e);
+            }
+        }
+    }
+    Ok("ok".to_string())
+}
+
+// ─── GitHub Tauri 커맨드 ───
+
+/// 연결의 access token (connection_id 미지정 시 가장 최근 연결)
+fn github_token(state: &AppState, connection_id: Option<&str>) -> Result<String, String> {
+    let connections = state.db.get_github_connections().map_err(|e| e.to_string())?;
+    let connection = match connection_id {
+        Some(id) => connections.into_iter().find(|c| c.id == id),
+        None => connections.into_iter().next(),
+    }
+    .ok_or_else(|| "GitHub 연결 없음".to_string())?;
+    state
+        .credentials
+        .resolve(&connection.access_token_ref)?
+        .ok_or_else(|| format!("GitHub 토큰 없음: {}", connection.id))
+}
+
+/// 접근 가능한 GitHub 레포 목록 (프로젝트 가져오기용)
+#[tauri::command]
+async fn github_list_repos(
+    app: tauri::AppHandle,
+    connection_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let state = app.state::<AppState>();
+    let token = github_token(&state, connection_id.as_deref())?;
+    let github = state.github.clone();
+    let repos = github.list_repos(&token).await?;
+    Ok(serde_json::json!(repos))
+}
+
+/// projects 메타데이터 갱신 → 이름 변경·이전된 레포는 감시 경로도 새 이름으로 이동
+#[tauri::command]
+async fn github_refresh_projects(
+    app: tauri::AppHandle,
+    connection_id: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let state = app.state::<AppState>();
+    let token = github_token(&state, connection_id.as_deref())?;
+    let (github, db) = (state.github.clone(), state.db.clone());
+    let summary = github.refresh_projects(&token, &db).await?;
+
+    let enabled = *state.watching_enabled.lock().map_err(|e| e.to_string())?;
+    for (old_name, new_name) in &summary.renamed {
+        // worktree 감시 키("owner/repo@이름")도 함께 이동
+        let moved: Vec<(String, String, PathBuf)> = {
+            let mut paths = state.project_paths.lock().map_err(|e| e.to_string())?;
+            let old_keys: Vec<String> = paths
+                .keys()
+                .filter(|k| git_discovery::split_watch_key(k).0 == old_name)
+                .cloned()
+                .collect();
+            old_keys
+                .into_iter()
+                .filter_map(|old_key| {
+                    let path = paths.remove(&old_key)?;
+                    let worktree = git_discovery::split_watch_key(&old_key).1;
+                    let new_key = git_discovery::watch_key(new_name, worktree);
+                    paths.insert(new_key.clone(), path.clone());
+                    Some((old_key, new_key, path))
+                })
+                .collect()
+        };
+        for (old_key, new_key, path) in moved {
+            state.watchers.stop(&old_key);
+            if enabled {
+                let sc = state.sync_client.clone();
+                if let Err(e) = state.watchers.start(&new_key, path, &app, sc) {
+                    log::error!("❌ {} 감시 재시작 실패: {}", new_key, e);
+                }
+            }
+        }
+    }
+
+    log::info!(
+        "🐙 GitHub 메타데이터 갱신: {}개 확인, {}개 변경, {}개 이름 변경, {}개 없음",
+        summary.checked, summary.updated, summary.renamed.len(), summary.missing.len()
+    );
+    Ok(serde_json::json!({
+        "summary": summary,
+        "rate_limit": github.rate_limit(),
+    }))
+}
+
+/// 창 생성 전 치명적 오류 안내 (네이티브 메시지 박스)
+fn show_startup_error(message: &str) {
+    eprintln!("{}", message);
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title("Orchestrator")
+        .set_description(message)
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let db = local_db::LocalDb::open().expect("로컬 DB 초기화 실패");
+    // 앱보다 새 스키마의 DB 등 열 수 없으면 안내 후 종료 (데이터 보호, 기존 파일은 건드리지 않음)
+    let db = match local_db::LocalDb::open() {
+        Ok(db) => Arc::new(db),
+        Err(e) => {
+            show_startup_error(&format!("로컬 DB를 열 수 없어 앱을 종료합니다.\n\n{}", e));
+            std::process::exit(1);
+        }
+    };
+
+    // 자격 증명 저장소 + v6 이전 평문 토큰 이전
+    let credentials = Arc::new(credential_store::Credentials::open());
+    match db.move_plaintext_credentials(&credentials) {
+        Ok(_) => {}
+        Err(e) if e == credential_store::LOCKED => {
+            log::warn!("🔒 평문 GitHub 토큰 이전 대기: credentials_unlock 필요");
+        }
+        Err(e) => log::error!("❌ 평문 GitHub 토큰 이전 실패: {}", e),
+    }
 
     // 저장된 watcher 경로 복원
     let mut initial_paths = HashMap::new();
@@ -515,7 +1052,7 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage({
             // Supabase 설정 로드 → SyncClient 생성
-            // 1) 환경변수 직접 체크 2) CWD/.env.local 3) exe 부모 디렉토리
+            // 1) 환경변수 직접 체크 2) CWD/.env.local 3) exe 부모 디렉토리 4) 빌드 시 임베드
             let env_url = std::env::var("VITE_SUPABASE_URL").ok();
             let env_key = std::env::var("VITE_SUPABASE_ANON_KEY").ok();
 
@@ -524,6 +1061,7 @@ pub fn run() {
                 Some(Arc::new(sync_client::SyncClient::new(
                     sync_client::SupabaseConfig { url, anon_key: key },
                     std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+                    db.clone(),
                 )))
             } else {
                 // .env.local / .env 파일 탐색
@@ -546,32 +1084,58 @@ pub fn run() {
                         None
                     });
                 
+                let result = match result {
+                    Some(config) => {
+                        log::info!("🔗 SyncClient (.env): {}", config.url);
+                        Some(config)
+                    }
+                    None => sync_client::embedded_supabase_config().inspect(|config| {
+                        log::info!("🔗 SyncClient (빌드 임베드): {}", config.url);
+                    }),
+                };
+
                 result.map(|config| {
-                    log::info!("🔗 SyncClient (.env): {}", config.url);
-                    Arc::new(sync_client::SyncClient::new(config, cwd.clone()))
+                    Arc::new(sync_client::SyncClient::new(config, cwd.clone(), db.clone()))
                 })
             };
 
             if sync.is_some() {
                 log::info!("✅ SyncClient 초기화 성공 → Supabase 이벤트 전송 활성화");
             } else {
-                log::warn!("⚠ SyncClient 초기화 실패 → .env.local에 VITE_SUPABASE_URL / VITE_SUPABASE_ANON_KEY 필요");
+                log::warn!("⚠ SyncClient 초기화 실패 → 빌드 또는 실행 환경에 VITE_SUPABASE_URL / VITE_SUPABASE_ANON_KEY 필요");
             }
             AppState {
-                watchers: Mutex::new(HashMap::new()),
+                watchers: Arc::new(watch_supervisor::WatchSupervisor::default()),
                 project_paths: Mutex::new(initial_paths),
                 watching_enabled: Mutex::new(true),
+                sync_engine: sync.as_ref().map(|sc| {
+                    Arc::new(sync_engine::SyncEngine::new(
+                        sc.config().clone(),
+                        db.clone(),
+                        sc.auth(),
+                    ))
+                }),
                 db,
+                credentials,
+                github: Arc::new(github_client::GithubClient::new(github_client::DEFAULT_API_URL)),
                 sync_client: sync.clone(),
             }
         })
         .invoke_handler(tauri::generate_handler![
-            start_oauth_server,
+            start_oauth_login,
             add_watch_project,
             remove_watch_project,
             toggle_watch_all,
             get_watch_status,
+            pause_watch_project,
+            resume_watch_project,
+            get_watcher_health,
             get_offline_changes,
+            session_start,
+            session_end,
+            session_handoff,
+            session_current,
+            session_history,
             resolve_local_paths,
             db_get_model_scores,
             db_upsert_model_score,
@@ -586,8 +1150,55 @@ pub fn run() {
             db_mark_synced,
             db_upsert_syncable,
             db_get_syncable,
+            db_soft_delete_work_item,
+            db_restore_work_item,
+            db_get_outbox_events,
+            sync_now,
+            set_auth_session,
+            clear_auth_session,
+            db_get_conflicts,
+            db_resolve_conflict,
+            db_set_conflict_policy,
+            db_requeue_outbox_events,
+            db_purge_outbox_events,
+            credentials_status,
+            credentials_unlock,
+            credentials_lock,
+            db_save_github_connection,
+            db_get_github_connections,
+            db_get_github_token,
+            db_delete_github_connection,
+            github_list_repos,
+            github_refresh_projects,
         ])
         .setup(|app| {
+            // ─── watcher supervisor (에러 시 재시작, 디바운스 정리) ───
+            {
+                let state = app.state::<AppState>();
+                state
+                    .watchers
+                    .clone()
+                    .spawn_monitor(app.handle().clone(), state.sync_client.clone());
+
+                // ─── 백그라운드 동기화 (연결 확인 + outbox 재전송 + 상태 emit) ───
+                if let (Some(sc), Some(engine)) = (&state.sync_client, &state.sync_engine) {
+                    // Rust에서 갱신한 토큰 → 프론트엔드 세션 교체 (refresh token 회전 공유)
+                    let handle = app.handle().clone();
+                    sc.auth().set_on_refresh(move |tokens| {
+                        if let Err(e) = handle.emit("orchx:auth-refreshed", tokens) {
+                            log::warn!("  ❌ Tauri emit 실패: {}", e);
+                        }
+                    });
+
+                    sync_worker::spawn(
+                        app.handle().clone(),
+                        sc.clone(),
+                        engine.clone(),
+                        state.db.clone(),
+                    );
+                }
+            }
+
             // ─── 시스템 트레이 ───
             let show_item = MenuItem::with_id(app, "show", "Orchestrator 열기", true, None::<&str>)?;
             let watch_item = MenuItem::with_id(app, "watch_toggle", "⏸ Watch 전체 중지", true, None::<&str>)?;
@@ -596,7 +1207,7 @@ pub fn run() {
 
             let icon = tauri::image::Image::from_bytes(include_bytes!("../icons/icon.png"))?;
 
-            TrayIconBuilder::new()
+            TrayIconBuilder::with_id(sync_worker::TRAY_ID)
                 .icon(icon)
                 .menu(&menu)
                 .tooltip("Orchestrator")
@@ -614,28 +1225,16 @@ pub fn run() {
 
                             if *enabled {
                                 // 전체 중지
-                                if let Ok(mut watchers) = state.watchers.lock() {
-                                    for (name, w) in watchers.iter() {
-                                        watcher::stop_watcher(w);
-
-                                    }
-                                    watchers.clear();
-                                }
+                                state.watchers.stop_all();
                                 *enabled = false;
                                 log::info!("트레이: 전체 Watch 중지");
                             } else {
                                 // 전체 시작
                                 if let Ok(paths) = state.project_paths.lock() {
-                                    if let Ok(mut watchers) = state.watchers.lock() {
-                                        for (name, path) in paths.iter() {
-                                            let sc = state.sync_client.clone();
-                                            match watcher::start_watcher(path.clone(), app.clone(), sc) {
-                                                Ok(ws) => {
-                                                    watchers.insert(name.clone(), ws);
-
-                                                }
-                                                Err(e) => log::error!("❌ {} 감시 실패: {}", name, e),
-                                            }
+                                    for (name, path) in paths.iter() {
+                                        let sc = state.sync_client.clone();
+                                        if let Err(e) = state.watchers.start(name, path.clone(), app, sc) {
+                                            log::error!("❌ {} 감시 실패: {}", name, e);
                                         }
                                     }
                                 }
@@ -648,11 +1247,7 @@ pub fn run() {
                             let state = app.state::<AppState>();
 
                             // 모든 watcher 중지
-                            if let Ok(watchers) = state.watchers.lock() {
-                                for (_, w) in watchers.iter() {
-                                    watcher::stop_watcher(w);
-                                }
-                            }
+                            state.watchers.stop_all();
 
                             // 모든 프로젝트에 shutdown timestamp 저장
                             if let Ok(paths) = state.project_paths.lock() {
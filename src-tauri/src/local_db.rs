@@ -3,10 +3,79 @@
 // ~/.orchestrator/local.db 에 사용자 데이터 저장
 // ============================================
 
-use rusqlite::{Connection, Result as SqliteResult, params};
+use rusqlite::{Connection, OptionalExtension, Result as SqliteResult, params};
+use serde::Serialize;
+use crate::models::{
+    id_or_new, invalid, parse_timestamp, DbResult, EditorModels, ModelScore, Project, SyncRecord, Validate,
+    WorkItem,
+};
 use std::path::PathBuf;
 use std::sync::Mutex;
 
+/// outbox 최대 전송 시도 횟수 (초과 시 dead)
+pub const OUTBOX_MAX_ATTEMPTS: u32 = 8;
+/// 재시도 대기 기본값/상한 (초)
+const OUTBOX_BACKOFF_BASE_SECS: i64 = 30;
+const OUTBOX_BACKOFF_MAX_SECS: i64 = 3600;
+/// 전송 중 이벤트 유예 (이 시간 안에 결과가 기록되지 않으면 재시도 대상)
+const OUTBOX_IN_FLIGHT_SECS: i64 = 60;
+
+/// cli_events outbox 행
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxEvent {
+    pub event_id: String,
+    pub event_type: String,
+    pub payload: serde_json::Value,
+    pub session_id: Option<String>,
+    /// 기록 시점 로그인 사용자 (None = 로그아웃 중 기록, 자동 전송 대상 아님)
+    pub user_id: Option<String>,
+    pub status: String,
+    pub attempts: u32,
+    pub next_attempt_at: String,
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
+/// 원격에 없는 로컬 전용 컬럼 (push payload에서 제외)
+const LOCAL_ONLY_COLUMNS: &[&str] = &["sync_status", "remote_updated_at"];
+
+/// 동기화 충돌 기록
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncConflict {
+    pub id: i64,
+    pub table_name: String,
+    pub record_id: String,
+    pub policy: String,
+    pub local: serde_json::Value,
+    pub remote: serde_json::Value,
+    /// None = 미해결, local | remote | merged
+    pub resolution: Option<String>,
+    pub detected_at: String,
+    pub resolved_at: Option<String>,
+}
+
+/// GitHub 연결 (토큰은 credential_store 참조만 저장)
+#[derive(Debug, Clone, Serialize)]
+pub struct GithubConnection {
+    pub id: String,
+    pub github_username: Option<String>,
+    pub access_token_ref: String,
+    pub refresh_token_ref: Option<String>,
+    pub token_expires_at: Option<String>,
+    pub connected_at: String,
+    pub updated_at: String,
+}
+
+/// 원격 행 반영 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemoteApply {
+    Applied,
+    /// 로컬 미전송 변경이 있고 원격은 기준 버전 그대로 → 로컬 유지
+    Skipped,
+    /// 로컬 미전송 변경 + 원격도 기준 버전 이후 변경됨
+    Conflict,
+}
+
 /// 로컬 DB 경로: ~/.orchestrator/local.db
 fn db_path() -> PathBuf {
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
@@ -22,11 +91,15 @@ pub struct LocalDb {
 
 impl LocalDb {
     /// DB 열기 + 스키마 마이그레이션
-    pub fn open() -> SqliteResult<Self> {
+    pub fn open() -> DbResult<Self> {
         let path = db_path();
         log::info!("📦 로컬 DB 경로: {}", path.display());
+        Self::open_at(&path)
+    }
 
-        let conn = Connection::open(&path)?;
+    /// 지정 경로의 DB 열기 + 스키마 마이그레이션
+    pub fn open_at(path: &std::path::Path) -> DbResult<Self> {
+        let conn = Connection::open(path)?;
 
         // WAL 모드 (성능 향상)
         conn.execute_batch("PRAGMA journal_mode=WAL;")?;
@@ -39,191 +112,11 @@ impl LocalDb {
         Ok(db)
     }
 
-    /// 스키마 마이그레이션 (idempotent)
-    fn migrate(&self) -> SqliteResult<()> {
-        let conn = self.conn.lock().map_err(|e| {
-            rusqlite::Error::ToSqlConversionFailure(Box::new(
-                std::io::Error::new(std::io::ErrorKind::Other, e.to_string()),
-            ))
-        })?;
-
-        conn.execute_batch("
-            -- 버전 관리
-            CREATE TABLE IF NOT EXISTS schema_version (
-                version INTEGER PRIMARY KEY,
-                applied_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-
-            -- 프로젝트 (Supabase projects → 로컬)
-            CREATE TABLE IF NOT EXISTS projects (
-                id TEXT PRIMARY KEY,
-                repo_id INTEGER NOT NULL,
-                repo_name TEXT NOT NULL,
-                repo_full_name TEXT NOT NULL UNIQUE,
-                repo_url TEXT NOT NULL,
-                description TEXT,
-                default_branch TEXT NOT NULL DEFAULT 'main',
-                language TEXT,
-                is_private INTEGER NOT NULL DEFAULT 0,
-                status TEXT NOT NULL DEFAULT 'active',
-                metadata TEXT NOT NULL DEFAULT '{}',
-                created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-
-            -- GitHub 연결 정보
-            CREATE TABLE IF NOT EXISTS github_connections (
-                id TEXT PRIMARY KEY,
-                github_username TEXT,
-                access_token TEXT NOT NULL,
-                refresh_token TEXT,
-                token_expires_at TEXT,
-                connected_at TEXT NOT NULL DEFAULT (datetime('now')),
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-
-            -- AI 모델 점수 커스텀
-            CREATE TABLE IF NOT EXISTS model_scores (
-                id TEXT PRIMARY KEY,
-                model_key TEXT NOT NULL UNIQUE,
-                coding REAL NOT NULL DEFAULT 50,
-                analysis REAL NOT NULL DEFAULT 50,
-                documentation REAL NOT NULL DEFAULT 50,
-                speed REAL NOT NULL DEFAULT 50,
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-
-            -- 에디터별 지원 모델
-            CREATE TABLE IF NOT EXISTS editor_models (
-                id TEXT PRIMARY KEY,
-                editor_type TEXT NOT NULL UNIQUE,
-                supported_models TEXT NOT NULL DEFAULT '[]',
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-
-            -- 프로젝트 데드라인
-            CREATE TABLE IF NOT EXISTS project_deadlines (
-                id TEXT PRIMARY KEY,
-                project_id TEXT NOT NULL,
-                milestone TEXT NOT NULL,
-                deadline_at TEXT NOT NULL,
-                risk_score REAL NOT NULL DEFAULT 0,
-                created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
-            );
-
-            -- 고정 이벤트/일정
-            CREATE TABLE IF NOT EXISTS fixed_events (
-                id TEXT PRIMARY KEY,
-                title TEXT NOT NULL,
-                start_at TEXT NOT NULL,
-                end_at TEXT NOT NULL,
-                importance TEXT NOT NULL DEFAULT 'medium',
-                created_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-
-            -- Watcher 경로 매핑 (현재 메모리에만 있던 것)
-            CREATE TABLE IF NOT EXISTS watcher_paths (
-                repo_full_name TEXT PRIMARY KEY,
-                local_path TEXT NOT NULL,
-                watching INTEGER NOT NULL DEFAULT 1,
-                created_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-
-            -- 사용자 설정 (동의 상태 등)
-            CREATE TABLE IF NOT EXISTS user_preferences (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL,
-                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-            );
-
-            -- 스키마 버전 기록
-            INSERT OR IGNORE INTO schema_version (version) VALUES (1);
-        ")?;
-
-        // ─── v2: 동기화 대상 테이블 ───
-        let v2_applied: bool = conn.query_row(
-            "SELECT COUNT(*) > 0 FROM schema_version WHERE version = 2",
-            [], |row| row.get(0),
-        ).unwrap_or(false);
-
-        if !v2_applied {
-            conn.execute_batch("
-                -- 작업 항목 (동기화 대상)
-                CREATE TABLE IF NOT EXISTS work_items (
-                    id TEXT PRIMARY KEY,
-                    title TEXT NOT NULL,
-                    status TEXT NOT NULL DEFAULT 'open',
-                    priority TEXT NOT NULL DEFAULT 'medium',
-                    project_id TEXT,
-                    description TEXT,
-                    due_at TEXT,
-                    metadata TEXT NOT NULL DEFAULT '{}',
-                    sync_status TEXT NOT NULL DEFAULT 'pending',
-                    created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-                );
-
-                -- 플랜 (동기화 대상)
-                CREATE TABLE IF NOT EXISTS plans (
-                    id TEXT PRIMARY KEY,
-                    title TEXT NOT NULL,
-                    plan_type TEXT NOT NULL DEFAULT 'task',
-                    status TEXT NOT NULL DEFAULT 'open',
-                    priority TEXT NOT NULL DEFAULT 'medium',
-                    description TEXT,
-                    due_at TEXT,
-                    start_at TEXT,
-                    metadata TEXT NOT NULL DEFAULT '{}',
-                    sync_status TEXT NOT NULL DEFAULT 'pending',
-                    created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-                );
-
-                -- 목표 (동기화 대상)
-                CREATE TABLE IF NOT EXISTS goals (
-                    id TEXT PRIMARY KEY,
-                    title TEXT NOT NULL,
-                    plan_id TEXT,
-                    status TEXT NOT NULL DEFAULT 'open',
-                    progress REAL NOT NULL DEFAULT 0,
-                    metadata TEXT NOT NULL DEFAULT '{}',
-                    sync_status TEXT NOT NULL DEFAULT 'pending',
-                    created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
-                );
-
-                -- 세션 로그 (동기화 대상)
-                CREATE TABLE IF NOT EXISTS session_logs (
-                    id TEXT PRIMARY KEY,
-                    project_id TEXT,
-                    editor_type TEXT,
-                    started_at TEXT NOT NULL DEFAULT (datetime('now')),
-                    ended_at TEXT,
-                    duration_min INTEGER,
-                    summary TEXT,
-                    metadata TEXT NOT NULL DEFAULT '{}',
-                    sync_status TEXT NOT NULL DEFAULT 'pending',
-                    created_at TEXT NOT NULL DEFAULT (datetime('now'))
-                );
-
-                -- 동기화 큐 (오프라인 변경 추적)
-                CREATE TABLE IF NOT EXISTS sync_queue (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    table_name TEXT NOT NULL,
-                    record_id TEXT NOT NULL,
-                    operation TEXT NOT NULL,
-                    payload TEXT NOT NULL DEFAULT '{}',
-                    created_at TEXT NOT NULL DEFAULT (datetime('now')),
-                    synced INTEGER NOT NULL DEFAULT 0
-                );
-
-                INSERT INTO schema_version (version) VALUES (2);
-            ")?;
-            log::info!("✅ v2 마이그레이션: 동기화 대상 테이블 5개 추가");
-        }
-
-        log::info!("✅ 로컬 DB 스키마 마이그레이션 완료");
+    /// 스키마 마이그레이션 (db_migrations 레지스트리의 미적용 버전)
+    fn migrate(&self) -> DbResult<()> {
+        let mut conn = self.lock_conn()?;
+        crate::db_migrations::run(&mut conn)?;
+        log::info!("✅ 로컬 DB 스키마 마이그레이션 완료 (v{})", crate::db_migrations::latest_version());
         Ok(())
     }
 }
@@ -330,37 +223,42 @@ impl LocalDb {
 // ─── CRUD: model_scores ───
 
 impl LocalDb {
-    pub fn get_all_model_scores(&self) -> SqliteResult<Vec<serde_json::Value>> {
+    pub fn get_all_model_scores(&self) -> DbResult<Vec<ModelScore>> {
         let conn = self.lock_conn()?;
         let mut stmt = conn.prepare(
             "SELECT id, model_key, coding, analysis, documentation, speed, updated_at FROM model_scores"
         )?;
         let rows = stmt.query_map([], |row| {
-            Ok(serde_json::json!({
-                "id": row.get::<_, String>(0)?,
-                "model_key": row.get::<_, String>(1)?,
-                "coding": row.get::<_, f64>(2)?,
-                "analysis": row.get::<_, f64>(3)?,
-                "documentation": row.get::<_, f64>(4)?,
-                "speed": row.get::<_, f64>(5)?,
-                "updated_at": row.get::<_, String>(6)?,
-            }))
+            Ok(ModelScore {
+                id: row.get(0)?,
+                model_key: row.get(1)?,
+                coding: row.get(2)?,
+                analysis: row.get(3)?,
+                documentation: row.get(4)?,
+                speed: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
         })?;
-        rows.collect()
+        Ok(rows.collect::<SqliteResult<_>>()?)
     }
 
-    pub fn upsert_model_score(
-        &self, model_key: &str, coding: f64, analysis: f64, documentation: f64, speed: f64,
-    ) -> SqliteResult<()> {
+    pub fn upsert_model_score(&self, score: &ModelScore) -> DbResult<()> {
+        score.validate()?;
         let conn = self.lock_conn()?;
-        let id = uuid::Uuid::new_v4().to_string();
         conn.execute(
             "INSERT INTO model_scores (id, model_key, coding, analysis, documentation, speed)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6)
              ON CONFLICT(model_key) DO UPDATE SET
                coding = ?3, analysis = ?4, documentation = ?5, speed = ?6,
                updated_at = datetime('now')",
-            params![id, model_key, coding, analysis, documentation, speed],
+            params![
+                id_or_new(&score.id),
+                score.model_key,
+                score.coding,
+                score.analysis,
+                score.documentation,
+                score.speed,
+            ],
         )?;
         Ok(())
     }
@@ -369,37 +267,33 @@ impl LocalDb {
 // ─── CRUD: editor_models ───
 
 impl LocalDb {
-    pub fn get_all_editor_models(&self) -> SqliteResult<Vec<serde_json::Value>> {
+    pub fn get_all_editor_models(&self) -> DbResult<Vec<EditorModels>> {
         let conn = self.lock_conn()?;
         let mut stmt = conn.prepare(
             "SELECT id, editor_type, supported_models, updated_at FROM editor_models"
         )?;
         let rows = stmt.query_map([], |row| {
             let models_str = row.get::<_, String>(2)?;
-            let models: serde_json::Value = serde_json::from_str(&models_str)
-                .unwrap_or(serde_json::json!([]));
-            Ok(serde_json::json!({
-                "id": row.get::<_, String>(0)?,
-                "editor_type": row.get::<_, String>(1)?,
-                "supported_models": models,
-                "updated_at": row.get::<_, String>(3)?,
-            }))
+            Ok(EditorModels {
+                id: row.get(0)?,
+                editor_type: row.get(1)?,
+                supported_models: serde_json::from_str(&models_str).unwrap_or_default(),
+                updated_at: row.get(3)?,
+            })
         })?;
-        rows.collect()
+        Ok(rows.collect::<SqliteResult<_>>()?)
     }
 
-    pub fn upsert_editor_models(
-        &self, editor_type: &str, supported_models: &[String],
-    ) -> SqliteResult<()> {
+    pub fn upsert_editor_models(&self, models: &EditorModels) -> DbResult<()> {
+        models.validate()?;
         let conn = self.lock_conn()?;
-        let id = uuid::Uuid::new_v4().to_string();
-        let models_json = serde_json::to_string(supported_models).unwrap_or_else(|_| "[]".to_string());
+        let models_json = serde_json::to_string(&models.supported_models).unwrap_or_else(|_| "[]".to_string());
         conn.execute(
             "INSERT INTO editor_models (id, editor_type, supported_models)
              VALUES (?1, ?2, ?3)
              ON CONFLICT(editor_type) DO UPDATE SET
                supported_models = ?3, updated_at = datetime('now')",
-            params![id, editor_type, models_json],
+            params![id_or_new(&models.id), models.editor_type, models_json],
         )?;
         Ok(())
     }
@@ -408,7 +302,7 @@ impl LocalDb {
 // ─── CRUD: projects ───
 
 impl LocalDb {
-    pub fn get_all_projects(&self) -> SqliteResult<Vec<serde_json::Value>> {
+    pub fn get_all_projects(&self) -> DbResult<Vec<Project>> {
         let conn = self.lock_conn()?;
         let mut stmt = conn.prepare(
             "SELECT id, repo_id, repo_name, repo_full_name, repo_url, description,
@@ -417,55 +311,57 @@ impl LocalDb {
         )?;
         let rows = stmt.query_map([], |row| {
             let metadata_str = row.get::<_, String>(10)?;
-            let metadata: serde_json::Value = serde_json::from_str(&metadata_str)
-                .unwrap_or(serde_json::json!({}));
-            Ok(serde_json::json!({
-                "id": row.get::<_, String>(0)?,
-                "repo_id": row.get::<_, i64>(1)?,
-                "repo_name": row.get::<_, String>(2)?,
-                "repo_full_name": row.get::<_, String>(3)?,
-                "repo_url": row.get::<_, String>(4)?,
-                "description": row.get::<_, Option<String>>(5)?,
-                "default_branch": row.get::<_, String>(6)?,
-                "language": row.get::<_, Option<String>>(7)?,
-                "is_private": row.get::<_, bool>(8)?,
-                "status": row.get::<_, String>(9)?,
-                "metadata": metadata,
-                "created_at": row.get::<_, String>(11)?,
-                "updated_at": row.get::<_, String>(12)?,
-            }))
+            Ok(Project {
+                id: row.get(0)?,
+                repo_id: row.get(1)?,
+                repo_name: row.get(2)?,
+                repo_full_name: row.get(3)?,
+                repo_url: row.get(4)?,
+                description: row.get(5)?,
+                default_branch: row.get(6)?,
+                language: row.get(7)?,
+                is_private: row.get(8)?,
+                status: row.get(9)?,
+                metadata: serde_json::from_str(&metadata_str).unwrap_or(serde_json::json!({})),
+                created_at: row.get(11)?,
+                updated_at: row.get(12)?,
+            })
         })?;
-        rows.collect()
+        Ok(rows.collect::<SqliteResult<_>>()?)
     }
 
-    pub fn upsert_project(&self, project: &serde_json::Value) -> SqliteResult<()> {
+    /// 프로젝트 저장 (repo_full_name 기준 upsert) → 저장된 ID
+    pub fn upsert_project(&self, project: &Project) -> DbResult<String> {
+        project.validate()?;
         let conn = self.lock_conn()?;
-        let id = project["id"].as_str().unwrap_or(&uuid::Uuid::new_v4().to_string()).to_string();
-        let metadata = serde_json::to_string(&project["metadata"]).unwrap_or_else(|_| "{}".to_string());
+        let id = id_or_new(&project.id);
+        let metadata = project.metadata.to_string();
 
-        conn.execute(
+        let saved_id: String = conn.query_row(
             "INSERT INTO projects (id, repo_id, repo_name, repo_full_name, repo_url, description,
                                    default_branch, language, is_private, status, metadata)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
              ON CONFLICT(repo_full_name) DO UPDATE SET
                description = ?6, default_branch = ?7, language = ?8,
                is_private = ?9, status = ?10, metadata = ?11,
-               updated_at = datetime('now')",
+               updated_at = datetime('now')
+             RETURNING id",
             params![
                 id,
-                project["repo_id"].as_i64().unwrap_or(0),
-                project["repo_name"].as_str().unwrap_or(""),
-                project["repo_full_name"].as_str().unwrap_or(""),
-                project["repo_url"].as_str().unwrap_or(""),
-                project["description"].as_str(),
-                project["default_branch"].as_str().unwrap_or("main"),
-                project["language"].as_str(),
-                project["is_private"].as_bool().unwrap_or(false),
-                project["status"].as_str().unwrap_or("active"),
+                project.repo_id,
+                project.repo_name,
+                project.repo_full_name,
+                project.repo_url,
+                project.description,
+                project.default_branch,
+                project.language,
+                project.is_private,
+                project.status,
                 metadata,
             ],
+            |row| row.get(0),
         )?;
-        Ok(())
+        Ok(saved_id)
     }
 
     pub fn delete_project(&self, id: &str) -> SqliteResult<()> {
@@ -473,6 +369,178 @@ impl LocalDb {
         conn.execute("DELETE FROM projects WHERE id = ?1", params![id])?;
         Ok(())
     }
+
+    /// GitHub 레포 메타데이터 반영 (repo_full_name이 바뀌면 watcher_paths도 함께 이동,
+    /// worktree 감시 키 "owner/repo@이름" 포함)
+    pub fn apply_github_repo(
+        &self,
+        id: &str,
+        repo: &crate::github_client::GithubRepo,
+    ) -> SqliteResult<()> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        let previous: String = tx.query_row(
+            "SELECT repo_full_name FROM projects WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            "UPDATE projects SET
+               repo_id = ?2, repo_name = ?3, repo_full_name = ?4, repo_url = ?5,
+               description = ?6, default_branch = ?7, language = ?8, is_private = ?9,
+               updated_at = datetime('now')
+             WHERE id = ?1",
+            params![
+                id,
+                repo.id,
+                repo.name,
+                repo.full_name,
+                repo.html_url,
+                repo.description,
+                repo.default_branch,
+                repo.language,
+                repo.private,
+            ],
+        )?;
+        if previous != repo.full_name {
+            tx.execute(
+                "UPDATE watcher_paths SET repo_full_name = ?2 || substr(repo_full_name, length(?1) + 1)
+                 WHERE repo_full_name = ?1 OR substr(repo_full_name, 1, length(?1) + 1) = ?1 || '@'",
+                params![previous, repo.full_name],
+            )?;
+        }
+        tx.commit()
+    }
+}
+
+// ─── CRUD: github_connections (토큰 참조) ───
+
+impl LocalDb {
+    pub fn upsert_github_connection(
+        &self,
+        id: &str,
+        github_username: Option<&str>,
+        access_token_ref: &str,
+        refresh_token_ref: Option<&str>,
+        token_expires_at: Option<&str>,
+    ) -> SqliteResult<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT INTO github_connections (id, github_username, access_token_ref, refresh_token_ref, token_expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(id) DO UPDATE SET
+               github_username = ?2, access_token_ref = ?3, refresh_token_ref = ?4,
+               token_expires_at = ?5, updated_at = datetime('now')",
+            params![id, github_username, access_token_ref, refresh_token_ref, token_expires_at],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_github_connections(&self) -> SqliteResult<Vec<GithubConnection>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, github_username, access_token_ref, refresh_token_ref, token_expires_at,
+                    connected_at, updated_at
+             FROM github_connections ORDER BY connected_at DESC"
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(GithubConnection {
+                id: row.get(0)?,
+                github_username: row.get(1)?,
+                access_token_ref: row.get(2)?,
+                refresh_token_ref: row.get(3)?,
+                token_expires_at: row.get(4)?,
+                connected_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// 연결 삭제 → 삭제된 행 (저장소 항목 정리용)
+    pub fn delete_github_connection(&self, id: &str) -> SqliteResult<Option<GithubConnection>> {
+        let existing = self.get_github_connections()?.into_iter().find(|c| c.id == id);
+        if existing.is_some() {
+            let conn = self.lock_conn()?;
+            conn.execute("DELETE FROM github_connections WHERE id = ?1", params![id])?;
+        }
+        Ok(existing)
+    }
+
+    /// v6 이전 평문 토큰 → 자격 증명 저장소 이전 후 평문 행 삭제 (옮긴 건수)
+    /// 저장소가 잠겨 있으면 LOCKED 에러 → 잠금 해제 후 다시 호출
+    pub fn move_plaintext_credentials(
+        &self,
+        credentials: &crate::credential_store::Credentials,
+    ) -> Result<usize, String> {
+        // (id, github_username, access_token, refresh_token, token_expires_at)
+        type LegacyRow = (String, Option<String>, String, Option<String>, Option<String>);
+        let legacy: Vec<LegacyRow> = {
+            let conn = self.lock_conn().map_err(|e| e.to_string())?;
+            let exists: bool = conn
+                .query_row(
+                    "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'github_connections_legacy'",
+                    [],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            if !exists {
+                // 이전은 끝났어도 지난 실행에서 백업 정리가 실패했을 수 있음
+                crate::db_migrations::remove_plaintext_backups(&conn);
+                return Ok(0);
+            }
+            let mut stmt = conn
+                .prepare(
+                    "SELECT id, github_username, access_token, refresh_token, token_expires_at
+                     FROM github_connections_legacy",
+                )
+                .map_err(|e| e.to_string())?;
+            let rows = stmt
+                .query_map([], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
+                })
+                .map_err(|e| e.to_string())?;
+            rows.collect::<SqliteResult<_>>().map_err(|e| e.to_string())?
+        };
+
+        // 저장소 호출(키링 IPC) 동안 DB 잠금을 잡지 않음
+        let mut moved = Vec::new();
+        for (id, username, access_token, refresh_token, expires_at) in &legacy {
+            let access_ref = credentials.put(&format!("github:{}:access", id), access_token)?;
+            let refresh_ref = match refresh_token.as_deref().filter(|t| !t.is_empty()) {
+                Some(token) => Some(credentials.put(&format!("github:{}:refresh", id), token)?),
+                None => None,
+            };
+            moved.push((id, username, access_ref, refresh_ref, expires_at));
+        }
+
+        let mut conn = self.lock_conn().map_err(|e| e.to_string())?;
+        // 삭제된 평문이 빈 페이지/WAL에 남지 않도록
+        conn.execute_batch("PRAGMA secure_delete = ON;")
+            .map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        for (id, username, access_ref, refresh_ref, expires_at) in &moved {
+            tx.execute(
+                "INSERT INTO github_connections (id, github_username, access_token_ref, refresh_token_ref, token_expires_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT(id) DO NOTHING",
+                params![id, username, access_ref, refresh_ref, expires_at],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.execute_batch("DROP TABLE github_connections_legacy;")
+            .map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+        // 마이그레이션 전 백업에도 평문 토큰이 남아 있음 (v6 이후 백업은 legacy 테이블)
+        crate::db_migrations::remove_plaintext_backups(&conn);
+        conn.execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
+            .map_err(|e| e.to_string())?;
+
+        if !moved.is_empty() {
+            log::info!("🔐 github_connections 평문 토큰 {}건 → 자격 증명 저장소", moved.len());
+        }
+        Ok(moved.len())
+    }
 }
 
 // ─── CRUD: sync_queue ───
@@ -530,110 +598,786 @@ impl LocalDb {
         Ok(())
     }
 
-    /// 범용 JSON upsert (work_items, plans, goals, session_logs)
-    pub fn upsert_syncable(
-        &self, table_name: &str, record: &serde_json::Value,
-    ) -> SqliteResult<String> {
-        let id = record["id"].as_str()
-            .unwrap_or(&uuid::Uuid::new_v4().to_string())
-            .to_string();
-        let json_str = serde_json::to_string(record).unwrap_or_else(|_| "{}".to_string());
-
-        // 주요 필드 추출
-        let title = record["title"].as_str().unwrap_or("Untitled");
-        let status = record["status"].as_str().unwrap_or("open");
-        let plan_type = record["plan_type"].as_str();
-        let priority = record["priority"].as_str();
-        let description = record["description"].as_str();
-        let due_at = record["due_at"].as_str();
-
-        // 1) 기존 레코드가 있는지 확인
+    /// 동기화 미동의 중 큐 비우기, 삭제 행 수 반환
+    /// (레코드는 sync_status = 'pending' 유지 → 동의 후 requeue_pending_records로 재구성)
+    pub fn clear_sync_queue(&self) -> SqliteResult<usize> {
         let conn = self.lock_conn()?;
-        let exists: bool = conn.query_row(
-            &format!("SELECT COUNT(*) > 0 FROM {} WHERE id = ?1", table_name),
-            params![id],
-            |row| row.get(0),
-        ).unwrap_or(false);
+        conn.execute("DELETE FROM sync_queue", [])
+    }
 
-        if exists {
-            // UPDATE: 주요 컬럼 + metadata 모두 업데이트
-            conn.execute(
-                &format!(
-                    "UPDATE {} SET title = ?2, status = ?3, metadata = json_patch(metadata, ?4), sync_status = 'pending', updated_at = datetime('now') WHERE id = ?1",
-                    table_name
-                ),
-                params![id, title, status, json_str],
-            )?;
-            // plan_type 등 선택적 컬럼 업데이트
-            if let Some(pt) = plan_type {
-                let _ = conn.execute(
-                    &format!("UPDATE {} SET plan_type = ?2 WHERE id = ?1", table_name),
-                    params![id, pt],
-                );
-            }
-            if let Some(p) = priority {
-                let _ = conn.execute(
-                    &format!("UPDATE {} SET priority = ?2 WHERE id = ?1", table_name),
-                    params![id, p],
-                );
+    /// 큐에 미전송 항목이 없는 pending 레코드 재등록, 추가 건수 반환
+    pub fn requeue_pending_records(&self, tables: &[&str]) -> SqliteResult<usize> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        let mut added = 0;
+        for table_name in tables {
+            let ids: Vec<String> = {
+                let mut stmt = tx.prepare(&format!(
+                    "SELECT id FROM {} WHERE sync_status = 'pending' AND NOT EXISTS (
+                       SELECT 1 FROM sync_queue
+                       WHERE table_name = ?1 AND record_id = {}.id AND synced = 0)
+                     ORDER BY rowid ASC",
+                    table_name, table_name
+                ))?;
+                let rows = stmt.query_map(params![table_name], |row| row.get(0))?;
+                rows.collect::<SqliteResult<_>>()?
+            };
+            for id in ids {
+                if let Some(current) = record_json(&tx, table_name, &id)? {
+                    tx.execute(
+                        "INSERT INTO sync_queue (table_name, record_id, operation, payload) VALUES (?1, ?2, 'update', ?3)",
+                        params![table_name, id, strip_local_columns(&current).to_string()],
+                    )?;
+                    added += 1;
+                }
             }
-            if let Some(d) = description {
-                let _ = conn.execute(
-                    &format!("UPDATE {} SET description = ?2 WHERE id = ?1", table_name),
-                    params![id, d],
-                );
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// 동기화 대상 레코드 upsert (work_items, plans, goals, session_logs)
+    /// 한 트랜잭션에서 행 + sync_queue 기록
+    /// - 삽입: 값이 있는 컬럼만 (나머지는 스키마 기본값)
+    /// - 수정: 입력에 있는 컬럼만 갱신 (null → NULL로 비움), metadata(plans/session_logs)는 병합
+    /// - 저장될 행 전체로 필수 값/상위 관계 검증, 실패 시 롤백
+    /// - work_items: 상태 전환 타임스탬프(started_at/completed_at/actual_min) 자동 기록
+    pub fn upsert_syncable(&self, record: &SyncRecord) -> DbResult<String> {
+        let table_name = record.table_name();
+        let id = id_or_new(record.id());
+
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        let stored = stored_record(&tx, table_name, &id)?;
+
+        let mut record = record.clone();
+        record.set_id(id.clone());
+        if let SyncRecord::WorkItem(item) = &mut record {
+            let stored = match &stored {
+                Some(SyncRecord::WorkItem(stored)) => Some(stored),
+                _ => None,
+            };
+            apply_lifecycle(item, stored, chrono::Utc::now());
+        }
+        // 입력에 있는 컬럼만 (삽입 시 null은 스키마 기본값에 맡김)
+        let metadata = record.metadata().map(|m| match m {
+            serde_json::Value::Object(m) => serde_json::Value::Object(m.clone()).to_string(),
+            _ => "{}".to_string(),
+        });
+        let present: Vec<(&str, rusqlite::types::Value)> = record
+            .columns()
+            .into_iter()
+            .filter_map(|(column, value)| match value {
+                Some(rusqlite::types::Value::Null) if stored.is_none() => None,
+                Some(value) => Some((column, value)),
+                None => None,
+            })
+            .collect();
+        // ?1 = id, (?2 = metadata), 이후 입력 컬럼
+        let mut values: Vec<rusqlite::types::Value> = vec![id.clone().into()];
+        values.extend(metadata.map(rusqlite::types::Value::from));
+        let first = values.len() + 1;
+        values.extend(present.iter().map(|(_, v)| v.clone()));
+
+        if stored.is_some() {
+            let mut sets: Vec<String> = present
+                .iter()
+                .enumerate()
+                .map(|(i, (c, _))| format!("{} = ?{}", c, i + first))
+                .collect();
+            if first > 2 {
+                sets.push("metadata = json_patch(metadata, ?2)".to_string());
             }
-            if let Some(da) = due_at {
-                let _ = conn.execute(
-                    &format!("UPDATE {} SET due_at = ?2 WHERE id = ?1", table_name),
-                    params![id, da],
-                );
+            // session_logs에는 updated_at 없음
+            if table_name != "session_logs" {
+                sets.push("updated_at = datetime('now')".to_string());
             }
+            sets.push("sync_status = 'pending'".to_string());
+            tx.execute(
+                &format!("UPDATE {} SET {} WHERE id = ?1", table_name, sets.join(", ")),
+                rusqlite::params_from_iter(values),
+            )?;
         } else {
-            // 삽입: metadata에 전체 JSON 저장 (스키마 유연성)
-            conn.execute(
+            let mut names = vec!["id"];
+            if first > 2 {
+                names.push("metadata");
+            }
+            names.extend(present.iter().map(|(c, _)| *c));
+            let placeholders: Vec<String> = (1..=names.len()).map(|i| format!("?{}", i)).collect();
+            tx.execute(
                 &format!(
-                    "INSERT INTO {} (id, title, status, metadata, sync_status) VALUES (?1, ?2, ?3, ?4, 'pending')",
-                    table_name
+                    "INSERT INTO {} ({}, sync_status) VALUES ({}, 'pending')",
+                    table_name, names.join(", "), placeholders.join(", ")
                 ),
-                params![id, title, status, json_str],
+                rusqlite::params_from_iter(values),
             )?;
         }
 
-        // 2) sync_queue에도 추가
-        self.enqueue_sync(table_name, &id, if exists { "update" } else { "insert" }, &json_str)?;
+        // 병합 결과(기본값 포함) 검증 + 전체 행을 push payload로
+        let Some(current) = stored_record(&tx, table_name, &id)? else {
+            return Err(invalid("id", format!("저장된 행 없음: {}", id)));
+        };
+        current.validate_row()?;
+        let payload = record_json(&tx, table_name, &id)?.unwrap_or_default();
 
+        let operation = if stored.is_some() { "update" } else { "insert" };
+        tx.execute(
+            "INSERT INTO sync_queue (table_name, record_id, operation, payload) VALUES (?1, ?2, ?3, ?4)",
+            params![table_name, id, operation, strip_local_columns(&payload).to_string()],
+        )?;
+        tx.commit()?;
         Ok(id)
     }
 
-    /// 범용 syncable 테이블 전체 조회
-    pub fn get_all_syncable(&self, table_name: &str) -> SqliteResult<Vec<serde_json::Value>> {
+    /// work_item soft delete (deleted_at 기록) → 변경을 sync_queue로, 해당 항목이 없으면 false
+    pub fn soft_delete_work_item(&self, id: &str) -> SqliteResult<bool> {
+        self.set_work_item_deleted_at(id, Some(&chrono::Utc::now().to_rfc3339()))
+    }
+
+    /// soft delete 취소 (deleted_at 제거)
+    pub fn restore_work_item(&self, id: &str) -> SqliteResult<bool> {
+        self.set_work_item_deleted_at(id, None)
+    }
+
+    fn set_work_item_deleted_at(&self, id: &str, deleted_at: Option<&str>) -> SqliteResult<bool> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        let changed = tx.execute(
+            "UPDATE work_items SET deleted_at = ?2, sync_status = 'pending', updated_at = datetime('now') WHERE id = ?1",
+            params![id, deleted_at],
+        )?;
+        if changed == 0 {
+            return Ok(false);
+        }
+        if let Some(current) = record_json(&tx, "work_items", id)? {
+            tx.execute(
+                "INSERT INTO sync_queue (table_name, record_id, operation, payload) VALUES ('work_items', ?1, 'update', ?2)",
+                params![id, strip_local_columns(&current).to_string()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    /// 동기화 대상 테이블 전체 조회 (테이블별 타입)
+    /// soft delete된 work_items는 include_deleted일 때만
+    pub fn get_all_syncable(&self, table_name: &str, include_deleted: bool) -> DbResult<Vec<SyncRecord>> {
+        let conn = self.lock_conn()?;
+        let order = if table_name == "session_logs" { "started_at" } else { "created_at" };
+        let filter = if table_name == "work_items" && !include_deleted { "WHERE deleted_at IS NULL " } else { "" };
+        let mut stmt = conn.prepare(&format!("SELECT id FROM {} {}ORDER BY {} DESC", table_name, filter, order))?;
+        let ids: Vec<String> = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<SqliteResult<_>>()?;
+
+        let mut records = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(record) = stored_record(&conn, table_name, &id)? {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+}
+
+// ─── 동기화 엔진: 원격 반영 / high-water mark ───
+
+impl LocalDb {
+    /// 테이블별 pull high-water mark
+    pub fn get_sync_cursor(&self, table_name: &str) -> SqliteResult<Option<String>> {
+        let conn = self.lock_conn()?;
+        let result = conn.query_row(
+            "SELECT cursor FROM sync_cursors WHERE table_name = ?1",
+            params![table_name],
+            |row| row.get::<_, String>(0),
+        );
+        match result {
+            Ok(v) => Ok(Some(v)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn set_sync_cursor(&self, table_name: &str, cursor: &str) -> SqliteResult<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT INTO sync_cursors (table_name, cursor) VALUES (?1, ?2)
+             ON CONFLICT(table_name) DO UPDATE SET cursor = ?2, updated_at = datetime('now')",
+            params![table_name, cursor],
+        )?;
+        Ok(())
+    }
+
+    /// push 완료된 레코드 sync_status = 'synced'
+    /// (그 사이 새 변경이 큐에 쌓였으면 pending 유지)
+    pub fn mark_records_synced(&self, table_name: &str, record_ids: &[String]) -> SqliteResult<()> {
+        self.set_records_sync_status(table_name, record_ids, "synced")
+    }
+
+    /// 큐에서 뺀 레코드의 sync_status 기록 (synced / local: 원격에 올리지 않음 / failed: 원격이 거부)
+    /// pending이 아니면 requeue_pending_records 대상에서 빠짐, 새 변경이 큐에 있으면 pending 유지
+    pub fn set_records_sync_status(
+        &self, table_name: &str, record_ids: &[String], sync_status: &str,
+    ) -> SqliteResult<()> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        for id in record_ids {
+            tx.execute(
+                &format!(
+                    "UPDATE {} SET sync_status = ?3 WHERE id = ?1 AND NOT EXISTS (
+                       SELECT 1 FROM sync_queue
+                       WHERE table_name = ?2 AND record_id = ?1 AND synced = 0)",
+                    table_name
+                ),
+                params![id, table_name, sync_status],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// 원격 행을 로컬에 반영 (sync_queue에는 넣지 않음)
+    /// 로컬 미전송 변경(pending)이 있으면 덮어쓰지 않고 기준 버전과 비교
+    pub fn apply_remote_row(&self, table_name: &str, row: &serde_json::Value) -> SqliteResult<RemoteApply> {
+        let id = match row["id"].as_str() {
+            Some(id) => id,
+            None => return Ok(RemoteApply::Skipped),
+        };
+
+        let conn = self.lock_conn()?;
+        let local: Option<(String, Option<String>)> = conn.query_row(
+            &format!("SELECT sync_status, remote_updated_at FROM {} WHERE id = ?1", table_name),
+            params![id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        ).optional()?;
+        if let Some((status, baseline)) = local {
+            if status == "pending" {
+                // 기준 버전이 없으면(로컬 신규/v5 이전 행) 충돌로 보지 않음
+                let remote_changed = match (baseline.as_deref(), row["updated_at"].as_str()) {
+                    (Some(base), Some(remote)) => !crate::sync_conflict::same_timestamp(base, remote),
+                    _ => false,
+                };
+                return Ok(if remote_changed { RemoteApply::Conflict } else { RemoteApply::Skipped });
+            }
+        }
+
+        write_row(&conn, table_name, row, "synced", row["updated_at"].as_str())?;
+        Ok(RemoteApply::Applied)
+    }
+
+    /// 레코드 전체 컬럼 → JSON (metadata는 파싱)
+    pub fn get_record(&self, table_name: &str, record_id: &str) -> SqliteResult<Option<serde_json::Value>> {
+        let conn = self.lock_conn()?;
+        record_json(&conn, table_name, record_id)
+    }
+
+    /// 로컬 변경의 기준이 된 원격 updated_at
+    pub fn get_remote_baseline(&self, table_name: &str, record_id: &str) -> SqliteResult<Option<String>> {
+        let conn = self.lock_conn()?;
+        let baseline = conn.query_row(
+            &format!("SELECT remote_updated_at FROM {} WHERE id = ?1", table_name),
+            params![record_id],
+            |r| r.get::<_, Option<String>>(0),
+        ).optional()?;
+        Ok(baseline.flatten())
+    }
+
+    /// push 성공 후 원격 updated_at을 기준 버전으로 기록
+    pub fn set_remote_baseline(&self, table_name: &str, record_id: &str, remote_updated_at: &str) -> SqliteResult<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            &format!("UPDATE {} SET remote_updated_at = ?2 WHERE id = ?1", table_name),
+            params![record_id, remote_updated_at],
+        )?;
+        Ok(())
+    }
+
+    /// 충돌 해결: 로컬(병합 결과) 유지 + 기준 버전을 원격에 맞춤 → 다음 push 통과
+    pub fn keep_local(
+        &self, table_name: &str, record: &serde_json::Value, remote_updated_at: Option<&str>,
+    ) -> SqliteResult<()> {
+        let conn = self.lock_conn()?;
+        write_row(&conn, table_name, record, "pending", remote_updated_at)
+    }
+
+    /// 원격 삭제 반영 (로컬 미전송 변경이 있으면 유지), 삭제했으면 true
+    pub fn delete_remote_row(&self, table_name: &str, record_id: &str) -> SqliteResult<bool> {
+        let conn = self.lock_conn()?;
+        let removed = conn.execute(
+            &format!("DELETE FROM {} WHERE id = ?1 AND sync_status != 'pending'", table_name),
+            params![record_id],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// 충돌 해결: 원격 채택 + 해당 레코드의 미전송 큐 항목 폐기
+    pub fn take_remote(&self, table_name: &str, row: &serde_json::Value) -> SqliteResult<()> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        write_row(&tx, table_name, row, "synced", row["updated_at"].as_str())?;
+        if let Some(id) = row["id"].as_str() {
+            discard_pending(&tx, table_name, id)?;
+        }
+        tx.commit()
+    }
+}
+
+// ─── 동기화 충돌 기록 ───
+
+impl LocalDb {
+    /// 충돌 기록 (resolution None = 미해결, 같은 레코드의 미해결 충돌은 최신 내용으로 갱신)
+    pub fn record_conflict(
+        &self,
+        table_name: &str,
+        record_id: &str,
+        policy: &str,
+        local: &serde_json::Value,
+        remote: &serde_json::Value,
+        resolution: Option<&str>,
+    ) -> SqliteResult<()> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "INSERT INTO sync_conflicts
+               (table_name, record_id, policy, local_payload, remote_payload, resolution, resolved_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, CASE WHEN ?6 IS NULL THEN NULL ELSE datetime('now') END)
+             ON CONFLICT(table_name, record_id) WHERE resolution IS NULL DO UPDATE SET
+               policy = excluded.policy,
+               local_payload = excluded.local_payload,
+               remote_payload = excluded.remote_payload,
+               detected_at = datetime('now')",
+            params![
+                table_name,
+                record_id,
+                policy,
+                local.to_string(),
+                remote.to_string(),
+                resolution,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 충돌 목록 (기본: 미해결만)
+    pub fn get_conflicts(&self, include_resolved: bool) -> SqliteResult<Vec<SyncConflict>> {
         let conn = self.lock_conn()?;
         let mut stmt = conn.prepare(
-            &format!("SELECT id, title, status, metadata, sync_status, created_at, updated_at FROM {} ORDER BY created_at DESC", table_name)
+            "SELECT id, table_name, record_id, policy, local_payload, remote_payload,
+                    resolution, detected_at, resolved_at
+             FROM sync_conflicts
+             WHERE ?1 OR resolution IS NULL
+             ORDER BY detected_at DESC, id DESC LIMIT 500"
         )?;
-        let rows = stmt.query_map([], |row| {
-            let metadata_str = row.get::<_, String>(3)?;
-            let metadata: serde_json::Value = serde_json::from_str(&metadata_str)
-                .unwrap_or(serde_json::json!({}));
-            Ok(serde_json::json!({
-                "id": row.get::<_, String>(0)?,
-                "title": row.get::<_, String>(1)?,
-                "status": row.get::<_, String>(2)?,
-                "metadata": metadata,
-                "sync_status": row.get::<_, String>(4)?,
-                "created_at": row.get::<_, String>(5)?,
-                "updated_at": row.get::<_, String>(6)?,
-            }))
+        let rows = stmt.query_map(params![include_resolved], |row| {
+            let local_str = row.get::<_, String>(4)?;
+            let remote_str = row.get::<_, String>(5)?;
+            Ok(SyncConflict {
+                id: row.get(0)?,
+                table_name: row.get(1)?,
+                record_id: row.get(2)?,
+                policy: row.get(3)?,
+                local: serde_json::from_str(&local_str).unwrap_or(serde_json::json!({})),
+                remote: serde_json::from_str(&remote_str).unwrap_or(serde_json::json!({})),
+                resolution: row.get(6)?,
+                detected_at: row.get(7)?,
+                resolved_at: row.get(8)?,
+            })
         })?;
         rows.collect()
     }
+
+    /// 미해결 충돌 수동 해결 (keep_local: 현재 로컬 내용 재전송 / false: 원격 채택)
+    /// 해당 충돌이 없거나 이미 해결됐으면 false
+    pub fn resolve_conflict(&self, conflict_id: i64, keep_local: bool) -> SqliteResult<bool> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        let found: Option<(String, String, String)> = tx.query_row(
+            "SELECT table_name, record_id, remote_payload FROM sync_conflicts
+             WHERE id = ?1 AND resolution IS NULL",
+            params![conflict_id],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)),
+        ).optional()?;
+        let (table_name, record_id, remote_str) = match found {
+            Some(f) => f,
+            None => return Ok(false),
+        };
+        let remote: serde_json::Value =
+            serde_json::from_str(&remote_str).unwrap_or(serde_json::json!({}));
+
+        if keep_local {
+            tx.execute(
+                &format!(
+                    "UPDATE {} SET remote_updated_at = ?2, sync_status = 'pending' WHERE id = ?1",
+                    table_name
+                ),
+                params![record_id, remote["updated_at"].as_str()],
+            )?;
+            if let Some(current) = record_json(&tx, &table_name, &record_id)? {
+                tx.execute(
+                    "INSERT INTO sync_queue (table_name, record_id, operation, payload) VALUES (?1, ?2, 'update', ?3)",
+                    params![table_name, record_id, strip_local_columns(&current).to_string()],
+                )?;
+            }
+        } else {
+            write_row(&tx, &table_name, &remote, "synced", remote["updated_at"].as_str())?;
+            discard_pending(&tx, &table_name, &record_id)?;
+        }
+
+        tx.execute(
+            "UPDATE sync_conflicts SET resolution = ?2, resolved_at = datetime('now') WHERE id = ?1",
+            params![conflict_id, if keep_local { "local" } else { "remote" }],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+}
+
+/// push payload용: 로컬 전용 컬럼 제거
+pub fn strip_local_columns(record: &serde_json::Value) -> serde_json::Value {
+    let mut payload = record.clone();
+    if let Some(obj) = payload.as_object_mut() {
+        for column in LOCAL_ONLY_COLUMNS {
+            obj.remove(*column);
+        }
+    }
+    payload
+}
+
+/// JSON 행 → 로컬 테이블 upsert (로컬 스키마에 있는 컬럼만 반영)
+fn write_row(
+    conn: &Connection,
+    table_name: &str,
+    row: &serde_json::Value,
+    sync_status: &str,
+    remote_updated_at: Option<&str>,
+) -> SqliteResult<()> {
+    let obj = match row.as_object() {
+        Some(o) if o.get("id").map(|v| v.is_string()).unwrap_or(false) => o,
+        _ => return Ok(()),
+    };
+
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table_name))?;
+    let local_columns: Vec<String> = stmt
+        .query_map([], |r| r.get::<_, String>(1))?
+        .collect::<SqliteResult<_>>()?;
+
+    let mut columns = Vec::new();
+    let mut values: Vec<rusqlite::types::Value> = Vec::new();
+    for column in &local_columns {
+        if LOCAL_ONLY_COLUMNS.contains(&column.as_str()) {
+            continue;
+        }
+        if let Some(v) = obj.get(column) {
+            columns.push(column.clone());
+            values.push(json_to_sql(v));
+        }
+    }
+    columns.push("sync_status".to_string());
+    values.push(rusqlite::types::Value::Text(sync_status.to_string()));
+    if let Some(baseline) = remote_updated_at {
+        columns.push("remote_updated_at".to_string());
+        values.push(rusqlite::types::Value::Text(baseline.to_string()));
+    }
+
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+    let updates: Vec<String> = columns
+        .iter()
+        .filter(|c| c.as_str() != "id")
+        .map(|c| format!("{} = excluded.{}", c, c))
+        .collect();
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT(id) DO UPDATE SET {}",
+        table_name,
+        columns.join(", "),
+        placeholders.join(", "),
+        updates.join(", ")
+    );
+    conn.execute(&sql, rusqlite::params_from_iter(values))?;
+    Ok(())
+}
+
+/// work_items 상태 전환 타임스탬프 (프론트 workItems.ts와 같은 규칙)
+/// 입력에 없는 상태/수명주기 필드는 저장된 값 기준
+/// - active 전환: started_at 최초 1회
+/// - done 전환: completed_at + actual_min(started_at 기준, 분)
+fn apply_lifecycle(item: &mut WorkItem, stored: Option<&WorkItem>, now: chrono::DateTime<chrono::Utc>) {
+    let stored_status = stored.and_then(|s| s.status.as_deref());
+    let status = item.status.as_deref().or(stored_status).unwrap_or("backlog");
+    let becomes_done = status == "done" && stored_status != Some("done");
+    let started_at = match &item.started_at {
+        Some(given) => given.clone(),
+        None => stored.and_then(|s| s.started_at.clone().flatten()),
+    };
+
+    if status == "active" && started_at.is_none() {
+        item.started_at = Some(Some(now.to_rfc3339()));
+    }
+    if becomes_done {
+        // 직접 준 값은 존중, 이전 완료 기록은 새 완료 시각으로 교체
+        let completed_at = match &item.completed_at {
+            Some(Some(given)) => given.clone(),
+            _ => now.to_rfc3339(),
+        };
+        if !matches!(item.actual_min, Some(Some(_))) {
+            let started = started_at.as_deref().and_then(parse_timestamp);
+            if let (Some(started), Some(completed)) = (started, parse_timestamp(&completed_at)) {
+                let minutes = ((completed - started).num_seconds() as f64 / 60.0).round().max(0.0) as i64;
+                item.actual_min = Some(Some(minutes));
+            }
+        }
+        item.completed_at = Some(Some(completed_at));
+    }
+}
+
+/// 저장된 행 → 타입 레코드 (NULL 컬럼은 필드 생략)
+fn stored_record(conn: &Connection, table_name: &str, record_id: &str) -> DbResult<Option<SyncRecord>> {
+    let Some(mut row) = record_json(conn, table_name, record_id)? else { return Ok(None) };
+    if let Some(obj) = row.as_object_mut() {
+        obj.remove("remote_updated_at");
+        obj.retain(|_, v| !v.is_null());
+    }
+    SyncRecord::from_stored(table_name, row).map(Some)
+}
+
+/// 레코드 1건 → JSON (전체 컬럼)
+fn record_json(
+    conn: &Connection, table_name: &str, record_id: &str,
+) -> SqliteResult<Option<serde_json::Value>> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {} WHERE id = ?1", table_name))?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    stmt.query_row(params![record_id], |row| {
+        let mut obj = serde_json::Map::new();
+        for (i, column) in columns.iter().enumerate() {
+            let value = sql_to_json(row.get_ref(i)?);
+            let value = match (column.as_str(), value) {
+                ("metadata", serde_json::Value::String(s)) => {
+                    serde_json::from_str(&s).unwrap_or(serde_json::json!({}))
+                }
+                (_, v) => v,
+            };
+            obj.insert(column.clone(), value);
+        }
+        Ok(serde_json::Value::Object(obj))
+    }).optional()
+}
+
+/// 레코드의 미전송 큐 항목 폐기
+fn discard_pending(conn: &Connection, table_name: &str, record_id: &str) -> SqliteResult<()> {
+    conn.execute(
+        "UPDATE sync_queue SET synced = 1 WHERE table_name = ?1 AND record_id = ?2 AND synced = 0",
+        params![table_name, record_id],
+    )?;
+    Ok(())
+}
+
+/// SQLite 값 → JSON 값
+fn sql_to_json(value: rusqlite::types::ValueRef) -> serde_json::Value {
+    use rusqlite::types::ValueRef;
+    match value {
+        ValueRef::Null | ValueRef::Blob(_) => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::json!(i),
+        ValueRef::Real(f) => serde_json::json!(f),
+        ValueRef::Text(t) => serde_json::Value::String(String::from_utf8_lossy(t).to_string()),
+    }
+}
+
+/// JSON 값 → SQLite 값 (객체/배열은 JSON 문자열)
+fn json_to_sql(value: &serde_json::Value) -> rusqlite::types::Value {
+    use rusqlite::types::Value;
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or(0.0)),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+// ─── CRUD: event_outbox ───
+
+impl LocalDb {
+    /// 전송 전 이벤트 기록 (전송 결과가 기록될 때까지 재시도 대상에서 유예)
+    pub fn outbox_insert(&self, events: &[OutboxEvent]) -> SqliteResult<()> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        for e in events {
+            tx.execute(
+                "INSERT OR IGNORE INTO event_outbox
+                   (event_id, event_type, payload, session_id, user_id, status, attempts,
+                    next_attempt_at, last_error)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, datetime('now', ?8), ?9)",
+                params![
+                    e.event_id,
+                    e.event_type,
+                    e.payload.to_string(),
+                    e.session_id,
+                    e.user_id,
+                    e.status,
+                    e.attempts,
+                    format!("+{} seconds", OUTBOX_IN_FLIGHT_SECS),
+                    e.last_error,
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// 재시도할 이벤트 (next_attempt_at 도래 + pending, 해당 사용자가 기록한 것만)
+    pub fn outbox_due(&self, limit: u32, user_id: &str) -> SqliteResult<Vec<OutboxEvent>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT event_id, event_type, payload, session_id, status, attempts,
+                    next_attempt_at, last_error, created_at, user_id
+             FROM event_outbox
+             WHERE status = 'pending' AND next_attempt_at <= datetime('now') AND user_id = ?2
+             ORDER BY created_at ASC LIMIT ?1"
+        )?;
+        let rows = stmt.query_map(params![limit, user_id], outbox_from_row)?;
+        rows.collect()
+    }
+
+    /// 전송 성공 (2xx / 409)
+    pub fn outbox_mark_delivered(&self, event_ids: &[String]) -> SqliteResult<()> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        for id in event_ids {
+            tx.execute(
+                "UPDATE event_outbox SET status = 'delivered', delivered_at = datetime('now'),
+                   last_error = NULL WHERE event_id = ?1",
+                params![id],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// 전송 실패 → 시도 횟수 증가 + 지수 backoff, 최대 횟수 도달 시 dead
+    pub fn outbox_mark_failed(&self, event_ids: &[String], error: &str) -> SqliteResult<()> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        for id in event_ids {
+            tx.execute(
+                "UPDATE event_outbox SET
+                   attempts = attempts + 1,
+                   last_error = ?2,
+                   status = CASE WHEN attempts + 1 >= ?3 THEN 'dead' ELSE 'pending' END,
+                   next_attempt_at = datetime('now',
+                     '+' || min(?4 * (1 << min(attempts, 16)), ?5) || ' seconds')
+                 WHERE event_id = ?1 AND status != 'delivered'",
+                params![
+                    id,
+                    error,
+                    OUTBOX_MAX_ATTEMPTS,
+                    OUTBOX_BACKOFF_BASE_SECS,
+                    OUTBOX_BACKOFF_MAX_SECS,
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// outbox 조회 (UI 점검용, status 미지정 시 미전송 전체)
+    pub fn outbox_list(&self, status: Option<&str>) -> SqliteResult<Vec<OutboxEvent>> {
+        let conn = self.lock_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT event_id, event_type, payload, session_id, status, attempts,
+                    next_attempt_at, last_error, created_at, user_id
+             FROM event_outbox
+             WHERE (?1 IS NULL AND status != 'delivered') OR status = ?1
+             ORDER BY created_at DESC LIMIT 500"
+        )?;
+        let rows = stmt.query_map(params![status], outbox_from_row)?;
+        rows.collect()
+    }
+
+    /// dead/pending 이벤트 즉시 재시도 (시도 횟수 초기화), 변경 행 수 반환
+    /// claim_user: 기록 사용자가 없는 이벤트(로그아웃 중 기록)를 명시적으로 현재 사용자에게 귀속
+    pub fn outbox_requeue(&self, event_ids: &[String], claim_user: Option<&str>) -> SqliteResult<usize> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        let mut changed = 0;
+        for id in event_ids {
+            changed += tx.execute(
+                "UPDATE event_outbox SET status = 'pending', attempts = 0,
+                   next_attempt_at = datetime('now'),
+                   user_id = COALESCE(user_id, ?2)
+                 WHERE event_id = ?1 AND status != 'delivered'",
+                params![id, claim_user],
+            )?;
+        }
+        tx.commit()?;
+        Ok(changed)
+    }
+
+    /// 이벤트 삭제 (ID 미지정 시 dead 전체), 삭제 행 수 반환
+    pub fn outbox_purge(&self, event_ids: Option<&[String]>) -> SqliteResult<usize> {
+        let mut conn = self.lock_conn()?;
+        let tx = conn.transaction()?;
+        let mut removed = 0;
+        match event_ids {
+            Some(ids) => {
+                for id in ids {
+                    removed += tx.execute(
+                        "DELETE FROM event_outbox WHERE event_id = ?1",
+                        params![id],
+                    )?;
+                }
+            }
+            None => {
+                removed = tx.execute("DELETE FROM event_outbox WHERE status = 'dead'", [])?;
+            }
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// (대기 중, dead) 이벤트 수
+    pub fn outbox_counts(&self) -> SqliteResult<(u64, u64)> {
+        let conn = self.lock_conn()?;
+        conn.query_row(
+            "SELECT COALESCE(SUM(status = 'pending'), 0), COALESCE(SUM(status = 'dead'), 0)
+             FROM event_outbox",
+            [],
+            |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, i64>(1)? as u64)),
+        )
+    }
+
+    /// 오래된 delivered 이벤트 정리
+    pub fn outbox_prune_delivered(&self, older_than_days: u32) -> SqliteResult<usize> {
+        let conn = self.lock_conn()?;
+        conn.execute(
+            "DELETE FROM event_outbox
+             WHERE status = 'delivered' AND delivered_at < datetime('now', ?1)",
+            params![format!("-{} days", older_than_days)],
+        )
+    }
+}
+
+fn outbox_from_row(row: &rusqlite::Row) -> SqliteResult<OutboxEvent> {
+    let payload_str = row.get::<_, String>(2)?;
+    Ok(OutboxEvent {
+        event_id: row.get(0)?,
+        event_type: row.get(1)?,
+        payload: serde_json::from_str(&payload_str).unwrap_or(serde_json::json!({})),
+        session_id: row.get(3)?,
+        status: row.get(4)?,
+        attempts: row.get(5)?,
+        next_attempt_at: row.get(6)?,
+        last_error: row.get(7)?,
+        created_at: row.get(8)?,
+        user_id: row.get(9)?,
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// 저장 형태 JSON → 레코드 → upsert (id 형식 검증 없이)
+    fn upsert(db: &LocalDb, table_name: &str, value: serde_json::Value) -> DbResult<String> {
+        db.upsert_syncable(&SyncRecord::from_stored(table_name, value)?)
+    }
+
     #[test]
     fn test_db_open_and_migrate() {
         // 임시 경로에 DB 생성
@@ -708,4 +1452,425 @@ mod tests {
 
         let _ = std::fs::remove_file(&tmp);
     }
+
+    #[test]
+    fn test_event_outbox_lifecycle() {
+        let tmp = std::env::temp_dir().join("orchestrator_outbox_test.db");
+        let _ = std::fs::remove_file(&tmp);
+
+        let conn = Connection::open(&tmp).unwrap();
+        let db = LocalDb { conn: Mutex::new(conn) };
+        db.migrate().unwrap();
+
+        let event = |id: &str| OutboxEvent {
+            event_id: id.to_string(),
+            event_type: "file.changed".to_string(),
+            payload: serde_json::json!({ "file": "src/a.ts" }),
+            session_id: Some("s-1".to_string()),
+            user_id: Some("user-a".to_string()),
+            status: "pending".to_string(),
+            attempts: 0,
+            next_attempt_at: String::new(),
+            last_error: None,
+            created_at: String::new(),
+        };
+        db.outbox_insert(&[event("e1"), event("e2")]).unwrap();
+
+        // 전송 중 이벤트는 유예
+        assert!(db.outbox_due(10, "user-a").unwrap().is_empty());
+
+        db.outbox_mark_delivered(&["e1".to_string()]).unwrap();
+        db.outbox_mark_failed(&["e1".to_string(), "e2".to_string()], "503").unwrap();
+        let listed = db.outbox_list(None).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].event_id, "e2");
+        assert_eq!(listed[0].attempts, 1);
+        assert_eq!(listed[0].last_error.as_deref(), Some("503"));
+
+        // 최대 횟수 → dead
+        for _ in 1..OUTBOX_MAX_ATTEMPTS {
+            db.outbox_mark_failed(&["e2".to_string()], "503").unwrap();
+        }
+        let dead = db.outbox_list(Some("dead")).unwrap();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(db.outbox_counts().unwrap(), (0, 1));
+
+        // requeue → 즉시 재시도 대상
+        assert_eq!(db.outbox_requeue(&["e2".to_string()], Some("user-b")).unwrap(), 1);
+        // 다른 사용자로 로그인 중이면 전송 대상 아님 (기록 사용자 유지)
+        assert!(db.outbox_due(10, "user-b").unwrap().is_empty());
+        let due = db.outbox_due(10, "user-a").unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].attempts, 0);
+        assert_eq!(due[0].payload["file"], "src/a.ts");
+
+        // 로그아웃 중 기록된 이벤트는 명시적 requeue 시에만 현재 사용자로 귀속
+        db.outbox_insert(&[OutboxEvent { user_id: None, ..event("e3") }]).unwrap();
+        assert_eq!(db.outbox_requeue(&["e3".to_string()], Some("user-b")).unwrap(), 1);
+        let claimed = db.outbox_due(10, "user-b").unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert_eq!(claimed[0].event_id, "e3");
+        assert_eq!(db.outbox_purge(Some(&["e3".to_string()])).unwrap(), 1);
+
+        assert_eq!(db.outbox_purge(Some(&["e2".to_string()])).unwrap(), 1);
+        assert!(db.outbox_list(None).unwrap().is_empty());
+
+        let _ = std::fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_apply_remote_row_and_cursor() {
+        let tmp = std::env::temp_dir().join("orchestrator_sync_engine_test.db");
+        let _ = std::fs::remove_file(&tmp);
+
+        let conn = Connection::open(&tmp).unwrap();
+        let db = LocalDb { conn: Mutex::new(conn) };
+        db.migrate().unwrap();
+
+        // 원격 신규 행 → synced로 반영, 로컬 스키마에 없는 컬럼은 무시
+        let remote = serde_json::json!({
+            "id": "w1",
+            "title": "Remote task",
+            "status": "active",
+            "user_id": "u1",
+            "energy": "low",
+            "updated_at": "2025-01-01T00:00:00+00:00",
+        });
+        assert_eq!(db.apply_remote_row("work_items", &remote).unwrap(), RemoteApply::Applied);
+        let items = serde_json::to_value(db.get_all_syncable("work_items", false).unwrap()).unwrap();
+        assert_eq!(items[0]["title"], "Remote task");
+        assert_eq!(items[0]["sync_status"], "synced");
+        assert_eq!(items[0]["energy"], "low");
+
+        // 로컬 미전송 변경이 있으면 덮어쓰지 않음
+        upsert(&db, "work_items", serde_json::json!({ "id": "w1", "title": "Local edit" }))
+            .unwrap();
+        assert_eq!(db.apply_remote_row("work_items", &remote).unwrap(), RemoteApply::Skipped);
+
+        // push 완료 → synced
+        let pending = db.get_pending_sync().unwrap();
+        let ids: Vec<i64> = pending.iter().map(|p| p["id"].as_i64().unwrap()).collect();
+        db.mark_synced(&ids).unwrap();
+        db.mark_records_synced("work_items", &["w1".to_string()]).unwrap();
+        let items = serde_json::to_value(db.get_all_syncable("work_items", false).unwrap()).unwrap();
+        assert_eq!(items[0]["sync_status"], "synced");
+
+        assert_eq!(db.get_sync_cursor("work_items").unwrap(), None);
+        db.set_sync_cursor("work_items", "2025-01-01T00:00:00+00:00").unwrap();
+        assert_eq!(
+            db.get_sync_cursor("work_items").unwrap().as_deref(),
+            Some("2025-01-01T00:00:00+00:00")
+        );
+
+        // 미동의 중 큐 비움 → 동의 후 pending 레코드만 재등록
+        upsert(&db, "work_items", serde_json::json!({ "id": "w2", "title": "Offline" }))
+            .unwrap();
+        db.clear_sync_queue().unwrap();
+        assert!(db.get_pending_sync().unwrap().is_empty());
+        assert_eq!(db.requeue_pending_records(&["work_items", "plans"]).unwrap(), 1);
+        let pending = db.get_pending_sync().unwrap();
+        assert_eq!(pending[0]["record_id"], "w2");
+        assert_eq!(pending[0]["payload"]["title"], "Offline");
+        assert!(pending[0]["payload"].get("sync_status").is_none());
+        assert_eq!(db.requeue_pending_records(&["work_items"]).unwrap(), 0);
+
+        let _ = std::fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_conflict_detection_and_resolution() {
+        let tmp = std::env::temp_dir().join("orchestrator_conflict_test.db");
+        let _ = std::fs::remove_file(&tmp);
+
+        let conn = Connection::open(&tmp).unwrap();
+        let db = LocalDb { conn: Mutex::new(conn) };
+        db.migrate().unwrap();
+
+        let remote = |title: &str, updated_at: &str| serde_json::json!({
+            "id": "p1",
+            "title": title,
+            "status": "backlog",
+            "metadata": { "source": "remote" },
+            "updated_at": updated_at,
+        });
+        db.apply_remote_row("plans", &remote("v1", "2025-01-01T00:00:00+00:00")).unwrap();
+        assert_eq!(
+            db.get_remote_baseline("plans", "p1").unwrap().as_deref(),
+            Some("2025-01-01T00:00:00+00:00")
+        );
+
+        // 로컬 변경 후 원격이 기준 버전 그대로면 보류, 바뀌었으면 충돌
+        upsert(&db, "plans", serde_json::json!({ "id": "p1", "title": "local" })).unwrap();
+        assert_eq!(
+            db.apply_remote_row("plans", &remote("v1", "2025-01-01T00:00:00Z")).unwrap(),
+            RemoteApply::Skipped
+        );
+        let v2 = remote("v2", "2025-01-02T00:00:00+00:00");
+        assert_eq!(db.apply_remote_row("plans", &v2).unwrap(), RemoteApply::Conflict);
+
+        // 미해결 충돌은 레코드당 하나
+        let local = strip_local_columns(&db.get_record("plans", "p1").unwrap().unwrap());
+        assert!(local.get("sync_status").is_none());
+        db.record_conflict("plans", "p1", "manual", &local, &v2, None).unwrap();
+        db.record_conflict("plans", "p1", "manual", &local, &v2, None).unwrap();
+        let open = db.get_conflicts(false).unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].local["title"], "local");
+        assert_eq!(open[0].remote["title"], "v2");
+
+        // 원격 채택 → 로컬 덮어쓰기 + 미전송 큐 폐기
+        assert!(db.resolve_conflict(open[0].id, false).unwrap());
+        assert!(!db.resolve_conflict(open[0].id, false).unwrap());
+        let record = db.get_record("plans", "p1").unwrap().unwrap();
+        assert_eq!(record["title"], "v2");
+        assert_eq!(record["sync_status"], "synced");
+        assert!(db.get_pending_sync().unwrap().is_empty());
+        assert!(db.get_conflicts(false).unwrap().is_empty());
+        assert_eq!(db.get_conflicts(true).unwrap()[0].resolution.as_deref(), Some("remote"));
+
+        // 로컬 유지 → 기준 버전을 원격에 맞추고 재전송 예약
+        upsert(&db, "plans", serde_json::json!({ "id": "p1", "title": "mine" })).unwrap();
+        let v3 = remote("v3", "2025-01-03T00:00:00+00:00");
+        assert_eq!(db.apply_remote_row("plans", &v3).unwrap(), RemoteApply::Conflict);
+        db.record_conflict("plans", "p1", "manual", &local, &v3, None).unwrap();
+        let id = db.get_conflicts(false).unwrap()[0].id;
+        assert!(db.resolve_conflict(id, true).unwrap());
+        assert_eq!(
+            db.get_remote_baseline("plans", "p1").unwrap().as_deref(),
+            Some("2025-01-03T00:00:00+00:00")
+        );
+        let pending = db.get_pending_sync().unwrap();
+        assert_eq!(pending.last().unwrap()["payload"]["title"], "mine");
+        assert!(pending.last().unwrap()["payload"].get("remote_updated_at").is_none());
+
+        // 원격 채택 (자동 해결)
+        db.take_remote("plans", &v3).unwrap();
+        assert!(db.get_pending_sync().unwrap().is_empty());
+        assert_eq!(db.get_record("plans", "p1").unwrap().unwrap()["title"], "v3");
+
+        let _ = std::fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_move_plaintext_credentials() {
+        let dir = tempfile::tempdir().unwrap();
+        let tmp = dir.path().join("local.db");
+        let vault = dir.path().join("credentials.enc");
+        let db = LocalDb::open_at(&tmp).unwrap();
+
+        // v6 이전 평문 행
+        db.lock_conn().unwrap().execute(
+            "INSERT INTO github_connections_legacy (id, github_username, access_token, refresh_token)
+             VALUES ('c1', 'octocat', 'gho_plain', 'ghr_plain')",
+            [],
+        ).unwrap();
+
+        // 잠금 상태로 v7 → v8 업그레이드를 거친 백업 (legacy 테이블 포함)
+        let legacy_backup = dir.path().join("local.db.v7.bak");
+        db.lock_conn().unwrap()
+            .execute("VACUUM INTO ?1", params![legacy_backup.to_string_lossy()])
+            .unwrap();
+
+        let credentials = crate::credential_store::Credentials::for_test(&vault);
+        assert!(db.move_plaintext_credentials(&credentials).is_err());
+        assert!(legacy_backup.exists());
+
+        credentials.unlock("pw").unwrap();
+        assert_eq!(db.move_plaintext_credentials(&credentials).unwrap(), 1);
+        let connections = db.get_github_connections().unwrap();
+        assert_eq!(connections[0].github_username.as_deref(), Some("octocat"));
+        assert_eq!(
+            credentials.resolve(&connections[0].access_token_ref).unwrap().as_deref(),
+            Some("gho_plain")
+        );
+        assert_eq!(
+            credentials
+                .resolve(connections[0].refresh_token_ref.as_deref().unwrap())
+                .unwrap()
+                .as_deref(),
+            Some("ghr_plain")
+        );
+        assert!(!legacy_backup.exists());
+        // 평문 테이블 제거 → 재호출은 no-op
+        assert_eq!(db.move_plaintext_credentials(&credentials).unwrap(), 0);
+        drop(db);
+        let raw = std::fs::read(&tmp).unwrap();
+        assert!(!raw.windows(9).any(|w| w == b"gho_plain"));
+
+        let db = LocalDb::open_at(&tmp).unwrap();
+        let removed = db.delete_github_connection("c1").unwrap().unwrap();
+        assert_eq!(removed.access_token_ref, connections[0].access_token_ref);
+        assert!(db.get_github_connections().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_work_item_lifecycle_and_soft_delete() {
+        let tmp = std::env::temp_dir().join("orchestrator_lifecycle_test.db");
+        let _ = std::fs::remove_file(&tmp);
+
+        let conn = Connection::open(&tmp).unwrap();
+        let db = LocalDb { conn: Mutex::new(conn) };
+        db.migrate().unwrap();
+
+        let item = |status: &str| serde_json::json!({
+            "id": "8d0f6a2e-1b3c-4d5e-9f60-7a8b9c0d1e2f",
+            "title": "Ship",
+            "status": status,
+        });
+        let get = |include_deleted: bool| {
+            serde_json::to_value(db.get_all_syncable("work_items", include_deleted).unwrap()).unwrap()
+        };
+
+        let id = upsert(&db, "work_items", item("backlog")).unwrap();
+        assert!(get(false)[0].get("started_at").is_none());
+
+        // active 전환 → started_at 최초 1회
+        upsert(&db, "work_items", item("active")).unwrap();
+        let started = get(false)[0]["started_at"].clone();
+        assert!(started.is_string());
+        upsert(&db, "work_items", item("blocked")).unwrap();
+        upsert(&db, "work_items", item("active")).unwrap();
+        assert_eq!(get(false)[0]["started_at"], started);
+
+        // done 전환 → completed_at + actual_min, push payload에도 포함
+        db.conn.lock().unwrap().execute(
+            "UPDATE work_items SET started_at = ?2 WHERE id = ?1",
+            params![id, (chrono::Utc::now() - chrono::Duration::minutes(90)).to_rfc3339()],
+        ).unwrap();
+        upsert(&db, "work_items", item("done")).unwrap();
+        let done = get(false)[0].clone();
+        assert!(done["completed_at"].is_string());
+        assert_eq!(done["actual_min"], 90);
+        let pending = db.get_pending_sync().unwrap();
+        assert_eq!(pending.last().unwrap()["payload"]["actual_min"], 90);
+
+        // soft delete → 기본 조회에서 제외, 큐에 update
+        assert!(db.soft_delete_work_item(&id).unwrap());
+        assert_eq!(get(false).as_array().unwrap().len(), 0);
+        assert!(get(true)[0]["deleted_at"].is_string());
+        let pending = db.get_pending_sync().unwrap();
+        let last = pending.last().unwrap();
+        assert_eq!(last["operation"], "update");
+        assert!(last["payload"]["deleted_at"].is_string());
+        assert!(last["payload"].get("sync_status").is_none());
+
+        assert!(db.restore_work_item(&id).unwrap());
+        assert_eq!(get(false)[0]["actual_min"], 90);
+        assert!(!db.soft_delete_work_item("missing").unwrap());
+
+        // goals: 상위 XOR은 DB에서도 거부
+        let plan_id = "0b8e7f4c-2d1a-4c3b-8e9f-5a6b7c8d9e0f";
+        let goal_id = upsert(&db, "goals", serde_json::json!({
+            "title": "G", "plan_id": plan_id, "priority": 2,
+        })).unwrap();
+        let goals = serde_json::to_value(db.get_all_syncable("goals", false).unwrap()).unwrap();
+        assert_eq!(goals[0]["priority"], 2);
+        assert_eq!(goals[0]["status"], "backlog");
+
+        // 부분 수정은 입력에 없는 필드를 기본값으로 되돌리지 않음
+        upsert(&db, "goals", serde_json::json!({ "id": goal_id, "description": "d" })).unwrap();
+        upsert(&db, "goals", serde_json::json!({ "id": goal_id, "status": "active" })).unwrap();
+        let goal = db.get_record("goals", &goal_id).unwrap().unwrap();
+        assert_eq!(goal["priority"], 2);
+        assert_eq!(goal["description"], "d");
+        assert_eq!(goal["status"], "active");
+        assert_eq!(goal["title"], "G");
+        upsert(&db, "work_items", serde_json::json!({ "id": id, "energy": "high" })).unwrap();
+        let item = db.get_record("work_items", &id).unwrap().unwrap();
+        assert_eq!(item["status"], "done");
+        assert_eq!(item["title"], "Ship");
+        // 수정 push payload는 병합된 전체 행
+        assert_eq!(db.get_pending_sync().unwrap().last().unwrap()["payload"]["status"], "done");
+
+        // 신규 goal: 입력에 없는 값은 스키마 기본값
+        let fresh = upsert(&db, "goals", serde_json::json!({ "title": "H", "plan_id": plan_id })).unwrap();
+        let fresh = db.get_record("goals", &fresh).unwrap().unwrap();
+        assert_eq!(fresh["priority"], 3);
+        assert_eq!(fresh["status"], "backlog");
+        assert!(upsert(&db, "goals", serde_json::json!({
+            "id": goal_id, "title": "G", "plan_id": plan_id, "project_id": plan_id,
+        })).is_err());
+
+        let _ = std::fs::remove_file(&tmp);
+    }
+
+    #[test]
+    fn test_upsert_syncable_columns_per_table() {
+        let tmp = std::env::temp_dir().join("orchestrator_upsert_syncable_test.db");
+        let _ = std::fs::remove_file(&tmp);
+
+        let conn = Connection::open(&tmp).unwrap();
+        let db = LocalDb { conn: Mutex::new(conn) };
+        db.migrate().unwrap();
+
+        // 신규 행도 선택 컬럼이 모두 저장됨
+        let plan_id = upsert(&db, "plans", serde_json::json!({
+            "title": "Launch", "plan_type": "fixed", "priority": "high",
+            "description": "v1", "due_at": "2026-03-01", "metadata": { "color": "red" },
+        })).unwrap();
+        let plan = db.get_record("plans", &plan_id).unwrap().unwrap();
+        assert_eq!(plan["plan_type"], "fixed");
+        assert_eq!(plan["priority"], "high");
+        assert_eq!(plan["description"], "v1");
+        assert_eq!(plan["due_at"], "2026-03-01");
+        assert_eq!(plan["metadata"], serde_json::json!({ "color": "red" }));
+
+        // 수정: 입력에 있는 컬럼만 갱신, metadata 병합
+        upsert(&db, "plans", serde_json::json!({
+            "id": plan_id, "title": "Launch 2", "metadata": { "pinned": true },
+        })).unwrap();
+        let plan = db.get_record("plans", &plan_id).unwrap().unwrap();
+        assert_eq!(plan["title"], "Launch 2");
+        assert_eq!(plan["description"], "v1");
+        assert_eq!(plan["metadata"], serde_json::json!({ "color": "red", "pinned": true }));
+
+        // 명시적 null → 컬럼 비움, 키 없음 → 유지
+        upsert(&db, "plans", serde_json::json!({
+            "id": plan_id, "description": null, "due_at": null,
+        })).unwrap();
+        let plan = db.get_record("plans", &plan_id).unwrap().unwrap();
+        assert!(plan["description"].is_null());
+        assert!(plan["due_at"].is_null());
+        assert_eq!(plan["title"], "Launch 2");
+
+        // session_logs: title/updated_at 없는 테이블
+        let log_id = upsert(&db, "session_logs", serde_json::json!({
+            "project_id": "p1", "editor_type": "cursor", "started_at": "2026-03-01 09:00:00",
+        })).unwrap();
+        upsert(&db, "session_logs", serde_json::json!({
+            "id": log_id, "ended_at": "2026-03-01 09:45:00", "duration_min": 45, "summary": "done",
+        })).unwrap();
+        let log = db.get_record("session_logs", &log_id).unwrap().unwrap();
+        assert_eq!(log["editor_type"], "cursor");
+        assert_eq!(log["started_at"], "2026-03-01 09:00:00");
+        assert_eq!(log["duration_min"], 45);
+        assert_eq!(log["summary"], "done");
+        upsert(&db, "session_logs", serde_json::json!({ "id": log_id, "ended_at": null })).unwrap();
+        let log = db.get_record("session_logs", &log_id).unwrap().unwrap();
+        assert!(log["ended_at"].is_null());
+        assert_eq!(log["summary"], "done");
+
+        // work_items: 상위 목표 연결 해제
+        let item_id = upsert(&db, "work_items", serde_json::json!({
+            "title": "Ship", "goal_id": "0b8e7f4c-2d1a-4c3b-8e9f-5a6b7c8d9e0f", "next_action": null,
+        })).unwrap();
+        upsert(&db, "work_items", serde_json::json!({ "id": item_id, "goal_id": null, "title": "Ship" })).unwrap();
+        assert!(db.get_record("work_items", &item_id).unwrap().unwrap()["goal_id"].is_null());
+
+        let pending = db.get_pending_sync().unwrap();
+        let ops: Vec<_> = pending.iter().map(|p| p["operation"].as_str().unwrap()).collect();
+        assert_eq!(ops, ["insert", "update", "update", "insert", "update", "update", "insert", "update"]);
+        assert_eq!(pending[4]["payload"]["summary"], "done");
+        // 비운 값도 push payload에 null로 포함
+        assert!(pending[5]["payload"]["ended_at"].is_null());
+        assert!(pending[5]["payload"].get("ended_at").is_some());
+
+        // 실패한 upsert는 행도 큐도 남기지 않음 (한 트랜잭션)
+        assert!(upsert(&db, "goals", serde_json::json!({ "title": "orphan" })).is_err());
+        assert!(db.get_all_syncable("goals", false).unwrap().is_empty());
+        assert_eq!(db.get_pending_sync().unwrap().len(), 8);
+        assert!(SyncRecord::from_stored("projects", serde_json::json!({})).is_err());
+
+        let _ = std::fs::remove_file(&tmp);
+    }
 }
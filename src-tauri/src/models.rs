@@ -0,0 +1,1069 @@
+// ===========================================
+// models.rs — local_db 도메인 모델 + 입력 검증
+// Tauri 커맨드 경계에서 JSON → 타입 변환, 잘못된 입력은 필드 단위 에러로 거부
+// DbError: local_db / db_* 커맨드 공용 에러
+// ===========================================
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+// ─── 에러 ───
+
+#[derive(Debug)]
+pub enum DbError {
+    /// 입력 검증 실패 (필드 단위)
+    Invalid { field: String, message: String },
+    /// SQLite 오류
+    Sqlite(rusqlite::Error),
+    /// 스키마 버전 문제 (앱보다 새 DB, 체크섬 불일치, 적용 실패)
+    Migration(String),
+    /// 자격 증명 저장소 오류 (잠김, 키체인 접근 실패)
+    Credentials(String),
+}
+
+pub type DbResult<T> = Result<T, DbError>;
+
+pub fn invalid(field: &str, message: impl Into<String>) -> DbError {
+    DbError::Invalid {
+        field: field.to_string(),
+        message: message.into(),
+    }
+}
+
+impl std::fmt::Display for DbError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbError::Invalid { field, message } => write!(f, "{}: {}", field, message),
+            DbError::Sqlite(e) => write!(f, "DB 오류: {}", e),
+            DbError::Migration(message) | DbError::Credentials(message) => {
+                write!(f, "{}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(e: rusqlite::Error) -> Self {
+        DbError::Sqlite(e)
+    }
+}
+
+/// 프론트엔드 전달 형식: { kind, field, message }
+impl Serialize for DbError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = match self {
+            DbError::Invalid { field, message } => serde_json::json!({
+                "kind": "invalid",
+                "field": field,
+                "message": message,
+            }),
+            DbError::Sqlite(e) => serde_json::json!({
+                "kind": "db",
+                "field": null,
+                "message": e.to_string(),
+            }),
+            DbError::Migration(message) => serde_json::json!({
+                "kind": "migration",
+                "field": null,
+                "message": message,
+            }),
+            DbError::Credentials(message) => serde_json::json!({
+                "kind": "credentials",
+                "field": null,
+                "message": message,
+            }),
+        };
+        value.serialize(serializer)
+    }
+}
+
+// ─── JSON → 모델 변환 ───
+
+pub trait Validate {
+    fn validate(&self) -> DbResult<()>;
+}
+
+/// JSON → 모델 (누락/알 수 없는 필드, 타입 불일치, 값 검증 실패 → 필드 단위 에러)
+pub fn decode<T: DeserializeOwned + Validate>(value: serde_json::Value) -> DbResult<T> {
+    let model: T = parse(value)?;
+    model.validate()?;
+    Ok(model)
+}
+
+/// JSON → 모델 (값 검증 없이 형태만)
+fn parse<T: DeserializeOwned>(value: serde_json::Value) -> DbResult<T> {
+    serde_path_to_error::deserialize(value).map_err(|e| {
+        let message = e.inner().to_string();
+        let path = e.path().to_string();
+        // 누락/알 수 없는 필드는 path가 "." → 메시지의 `필드명` 사용
+        let field = if path == "." {
+            message
+                .split('`')
+                .nth(1)
+                .map(str::to_string)
+                .unwrap_or(path)
+        } else {
+            path
+        };
+        invalid(&field, message)
+    })
+}
+
+fn required(field: &str, value: &str) -> DbResult<()> {
+    if value.trim().is_empty() {
+        return Err(invalid(field, "필수 값 누락"));
+    }
+    Ok(())
+}
+
+fn one_of(field: &str, value: &str, allowed: &[&str]) -> DbResult<()> {
+    if !allowed.contains(&value) {
+        return Err(invalid(
+            field,
+            format!(
+                "허용되지 않은 값 '{}' (가능: {})",
+                value,
+                allowed.join(", ")
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// 빈 값이면 새 ID 발급 대상, 값이 있으면 UUID여야 함 (원격 id 컬럼이 UUID)
+fn optional_uuid(field: &str, value: &str) -> DbResult<()> {
+    if !value.is_empty() && uuid::Uuid::parse_str(value).is_err() {
+        return Err(invalid(field, format!("UUID 형식 아님: {}", value)));
+    }
+    Ok(())
+}
+
+fn optional_timestamp(field: &str, value: Option<&str>) -> DbResult<()> {
+    let Some(value) = value else { return Ok(()) };
+    let parsed = parse_timestamp(value).is_some()
+        || chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok();
+    if !parsed {
+        return Err(invalid(field, format!("날짜 형식 아님: {}", value)));
+    }
+    Ok(())
+}
+
+/// RFC 3339(원격) 또는 SQLite datetime('now') 형식(UTC) → 시각
+pub fn parse_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(t) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(t.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|t| t.and_utc())
+}
+
+fn non_negative(field: &str, value: Option<i64>) -> DbResult<()> {
+    if matches!(value, Some(v) if v < 0) {
+        return Err(invalid(field, "음수 불가"));
+    }
+    Ok(())
+}
+
+fn in_range(field: &str, value: f64, min: f64, max: f64) -> DbResult<()> {
+    if !value.is_finite() || value < min || value > max {
+        return Err(invalid(
+            field,
+            format!("{} ~ {} 범위 밖: {}", min, max, value),
+        ));
+    }
+    Ok(())
+}
+
+fn json_object(field: &str, value: &serde_json::Value) -> DbResult<()> {
+    if !value.is_object() {
+        return Err(invalid(field, "JSON 객체여야 함"));
+    }
+    Ok(())
+}
+
+/// 수정 가능한 nullable 필드: 키 없음(None) → 유지, null(Some(None)) → 비움, 값 → 설정
+pub type Patch<T> = Option<Option<T>>;
+
+/// 키가 있으면 null이어도 Some (serde 기본 Option은 null과 키 없음을 구분하지 않음)
+fn patch<'de, D, T>(deserializer: D) -> Result<Patch<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// 키가 있으면 값 필수 (null 거부), 키 없음 → None (수정: 유지 / 삽입: 스키마 기본값)
+fn non_null<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+/// 설정된 값만 (키 없음 / null → None)
+fn text(value: &Patch<String>) -> Option<&str> {
+    value.as_ref()?.as_deref()
+}
+
+fn empty_object() -> serde_json::Value {
+    serde_json::json!({})
+}
+
+/// 미지정 ID → 새 UUID
+pub fn id_or_new(id: &str) -> String {
+    if id.is_empty() {
+        uuid::Uuid::new_v4().to_string()
+    } else {
+        id.to_string()
+    }
+}
+
+pub const PROJECT_STATUSES: &[&str] = &["backlog", "active", "archived", "completed"];
+/// Supabase work_items/plans status CHECK
+pub const WORK_STATUSES: &[&str] = &[
+    "backlog",
+    "candidate",
+    "active",
+    "done",
+    "blocked",
+    "deferred",
+];
+pub const GOAL_STATUSES: &[&str] = &["backlog", "active", "done", "deferred"];
+pub const PRIORITIES: &[&str] = &["low", "medium", "high", "critical"];
+pub const ENERGY_LEVELS: &[&str] = &["high", "medium", "low"];
+/// Supabase session_logs.result CHECK
+pub const SESSION_LOG_RESULTS: &[&str] = &["done", "partial", "blocked"];
+pub const PLAN_TYPES: &[&str] = &["task", "event", "project", "fixed"];
+
+// ─── projects ───
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Project {
+    #[serde(default)]
+    pub id: String,
+    pub repo_id: i64,
+    pub repo_name: String,
+    pub repo_full_name: String,
+    pub repo_url: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_branch")]
+    pub default_branch: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub is_private: bool,
+    #[serde(default = "default_project_status")]
+    pub status: String,
+    #[serde(default = "empty_object")]
+    pub metadata: serde_json::Value,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+fn default_branch() -> String {
+    "main".to_string()
+}
+
+fn default_project_status() -> String {
+    "active".to_string()
+}
+
+impl Validate for Project {
+    fn validate(&self) -> DbResult<()> {
+        optional_uuid("id", &self.id)?;
+        if self.repo_id < 0 {
+            return Err(invalid("repo_id", "음수 불가"));
+        }
+        required("repo_name", &self.repo_name)?;
+        required("repo_full_name", &self.repo_full_name)?;
+        let mut parts = self.repo_full_name.split('/');
+        let well_formed = matches!(
+            (parts.next(), parts.next(), parts.next()),
+            (Some(owner), Some(repo), None) if !owner.is_empty() && !repo.is_empty()
+        );
+        if !well_formed {
+            return Err(invalid("repo_full_name", "owner/repo 형식이어야 함"));
+        }
+        if !self.repo_url.starts_with("https://") && !self.repo_url.starts_with("http://") {
+            return Err(invalid("repo_url", "http(s) URL이어야 함"));
+        }
+        required("default_branch", &self.default_branch)?;
+        one_of("status", &self.status, PROJECT_STATUSES)?;
+        json_object("metadata", &self.metadata)
+    }
+}
+
+// ─── model_scores ───
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelScore {
+    #[serde(default)]
+    pub id: String,
+    pub model_key: String,
+    pub coding: f64,
+    pub analysis: f64,
+    pub documentation: f64,
+    pub speed: f64,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+impl Validate for ModelScore {
+    fn validate(&self) -> DbResult<()> {
+        required("model_key", &self.model_key)?;
+        in_range("coding", self.coding, 0.0, 100.0)?;
+        in_range("analysis", self.analysis, 0.0, 100.0)?;
+        in_range("documentation", self.documentation, 0.0, 100.0)?;
+        in_range("speed", self.speed, 0.0, 100.0)
+    }
+}
+
+// ─── editor_models ───
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EditorModels {
+    #[serde(default)]
+    pub id: String,
+    pub editor_type: String,
+    pub supported_models: Vec<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+impl Validate for EditorModels {
+    fn validate(&self) -> DbResult<()> {
+        required("editor_type", &self.editor_type)?;
+        for (i, model) in self.supported_models.iter().enumerate() {
+            required(&format!("supported_models[{}]", i), model)?;
+        }
+        Ok(())
+    }
+}
+
+// ─── 동기화 대상: work_items / plans / goals / session_logs ───
+// 필드 = Supabase 컬럼 (session_logs의 에이전트 세션 기록 컬럼만 로컬 전용)
+// 입력은 부분 수정 형태: 없는 필드는 기존 값 유지, 기본값은 삽입 시 스키마에서
+// 필수 값/상위 관계는 저장될 행 전체로 검증 (SyncRecord::validate_row)
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WorkItem {
+    #[serde(default)]
+    pub id: String,
+    #[serde(
+        default,
+        deserialize_with = "non_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub title: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "non_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub project_id: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub next_action: Patch<String>,
+    /// 예상 소요 시간 (분)
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub estimate_min: Patch<i64>,
+    /// high / medium / low
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub energy: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub due_at: Patch<String>,
+    /// 외부 출처 (예: github / 이슈 URL)
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub source_app: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub source_ref: Patch<String>,
+    /// 상위 목표 (계층: project/plan → goal → work_item)
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub goal_id: Patch<String>,
+    /// 수명주기: active 최초 전환 / done 전환 / soft delete 시각
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub started_at: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub completed_at: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub deleted_at: Patch<String>,
+    /// 실제 소요 시간 (분, done 전환 시 started_at 기준 자동 계산)
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub actual_min: Patch<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+impl Validate for WorkItem {
+    fn validate(&self) -> DbResult<()> {
+        optional_uuid("id", &self.id)?;
+        if let Some(title) = &self.title {
+            required("title", title)?;
+        }
+        if let Some(status) = &self.status {
+            one_of("status", status, WORK_STATUSES)?;
+        }
+        non_negative("estimate_min", self.estimate_min.flatten())?;
+        if let Some(energy) = text(&self.energy) {
+            one_of("energy", energy, ENERGY_LEVELS)?;
+        }
+        optional_timestamp("due_at", text(&self.due_at))?;
+        if let Some(goal_id) = text(&self.goal_id) {
+            optional_uuid("goal_id", goal_id)?;
+        }
+        optional_timestamp("started_at", text(&self.started_at))?;
+        optional_timestamp("completed_at", text(&self.completed_at))?;
+        optional_timestamp("deleted_at", text(&self.deleted_at))?;
+        non_negative("actual_min", self.actual_min.flatten())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Plan {
+    #[serde(default)]
+    pub id: String,
+    #[serde(
+        default,
+        deserialize_with = "non_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub title: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "non_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub plan_type: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "non_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub priority: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub description: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub due_at: Patch<String>,
+    /// 유형별 부가 정보 (예: event의 start_at)
+    #[serde(default = "empty_object")]
+    pub metadata: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+impl Validate for Plan {
+    fn validate(&self) -> DbResult<()> {
+        optional_uuid("id", &self.id)?;
+        if let Some(title) = &self.title {
+            required("title", title)?;
+        }
+        if let Some(plan_type) = &self.plan_type {
+            one_of("plan_type", plan_type, PLAN_TYPES)?;
+        }
+        if let Some(status) = &self.status {
+            one_of("status", status, WORK_STATUSES)?;
+        }
+        if let Some(priority) = text(&self.priority) {
+            one_of("priority", priority, PRIORITIES)?;
+        }
+        optional_timestamp("due_at", text(&self.due_at))?;
+        json_object("metadata", &self.metadata)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Goal {
+    #[serde(default)]
+    pub id: String,
+    #[serde(
+        default,
+        deserialize_with = "non_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub title: Option<String>,
+    /// 상위: project_id / plan_id 중 정확히 하나 (Supabase goals_parent_xor)
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub project_id: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub plan_id: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "non_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub status: Option<String>,
+    /// 1(높음) ~ 5(낮음)
+    #[serde(
+        default,
+        deserialize_with = "non_null",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub priority: Option<i64>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub description: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub due_at: Patch<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+impl Validate for Goal {
+    fn validate(&self) -> DbResult<()> {
+        optional_uuid("id", &self.id)?;
+        if let Some(title) = &self.title {
+            required("title", title)?;
+        }
+        if let Some(project_id) = text(&self.project_id) {
+            optional_uuid("project_id", project_id)?;
+        }
+        if let Some(plan_id) = text(&self.plan_id) {
+            optional_uuid("plan_id", plan_id)?;
+        }
+        if let Some(status) = &self.status {
+            one_of("status", status, GOAL_STATUSES)?;
+        }
+        if let Some(priority) = self.priority {
+            if !(1..=5).contains(&priority) {
+                return Err(invalid("priority", format!("1 ~ 5 범위 밖: {}", priority)));
+            }
+        }
+        optional_timestamp("due_at", text(&self.due_at))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SessionLog {
+    #[serde(default)]
+    pub id: String,
+    /// 원격 필수 (없으면 로컬에만 보관)
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub work_item_id: Patch<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ended_at: Patch<String>,
+    /// done / partial / blocked
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub result: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub done_log: Patch<String>,
+    // 로컬 전용: 에이전트 세션 기록 (원격 컬럼 없음, push하지 않음)
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub project_id: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub editor_type: Patch<String>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration_min: Patch<i64>,
+    #[serde(
+        default,
+        deserialize_with = "patch",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub summary: Patch<String>,
+    #[serde(default = "empty_object")]
+    pub metadata: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+impl Validate for SessionLog {
+    fn validate(&self) -> DbResult<()> {
+        optional_uuid("id", &self.id)?;
+        if let Some(work_item_id) = text(&self.work_item_id) {
+            optional_uuid("work_item_id", work_item_id)?;
+        }
+        optional_timestamp("started_at", self.started_at.as_deref())?;
+        optional_timestamp("ended_at", text(&self.ended_at))?;
+        if let Some(result) = text(&self.result) {
+            one_of("result", result, SESSION_LOG_RESULTS)?;
+        }
+        non_negative("duration_min", self.duration_min.flatten())?;
+        json_object("metadata", &self.metadata)
+    }
+}
+
+/// 컬럼명 + 값 (None: 입력에 없음, Some(Null): NULL로 비움)
+pub type Column = (&'static str, Option<rusqlite::types::Value>);
+
+fn column<T: Clone + Into<rusqlite::types::Value>>(
+    name: &'static str,
+    value: &Option<T>,
+) -> Column {
+    (name, value.clone().map(Into::into))
+}
+
+fn patch_column<T: Clone + Into<rusqlite::types::Value>>(
+    name: &'static str,
+    value: &Patch<T>,
+) -> Column {
+    (name, value.clone().map(rusqlite::types::Value::from))
+}
+
+/// 동기화 대상 테이블 레코드 (테이블명으로 타입 결정)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum SyncRecord {
+    WorkItem(WorkItem),
+    Plan(Plan),
+    Goal(Goal),
+    SessionLog(SessionLog),
+}
+
+impl SyncRecord {
+    /// 테이블별 타입으로 변환 + 검증
+    pub fn decode(table_name: &str, value: serde_json::Value) -> DbResult<Self> {
+        Ok(match table_name {
+            "work_items" => SyncRecord::WorkItem(decode(value)?),
+            "plans" => SyncRecord::Plan(decode(value)?),
+            "goals" => SyncRecord::Goal(decode(value)?),
+            "session_logs" => SyncRecord::SessionLog(decode(value)?),
+            other => {
+                return Err(invalid(
+                    "table_name",
+                    format!("허용되지 않은 테이블: {}", other),
+                ))
+            }
+        })
+    }
+
+    pub fn table_name(&self) -> &'static str {
+        match self {
+            SyncRecord::WorkItem(_) => "work_items",
+            SyncRecord::Plan(_) => "plans",
+            SyncRecord::Goal(_) => "goals",
+            SyncRecord::SessionLog(_) => "session_logs",
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            SyncRecord::WorkItem(r) => &r.id,
+            SyncRecord::Plan(r) => &r.id,
+            SyncRecord::Goal(r) => &r.id,
+            SyncRecord::SessionLog(r) => &r.id,
+        }
+    }
+
+    pub fn set_id(&mut self, id: String) {
+        match self {
+            SyncRecord::WorkItem(r) => r.id = id,
+            SyncRecord::Plan(r) => r.id = id,
+            SyncRecord::Goal(r) => r.id = id,
+            SyncRecord::SessionLog(r) => r.id = id,
+        }
+    }
+
+    /// metadata 컬럼이 있는 테이블만 (plans, session_logs)
+    pub fn metadata(&self) -> Option<&serde_json::Value> {
+        match self {
+            SyncRecord::Plan(r) => Some(&r.metadata),
+            SyncRecord::SessionLog(r) => Some(&r.metadata),
+            SyncRecord::WorkItem(_) | SyncRecord::Goal(_) => None,
+        }
+    }
+
+    /// 입력 필드 → 컬럼 값 (id/metadata/sync_status/타임스탬프 제외)
+    /// None: 입력에 없음 → 유지, Some(Null): 비움
+    pub fn columns(&self) -> Vec<Column> {
+        match self {
+            SyncRecord::WorkItem(r) => vec![
+                column("title", &r.title),
+                column("status", &r.status),
+                patch_column("project_id", &r.project_id),
+                patch_column("next_action", &r.next_action),
+                patch_column("estimate_min", &r.estimate_min),
+                patch_column("energy", &r.energy),
+                patch_column("due_at", &r.due_at),
+                patch_column("source_app", &r.source_app),
+                patch_column("source_ref", &r.source_ref),
+                patch_column("goal_id", &r.goal_id),
+                patch_column("started_at", &r.started_at),
+                patch_column("completed_at", &r.completed_at),
+                patch_column("deleted_at", &r.deleted_at),
+                patch_column("actual_min", &r.actual_min),
+            ],
+            SyncRecord::Plan(r) => vec![
+                column("title", &r.title),
+                column("plan_type", &r.plan_type),
+                column("status", &r.status),
+                patch_column("priority", &r.priority),
+                patch_column("description", &r.description),
+                patch_column("due_at", &r.due_at),
+            ],
+            SyncRecord::Goal(r) => vec![
+                column("title", &r.title),
+                patch_column("project_id", &r.project_id),
+                patch_column("plan_id", &r.plan_id),
+                column("status", &r.status),
+                column("priority", &r.priority),
+                patch_column("description", &r.description),
+                patch_column("due_at", &r.due_at),
+            ],
+            SyncRecord::SessionLog(r) => vec![
+                patch_column("work_item_id", &r.work_item_id),
+                column("started_at", &r.started_at),
+                patch_column("ended_at", &r.ended_at),
+                patch_column("result", &r.result),
+                patch_column("done_log", &r.done_log),
+                patch_column("project_id", &r.project_id),
+                patch_column("editor_type", &r.editor_type),
+                patch_column("duration_min", &r.duration_min),
+                patch_column("summary", &r.summary),
+            ],
+        }
+    }
+
+    /// 저장될 행 전체 검증 (수정은 기존 행과 병합한 결과): 필수 값 + goals 상위 XOR
+    pub fn validate_row(&self) -> DbResult<()> {
+        let title = match self {
+            SyncRecord::WorkItem(r) => &r.title,
+            SyncRecord::Plan(r) => &r.title,
+            SyncRecord::Goal(r) => &r.title,
+            SyncRecord::SessionLog(_) => return Ok(()),
+        };
+        required("title", title.as_deref().unwrap_or_default())?;
+        if let SyncRecord::Goal(goal) = self {
+            // Supabase goals_parent_xor
+            if text(&goal.project_id).is_some() == text(&goal.plan_id).is_some() {
+                return Err(invalid(
+                    "project_id",
+                    "project_id / plan_id 중 정확히 하나 필요",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// 저장된 행 → 레코드 (값 검증 없음: 원격에서 받은 행도 그대로 조회)
+    pub fn from_stored(table_name: &str, value: serde_json::Value) -> DbResult<Self> {
+        Ok(match table_name {
+            "work_items" => SyncRecord::WorkItem(parse(value)?),
+            "plans" => SyncRecord::Plan(parse(value)?),
+            "goals" => SyncRecord::Goal(parse(value)?),
+            "session_logs" => SyncRecord::SessionLog(parse(value)?),
+            other => {
+                return Err(invalid(
+                    "table_name",
+                    format!("허용되지 않은 테이블: {}", other),
+                ))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project_json() -> serde_json::Value {
+        serde_json::json!({
+            "id": "6f1c2b8e-3f4a-4d2b-9a53-1f9a2c7d8e10",
+            "repo_id": 42,
+            "repo_name": "orchestrator",
+            "repo_full_name": "jungjipdo/orchestrator",
+            "repo_url": "https://github.com/jungjipdo/orchestrator",
+            "created_at": "2026-01-01T00:00:00Z",
+        })
+    }
+
+    fn field_of(err: DbError) -> String {
+        match err {
+            DbError::Invalid { field, .. } => field,
+            other => panic!("검증 에러가 아님: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_project_defaults_and_field_errors() {
+        let project: Project = decode(project_json()).unwrap();
+        assert_eq!(project.default_branch, "main");
+        assert_eq!(project.status, "active");
+        assert_eq!(project.metadata, serde_json::json!({}));
+
+        let mut missing = project_json();
+        missing.as_object_mut().unwrap().remove("repo_full_name");
+        assert_eq!(
+            field_of(decode::<Project>(missing).unwrap_err()),
+            "repo_full_name"
+        );
+
+        let mut typo = project_json();
+        typo["repo_ful_name"] = serde_json::json!("x/y");
+        assert_eq!(
+            field_of(decode::<Project>(typo).unwrap_err()),
+            "repo_ful_name"
+        );
+
+        let mut wrong_type = project_json();
+        wrong_type["is_private"] = serde_json::json!("yes");
+        assert_eq!(
+            field_of(decode::<Project>(wrong_type).unwrap_err()),
+            "is_private"
+        );
+
+        let mut empty = project_json();
+        empty["repo_full_name"] = serde_json::json!("");
+        assert_eq!(
+            field_of(decode::<Project>(empty).unwrap_err()),
+            "repo_full_name"
+        );
+
+        let mut status = project_json();
+        status["status"] = serde_json::json!("deleted");
+        assert_eq!(field_of(decode::<Project>(status).unwrap_err()), "status");
+    }
+
+    #[test]
+    fn test_sync_record_decode() {
+        let item = SyncRecord::decode(
+            "work_items",
+            serde_json::json!({ "title": "Ship", "due_at": "2026-03-01" }),
+        )
+        .unwrap();
+        assert!(matches!(item, SyncRecord::WorkItem(ref w) if w.id.is_empty()));
+
+        let err = SyncRecord::decode(
+            "work_items",
+            serde_json::json!({ "title": "W", "energy": "extreme" }),
+        )
+        .unwrap_err();
+        assert_eq!(field_of(err), "energy");
+
+        // 원격에 없는 상태값/컬럼 거부
+        let err = SyncRecord::decode(
+            "work_items",
+            serde_json::json!({ "title": "W", "status": "open" }),
+        )
+        .unwrap_err();
+        assert_eq!(field_of(err), "status");
+        let err = SyncRecord::decode(
+            "goals",
+            serde_json::json!({
+                "title": "G",
+                "plan_id": "0b8e7f4c-2d1a-4c3b-8e9f-5a6b7c8d9e0f",
+                "progress": 40
+            }),
+        )
+        .unwrap_err();
+        assert_eq!(field_of(err), "progress");
+        let err = SyncRecord::decode(
+            "session_logs",
+            serde_json::json!({ "work_item_id": "w1", "result": "done" }),
+        )
+        .unwrap_err();
+        assert_eq!(field_of(err), "work_item_id");
+
+        let err = SyncRecord::decode(
+            "plans",
+            serde_json::json!({ "title": "P", "due_at": "next week" }),
+        )
+        .unwrap_err();
+        assert_eq!(field_of(err), "due_at");
+
+        // 키 없음 / null / 값 구분 → null은 그대로 직렬화 (컬럼 비움)
+        let item: WorkItem = decode(
+            serde_json::json!({ "title": "W", "next_action": null, "due_at": "2026-03-01" }),
+        )
+        .unwrap();
+        assert_eq!(item.next_action, Some(None));
+        assert_eq!(item.goal_id, None);
+        assert_eq!(item.due_at, Some(Some("2026-03-01".to_string())));
+        let value = serde_json::to_value(&item).unwrap();
+        assert!(value["next_action"].is_null() && value.get("next_action").is_some());
+        assert!(value.get("goal_id").is_none());
+
+        let err = SyncRecord::decode("projects", serde_json::json!({})).unwrap_err();
+        assert_eq!(field_of(err), "table_name");
+
+        let score = ModelScore {
+            id: String::new(),
+            model_key: "gpt".to_string(),
+            coding: 101.0,
+            analysis: 50.0,
+            documentation: 50.0,
+            speed: 50.0,
+            updated_at: None,
+        };
+        assert_eq!(field_of(score.validate().unwrap_err()), "coding");
+    }
+
+    #[test]
+    fn test_goal_parent_and_priority() {
+        let plan_id = "0b8e7f4c-2d1a-4c3b-8e9f-5a6b7c8d9e0f";
+        let project_id = "6f1c2b8e-3f4a-4d2b-9a53-1f9a2c7d8e10";
+
+        // 기본값은 삽입 시 스키마에서 → 입력에 없는 필드는 None (수정 시 기존 값 유지)
+        let goal: Goal =
+            decode(serde_json::json!({ "title": "G", "project_id": project_id })).unwrap();
+        assert_eq!(goal.priority, None);
+        assert_eq!(goal.status, None);
+        let row = |value| SyncRecord::decode("goals", value).unwrap().validate_row();
+        assert!(row(serde_json::json!({ "title": "G", "project_id": project_id })).is_ok());
+
+        // 상위 없음 / 둘 다 → goals_parent_xor 위반
+        let err = row(serde_json::json!({ "title": "G" })).unwrap_err();
+        assert_eq!(field_of(err), "project_id");
+        let err = row(serde_json::json!({
+            "title": "G", "project_id": project_id, "plan_id": plan_id
+        }))
+        .unwrap_err();
+        assert_eq!(field_of(err), "project_id");
+        let err = row(serde_json::json!({ "plan_id": plan_id })).unwrap_err();
+        assert_eq!(field_of(err), "title");
+
+        // null 불가 필드에 null → 해당 필드 에러
+        let err = decode::<Goal>(serde_json::json!({ "status": null })).unwrap_err();
+        assert_eq!(field_of(err), "status");
+
+        let err = decode::<Goal>(serde_json::json!({
+            "title": "G", "plan_id": plan_id, "priority": 6
+        }))
+        .unwrap_err();
+        assert_eq!(field_of(err), "priority");
+
+        let err =
+            decode::<WorkItem>(serde_json::json!({ "title": "W", "actual_min": -5 })).unwrap_err();
+        assert_eq!(field_of(err), "actual_min");
+
+        assert_eq!(
+            parse_timestamp("2026-03-01 09:30:00"),
+            parse_timestamp("2026-03-01T18:30:00+09:00")
+        );
+    }
+}
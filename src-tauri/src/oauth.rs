@@ -1,93 +1,473 @@
+// ===========================================
+// oauth.rs — 루프백 OAuth 로그인 (PKCE + state 검증)
+// 127.0.0.1 랜덤 포트 콜백 서버 → authorize URL 생성 → 일치하는 콜백까지 대기(타임아웃)
+// code → 토큰 교환은 Rust에서 (Supabase /auth/v1/token?grant_type=pkce)
+// ===========================================
+
+use crate::auth_session::{self, AuthTokens};
+use crate::sync_client::{SupabaseConfig, SyncClient};
+use base64::Engine;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{Emitter, Manager};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpListener;
+use tokio::net::{TcpListener, TcpStream};
 
-/// 로컬 콜백 서버 상태
-pub struct OAuthServer {
-    port: u16,
+/// 콜백 경로
+const CALLBACK_PATH: &str = "/auth/callback";
+/// 로그인 전체 대기 시간 (초)
+pub const LOGIN_TIMEOUT_SECS: u64 = 300;
+/// 연결 1건 요청 읽기 제한 (초) — 열어만 두는 연결이 대기를 막지 않도록
+const READ_TIMEOUT_SECS: u64 = 5;
+/// 요청 헤더 최대 크기
+const MAX_REQUEST_BYTES: usize = 8192;
+
+const SUCCESS_HTML: &str = r#"<!DOCTYPE html><html><head><meta charset="utf-8"><style>
+    body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;display:flex;align-items:center;justify-content:center;height:100vh;margin:0;background:#f8f9fa}
+    .card{background:white;padding:48px 56px;border-radius:20px;text-align:center;box-shadow:0 8px 32px rgba(0,0,0,0.08);max-width:380px}
+    .logo{width:48px;height:48px;background:#1a1a1a;border-radius:12px;display:flex;align-items:center;justify-content:center;margin:0 auto 24px;color:white;font-size:20px}
+    h1{font-size:22px;font-weight:700;margin:0 0 8px;color:#1a1a1a}
+    p{color:#6b7280;font-size:14px;margin:0;line-height:1.5}
+    .closing{color:#10b981;font-weight:600}
+</style></head><body><div class="card">
+    <div class="logo">O</div>
+    <h1>✅ 인증 완료</h1>
+    <p class="closing">이 창은 자동으로 닫힙니다...</p>
+    <p style="margin-top:12px">자동으로 닫히지 않으면 이 탭을 직접 닫아주세요.</p>
+</div>
+<script>setTimeout(function(){window.close()},1500)</script>
+</body></html>"#;
+
+const DENIED_HTML: &str = r#"<!DOCTYPE html><html><head><meta charset="utf-8"><style>
+    body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;display:flex;align-items:center;justify-content:center;height:100vh;margin:0;background:#f8f9fa}
+    .card{background:white;padding:48px 56px;border-radius:20px;text-align:center;box-shadow:0 8px 32px rgba(0,0,0,0.08);max-width:380px}
+    h1{font-size:22px;font-weight:700;margin:0 0 8px;color:#1a1a1a}
+    p{color:#6b7280;font-size:14px;margin:0;line-height:1.5}
+</style></head><body><div class="card">
+    <h1>❌ 인증 실패</h1>
+    <p>Orchestrator 앱으로 돌아가 다시 시도해주세요.</p>
+</div></body></html>"#;
+
+/// PKCE verifier + S256 challenge
+#[derive(Debug, Clone)]
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
 }
 
-impl OAuthServer {
-    /// 사용 가능한 랜덤 포트에 바인딩
-    pub async fn new() -> Result<(Self, TcpListener), Box<dyn std::error::Error>> {
-        let listener = TcpListener::bind("127.0.0.1:0").await?;
-        let port = listener.local_addr()?.port();
-        Ok((Self { port }, listener))
+impl Pkce {
+    pub fn generate() -> Result<Self, String> {
+        Ok(Self::from_verifier(random_token(32)?))
     }
 
-    pub fn callback_url(&self) -> String {
-        format!("http://127.0.0.1:{}/auth/callback", self.port)
+    fn from_verifier(verifier: String) -> Self {
+        let digest = Sha256::digest(verifier.as_bytes());
+        let challenge = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest);
+        Self {
+            verifier,
+            challenge,
+        }
     }
 }
 
-/// 단일 요청만 받는 콜백 서버 실행
-/// Chrome이 code를 가지고 리디렉트하면 받아서 프론트엔드에 emit
-pub async fn start_callback_server(
+/// OS 난수 → base64url (패딩 없음)
+fn random_token(bytes: usize) -> Result<String, String> {
+    let mut buf = vec![0u8; bytes];
+    getrandom::getrandom(&mut buf).map_err(|e| format!("난수 생성 실패: {}", e))?;
+    Ok(base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(buf))
+}
+
+/// 콜백 요청 분류
+#[derive(Debug, PartialEq)]
+enum Callback {
+    /// 콜백 경로가 아님 (favicon 등) → 404 후 계속 대기
+    Stray,
+    /// state 불일치/누락, code 없음 → 400 후 계속 대기
+    Invalid(&'static str),
+    /// 사용자가 거부했거나 provider 오류
+    Denied {
+        error: String,
+        description: Option<String>,
+    },
+    Code(String),
+}
+
+/// 요청 헤더 → 콜백 분류 (쿼리는 URL 디코딩)
+fn parse_callback(request: &str, expected_state: &str) -> Callback {
+    let mut parts = request.lines().next().unwrap_or("").split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(m), Some(t)) => (m, t),
+        _ => return Callback::Invalid("잘못된 요청"),
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if method != "GET" || path != CALLBACK_PATH {
+        return Callback::Stray;
+    }
+
+    let mut state = None;
+    let mut code = None;
+    let mut error = None;
+    let mut description = None;
+    for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+        match key.as_ref() {
+            "state" => state = Some(value.into_owned()),
+            "code" => code = Some(value.into_owned()),
+            "error" => error = Some(value.into_owned()),
+            "error_description" => description = Some(value.into_owned()),
+            _ => {}
+        }
+    }
+
+    if state.as_deref() != Some(expected_state) {
+        return Callback::Invalid("state 불일치");
+    }
+    if let Some(error) = error {
+        return Callback::Denied { error, description };
+    }
+    match code {
+        Some(code) if !code.is_empty() => Callback::Code(code),
+        _ => Callback::Invalid("code 없음"),
+    }
+}
+
+/// 진행 중인 로그인 1건 (콜백 서버 + PKCE + state)
+pub struct OAuthLogin {
     listener: TcpListener,
-    app_handle: tauri::AppHandle,
-) {
-    // 1회 연결만 수락
-    if let Ok((mut stream, _)) = listener.accept().await {
-        let mut buf = vec![0u8; 4096];
-        if let Ok(n) = stream.read(&mut buf).await {
-            let request = String::from_utf8_lossy(&buf[..n]);
-
-            // GET /auth/callback?code=XXXX HTTP/1.1 에서 code 추출
-            if let Some(code) = extract_code(&request) {
-                // 성공 HTML 응답 — 인증 완료 후 브라우저 탭 자동 닫기
-                let html = r#"<!DOCTYPE html><html><head><meta charset="utf-8"><style>
-                    body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;display:flex;align-items:center;justify-content:center;height:100vh;margin:0;background:#f8f9fa}
-                    .card{background:white;padding:48px 56px;border-radius:20px;text-align:center;box-shadow:0 8px 32px rgba(0,0,0,0.08);max-width:380px}
-                    .logo{width:48px;height:48px;background:#1a1a1a;border-radius:12px;display:flex;align-items:center;justify-content:center;margin:0 auto 24px;color:white;font-size:20px}
-                    h1{font-size:22px;font-weight:700;margin:0 0 8px;color:#1a1a1a}
-                    p{color:#6b7280;font-size:14px;margin:0;line-height:1.5}
-                    .closing{color:#10b981;font-weight:600}
-                </style></head><body><div class="card">
-                    <div class="logo">O</div>
-                    <h1>✅ 인증 완료</h1>
-                    <p class="closing">이 창은 자동으로 닫힙니다...</p>
-                    <p style="margin-top:12px">자동으로 닫히지 않으면 이 탭을 직접 닫아주세요.</p>
-                </div>
-                <script>setTimeout(function(){window.close()},1500)</script>
-                </body></html>"#;
-
-                let response = format!(
-                    "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-                    html.len(),
-                    html
-                );
-                let _ = stream.write_all(response.as_bytes()).await;
-                let _ = stream.flush().await;
-
-                // Tauri 메인 윈도우 포커스 (앱으로 자동 전환)
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.set_focus();
+    port: u16,
+    state: String,
+    pkce: Pkce,
+}
+
+impl OAuthLogin {
+    /// 사용 가능한 랜덤 포트에 바인딩 + PKCE/state 생성
+    pub async fn bind() -> Result<Self, String> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| format!("서버 시작 실패: {}", e))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("서버 시작 실패: {}", e))?
+            .port();
+        Ok(Self {
+            listener,
+            port,
+            state: random_token(16)?,
+            pkce: Pkce::generate()?,
+        })
+    }
+
+    /// 콜백 URL (state는 redirect_to 쿼리로 왕복)
+    pub fn redirect_uri(&self) -> String {
+        format!(
+            "http://127.0.0.1:{}{}?state={}",
+            self.port, CALLBACK_PATH, self.state
+        )
+    }
+
+    /// Supabase authorize URL (PKCE S256)
+    pub fn authorize_url(
+        &self,
+        config: &SupabaseConfig,
+        provider: &str,
+        scopes: Option<&str>,
+    ) -> String {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+        query
+            .append_pair("provider", provider)
+            .append_pair("redirect_to", &self.redirect_uri())
+            .append_pair("code_challenge", &self.pkce.challenge)
+            .append_pair("code_challenge_method", "s256");
+        if let Some(scopes) = scopes {
+            query.append_pair("scopes", scopes);
+        }
+        format!(
+            "{}/auth/v1/authorize?{}",
+            config.url.trim_end_matches('/'),
+            query.finish()
+        )
+    }
+
+    /// state가 일치하는 콜백이 올 때까지 대기 → code
+    pub async fn wait_for_code(&self, timeout: Duration) -> Result<String, String> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let (mut stream, _) = tokio::time::timeout(remaining, self.listener.accept())
+                .await
+                .map_err(|_| "OAuth 로그인 시간 초과".to_string())?
+                .map_err(|e| format!("콜백 연결 실패: {}", e))?;
+
+            let request = match read_request_head(&mut stream).await {
+                Some(r) => r,
+                None => continue,
+            };
+            match parse_callback(&request, &self.state) {
+                Callback::Stray => {
+                    respond(&mut stream, "404 Not Found", "text/plain", "Not Found").await;
                 }
+                Callback::Invalid(reason) => {
+                    log::warn!("⚠ OAuth 콜백 무시: {}", reason);
+                    respond(&mut stream, "400 Bad Request", "text/plain", reason).await;
+                }
+                Callback::Denied { error, description } => {
+                    respond(
+                        &mut stream,
+                        "200 OK",
+                        "text/html; charset=utf-8",
+                        DENIED_HTML,
+                    )
+                    .await;
+                    return Err(match description {
+                        Some(d) => format!("OAuth 거부: {} ({})", error, d),
+                        None => format!("OAuth 거부: {}", error),
+                    });
+                }
+                Callback::Code(code) => {
+                    respond(
+                        &mut stream,
+                        "200 OK",
+                        "text/html; charset=utf-8",
+                        SUCCESS_HTML,
+                    )
+                    .await;
+                    return Ok(code);
+                }
+            }
+        }
+    }
+}
 
-                // 프론트엔드에 code 전달
-                let _ = app_handle.emit("oauth-callback", code);
-            } else {
-                let body = "Missing code parameter";
-                let response = format!(
-                    "HTTP/1.1 400 Bad Request\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-                    body.len(),
-                    body
-                );
-                let _ = stream.write_all(response.as_bytes()).await;
+/// 요청 헤더까지 읽기 (시간 초과/크기 초과/연결 종료 시 None)
+async fn read_request_head(stream: &mut TcpStream) -> Option<String> {
+    let read = async {
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 1024];
+        loop {
+            let n = stream.read(&mut chunk).await.ok()?;
+            if n == 0 {
+                return None;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+            if buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                return Some(String::from_utf8_lossy(&buf).to_string());
+            }
+            if buf.len() > MAX_REQUEST_BYTES {
+                return None;
             }
         }
+    };
+    tokio::time::timeout(Duration::from_secs(READ_TIMEOUT_SECS), read)
+        .await
+        .ok()
+        .flatten()
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.flush().await;
+}
+
+/// 로그인 결과 (프론트엔드 oauth-session 페이로드)
+#[derive(Debug, Clone, Serialize)]
+pub struct OAuthSession {
+    #[serde(flatten)]
+    pub tokens: AuthTokens,
+    /// GitHub access token (github_connections 동기화용)
+    pub provider_token: Option<String>,
+    pub provider_refresh_token: Option<String>,
+}
+
+/// code + verifier → 세션
+pub async fn exchange_code(
+    config: &SupabaseConfig,
+    code: &str,
+    verifier: &str,
+) -> Result<OAuthSession, String> {
+    let url = format!("{}/auth/v1/token", config.url.trim_end_matches('/'));
+    let resp = reqwest::Client::new()
+        .post(&url)
+        .query(&[("grant_type", "pkce")])
+        .header("apikey", &config.anon_key)
+        .json(&serde_json::json!({
+            "auth_code": code,
+            "code_verifier": verifier,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("토큰 교환 네트워크 오류: {}", e))?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!("토큰 교환 실패 ({}): {}", status, body));
     }
+    let body: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("토큰 응답 파싱 실패: {}", e))?;
+    Ok(OAuthSession {
+        tokens: auth_session::parse_token_response(&body, auth_session::unix_now())?,
+        provider_token: body["provider_token"].as_str().map(str::to_string),
+        provider_refresh_token: body["provider_refresh_token"].as_str().map(str::to_string),
+    })
 }
 
-fn extract_code(request: &str) -> Option<String> {
-    let first_line = request.lines().next()?;
-    let path = first_line.split_whitespace().nth(1)?;
-    let query = path.split('?').nth(1)?;
+/// 콜백 대기 → 토큰 교환 → Rust 세션 반영 → oauth-session / oauth-error emit
+pub async fn complete_login(app: tauri::AppHandle, login: OAuthLogin, client: Arc<SyncClient>) {
+    let result = match login
+        .wait_for_code(Duration::from_secs(LOGIN_TIMEOUT_SECS))
+        .await
+    {
+        Ok(code) => exchange_code(client.config(), &code, &login.pkce.verifier).await,
+        Err(e) => Err(e),
+    };
+
+    // Tauri 메인 윈도우 포커스 (앱으로 자동 전환)
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_focus();
+    }
 
-    for param in query.split('&') {
-        if let Some(value) = param.strip_prefix("code=") {
-            return Some(value.to_string());
+    match result {
+        Ok(session) => {
+            log::info!("🔑 OAuth 로그인 완료: {}", session.tokens.user_id);
+            client.sign_in(app.clone(), session.tokens.clone());
+            if let Err(e) = app.emit("oauth-session", &session) {
+                log::warn!("  ❌ Tauri emit 실패: {}", e);
+            }
+        }
+        Err(e) => {
+            log::warn!("⚠ OAuth 로그인 실패: {}", e);
+            if let Err(e) = app.emit("oauth-error", &e) {
+                log::warn!("  ❌ Tauri emit 실패: {}", e);
+            }
         }
     }
-    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkce_challenge_matches_rfc7636() {
+        // RFC 7636 Appendix B
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string());
+        assert_eq!(
+            pkce.challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+
+        let generated = Pkce::generate().unwrap();
+        assert_eq!(generated.verifier.len(), 43);
+        assert_ne!(generated.verifier, Pkce::generate().unwrap().verifier);
+    }
+
+    #[test]
+    fn test_parse_callback() {
+        let get = |target: &str| format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1\r\n\r\n", target);
+
+        assert_eq!(parse_callback(&get("/favicon.ico"), "s1"), Callback::Stray);
+        assert_eq!(
+            parse_callback(&get("/auth/callback?state=other&code=c"), "s1"),
+            Callback::Invalid("state 불일치")
+        );
+        assert_eq!(
+            parse_callback(&get("/auth/callback?code=c"), "s1"),
+            Callback::Invalid("state 불일치")
+        );
+        assert_eq!(
+            parse_callback(&get("/auth/callback?state=s1"), "s1"),
+            Callback::Invalid("code 없음")
+        );
+        assert_eq!(
+            parse_callback(&get("/auth/callback?state=s1&code=a%2Fb%3D"), "s1"),
+            Callback::Code("a/b=".to_string())
+        );
+        assert_eq!(
+            parse_callback(
+                &get(
+                    "/auth/callback?state=s1&error=access_denied&error_description=User+denied%21"
+                ),
+                "s1"
+            ),
+            Callback::Denied {
+                error: "access_denied".to_string(),
+                description: Some("User denied!".to_string()),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authorize_url() {
+        let mut login = OAuthLogin::bind().await.unwrap();
+        login.port = 4321;
+        login.state = "st".to_string();
+        login.pkce = Pkce::from_verifier("v".to_string());
+        let config = SupabaseConfig {
+            url: "https://abc.supabase.co/".to_string(),
+            anon_key: "anon".to_string(),
+        };
+        let url = login.authorize_url(&config, "github", Some("repo read:user"));
+        let parsed = url::Url::parse(&url).unwrap();
+        assert_eq!(parsed.path(), "/auth/v1/authorize");
+        let params: std::collections::HashMap<_, _> = parsed.query_pairs().into_owned().collect();
+        assert_eq!(params["provider"], "github");
+        assert_eq!(
+            params["redirect_to"],
+            "http://127.0.0.1:4321/auth/callback?state=st"
+        );
+        assert_eq!(params["code_challenge"], login.pkce.challenge);
+        assert_eq!(params["code_challenge_method"], "s256");
+        assert_eq!(params["scopes"], "repo read:user");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_code_skips_stray_requests() {
+        let login = OAuthLogin::bind().await.unwrap();
+        let addr = format!("127.0.0.1:{}", login.port);
+        let state = login.state.clone();
+
+        let browser = tokio::spawn(async move {
+            let mut statuses = Vec::new();
+            for target in [
+                "/favicon.ico".to_string(),
+                "/auth/callback?state=forged&code=evil".to_string(),
+                format!("/auth/callback?state={}&code=good%2Bcode", state),
+            ] {
+                let mut stream = TcpStream::connect(&addr).await.unwrap();
+                let request = format!("GET {} HTTP/1.1\r\nHost: {}\r\n\r\n", target, addr);
+                stream.write_all(request.as_bytes()).await.unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).await.unwrap();
+                statuses.push(response.lines().next().unwrap_or("").to_string());
+            }
+            statuses
+        });
+
+        let code = login.wait_for_code(Duration::from_secs(5)).await.unwrap();
+        assert_eq!(code, "good+code");
+        let statuses = browser.await.unwrap();
+        assert_eq!(statuses[0], "HTTP/1.1 404 Not Found");
+        assert_eq!(statuses[1], "HTTP/1.1 400 Bad Request");
+        assert_eq!(statuses[2], "HTTP/1.1 200 OK");
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_code_times_out() {
+        let login = OAuthLogin::bind().await.unwrap();
+        let err = login
+            .wait_for_code(Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(err.contains("시간 초과"));
+    }
 }
@@ -3,6 +3,7 @@
 // 앱 재시작 시 git diff + timestamp로 변경 감지
 // ===========================================
 
+use crate::ignore_rules::IgnoreMatcher;
 use crate::session;
 use std::path::Path;
 use std::process::Command;
@@ -47,14 +48,12 @@ fn detect_timestamp_changes(
     let since_systime = std::time::SystemTime::from(*since);
     let mut changed = Vec::new();
 
-    let ignored = ["node_modules", ".git", "dist", "build", ".next", "target", ".orchestrator"];
+    // watcher와 동일한 제외 규칙 (.gitignore / .orchestrator/ignore)
+    let matcher = IgnoreMatcher::new(project_path);
 
     let walker = walkdir::WalkDir::new(project_path)
         .into_iter()
-        .filter_entry(|e| {
-            let name = e.file_name().to_string_lossy();
-            !ignored.iter().any(|i| *i == name.as_ref())
-        });
+        .filter_entry(|e| !matcher.is_ignored(e.path(), e.file_type().is_dir()));
 
     for entry in walker.filter_map(|e| e.ok()) {
         if !entry.file_type().is_file() {
@@ -0,0 +1,761 @@
+// ===========================================
+// realtime.rs — Supabase Realtime 구독 (Phoenix channel over WebSocket)
+// agent_tasks / plans / work_items 변경 → local_db 반영 → Tauri 이벤트 + 알림
+// 연결이 끊기면 backoff 재연결, 사용자가 바뀌면 세션 교체 (상태는 orchx:realtime-status)
+// 토큰이 갱신되면 채널에 access_token 전달 (RLS 유지)
+// WebSocket 핸드셰이크/프레임은 tokio-tungstenite
+// ===========================================
+
+use crate::auth_session::AuthSession;
+use crate::local_db::{LocalDb, RemoteApply};
+use crate::sync_client::SupabaseConfig;
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio_tungstenite::tungstenite::Message;
+
+/// 구독 테이블
+pub const REALTIME_TABLES: &[&str] = &["agent_tasks", "plans", "work_items"];
+/// 채널 토픽
+const CHANNEL_TOPIC: &str = "realtime:orchx-desktop";
+/// Phoenix heartbeat 주기 (초)
+const HEARTBEAT_SECS: u64 = 25;
+/// 이 시간 동안 아무것도 받지 못하면 연결 끊김으로 판단 (초)
+const IDLE_TIMEOUT_SECS: u64 = HEARTBEAT_SECS * 2;
+/// 재연결 backoff 상한 (초)
+const RECONNECT_MAX_SECS: u64 = 60;
+/// 알림 본문 최대 글자 수
+const NOTIFY_BODY_CHARS: usize = 120;
+
+/// 구독 대상 사용자
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    pub user_id: String,
+    /// 사용자 JWT (없으면 anon key → RLS로 걸러질 수 있음)
+    pub access_token: Option<String>,
+}
+
+/// 구독 상태 (orchx:realtime-status 페이로드)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum RealtimeStatus {
+    /// 채널 join 성공
+    Connected,
+    /// 연결 종료/실패 → retry_in_secs 후 재연결
+    Reconnecting {
+        attempt: u32,
+        retry_in_secs: u64,
+        error: Option<String>,
+    },
+    /// 구독 종료 (중지/로그아웃, 토큰 갱신 실패 시 reason)
+    Stopped { reason: Option<String> },
+}
+
+/// postgres_changes 한 건
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RemoteChange {
+    pub table: String,
+    /// INSERT | UPDATE | DELETE
+    pub change_type: String,
+    pub record: serde_json::Value,
+    pub old_record: serde_json::Value,
+    pub commit_timestamp: Option<String>,
+}
+
+impl RemoteChange {
+    fn record_id(&self) -> Option<&str> {
+        self.record["id"]
+            .as_str()
+            .or_else(|| self.old_record["id"].as_str())
+    }
+}
+
+/// 서버 메시지 분류
+#[derive(Debug, Clone, PartialEq)]
+enum Inbound {
+    Joined,
+    JoinFailed(String),
+    Change(RemoteChange),
+    ChannelClosed(String),
+    Other,
+}
+
+/// Supabase URL → Realtime WebSocket URL
+pub fn websocket_url(config: &SupabaseConfig) -> String {
+    let base = config.url.trim_end_matches('/');
+    let base = if let Some(rest) = base.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = base.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        base.to_string()
+    };
+    format!(
+        "{}/realtime/v1/websocket?apikey={}&vsn=1.0.0",
+        base, config.anon_key
+    )
+}
+
+/// phx_join — 테이블별 postgres_changes (사용자 행만)
+fn join_message(sub: &Subscription, anon_key: &str, msg_ref: u64) -> serde_json::Value {
+    let changes: Vec<serde_json::Value> = REALTIME_TABLES
+        .iter()
+        .map(|table| {
+            serde_json::json!({
+                "event": "*",
+                "schema": "public",
+                "table": table,
+                "filter": format!("user_id=eq.{}", sub.user_id),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "topic": CHANNEL_TOPIC,
+        "event": "phx_join",
+        "payload": {
+            "config": {
+                "broadcast": { "self": false },
+                "presence": { "key": "" },
+                "postgres_changes": changes,
+            },
+            "access_token": sub.access_token.as_deref().unwrap_or(anon_key),
+        },
+        "ref": msg_ref.to_string(),
+        "join_ref": msg_ref.to_string(),
+    })
+}
+
+fn heartbeat_message(msg_ref: u64) -> serde_json::Value {
+    serde_json::json!({
+        "topic": "phoenix",
+        "event": "heartbeat",
+        "payload": {},
+        "ref": msg_ref.to_string(),
+    })
+}
+
+/// 갱신된 사용자 JWT → 채널 (만료 전에 보내야 구독 유지)
+fn access_token_message(access_token: &str, msg_ref: u64) -> serde_json::Value {
+    serde_json::json!({
+        "topic": CHANNEL_TOPIC,
+        "event": "access_token",
+        "payload": { "access_token": access_token },
+        "ref": msg_ref.to_string(),
+    })
+}
+
+fn parse_inbound(text: &str) -> Inbound {
+    let msg: serde_json::Value = match serde_json::from_str(text) {
+        Ok(v) => v,
+        Err(_) => return Inbound::Other,
+    };
+    // heartbeat 응답(topic: phoenix) 등은 무시
+    if msg["topic"] != CHANNEL_TOPIC {
+        return Inbound::Other;
+    }
+    let payload = &msg["payload"];
+    match msg["event"].as_str().unwrap_or_default() {
+        "phx_reply" if payload["status"] == "ok" => Inbound::Joined,
+        "phx_reply" => Inbound::JoinFailed(payload["response"].to_string()),
+        // postgres_changes 설정 오류는 system 이벤트로 옴
+        "system" if payload["status"] == "error" => {
+            Inbound::JoinFailed(payload["message"].as_str().unwrap_or("unknown").to_string())
+        }
+        "postgres_changes" => {
+            let data = &payload["data"];
+            match (data["table"].as_str(), data["type"].as_str()) {
+                (Some(table), Some(change_type)) => Inbound::Change(RemoteChange {
+                    table: table.to_string(),
+                    change_type: change_type.to_string(),
+                    record: data["record"].clone(),
+                    old_record: data["old_record"].clone(),
+                    commit_timestamp: data["commit_timestamp"].as_str().map(String::from),
+                }),
+                _ => Inbound::Other,
+            }
+        }
+        event @ ("phx_error" | "phx_close") => Inbound::ChannelClosed(event.to_string()),
+        _ => Inbound::Other,
+    }
+}
+
+/// 변경을 local_db에 반영, 로컬이 실제로 바뀌었으면 true
+/// (로컬 미전송 변경/충돌은 건드리지 않음 → 다음 sync 주기의 pull이 처리)
+pub fn apply_change(db: &LocalDb, change: &RemoteChange) -> Result<bool, String> {
+    // agent_tasks는 로컬 테이블 없음 → 이벤트만
+    if !crate::sync_engine::is_sync_table(&change.table) {
+        return Ok(false);
+    }
+    match change.change_type.as_str() {
+        "INSERT" | "UPDATE" => db
+            .apply_remote_row(&change.table, &change.record)
+            .map(|r| r == RemoteApply::Applied)
+            .map_err(|e| e.to_string()),
+        "DELETE" => match change.record_id() {
+            Some(id) => db
+                .delete_remote_row(&change.table, id)
+                .map_err(|e| e.to_string()),
+            None => Ok(false),
+        },
+        _ => Ok(false),
+    }
+}
+
+/// 연속 실패 횟수 → 재연결 대기 (1s, 2s, 4s … 최대 60s)
+fn reconnect_backoff(attempt: u32) -> Duration {
+    let exp = attempt.saturating_sub(1).min(16);
+    Duration::from_secs((1u64 << exp).min(RECONNECT_MAX_SECS))
+}
+
+/// 연결 1회: 핸드셰이크 → join → 수신 루프
+/// 서버 종료/중지 시 Ok(join 성공 여부), 연결 오류 시 Err
+async fn run_session(
+    url: &str,
+    sub: &Subscription,
+    anon_key: &str,
+    current_token: &(dyn Fn() -> Option<String> + Sync),
+    is_current: &(dyn Fn() -> bool + Sync),
+    on_change: &mut (dyn FnMut(RemoteChange) + Send),
+    on_status: &mut (dyn FnMut(RealtimeStatus) + Send),
+) -> Result<bool, String> {
+    let (mut ws, _) = tokio_tungstenite::connect_async(url)
+        .await
+        .map_err(|e| format!("연결 실패: {}", e))?;
+    let send_error = |e: tokio_tungstenite::tungstenite::Error| format!("전송 실패: {}", e);
+
+    let mut msg_ref = 1u64;
+    ws.send(Message::text(
+        join_message(sub, anon_key, msg_ref).to_string(),
+    ))
+    .await
+    .map_err(send_error)?;
+    let mut sent_token = sub.access_token.clone();
+
+    let mut joined = false;
+    let mut last_heartbeat = Instant::now();
+    let mut last_received = Instant::now();
+    loop {
+        if !is_current() {
+            let _ = ws.close(None).await;
+            return Ok(joined);
+        }
+        if last_received.elapsed() >= Duration::from_secs(IDLE_TIMEOUT_SECS) {
+            return Err("heartbeat 응답 없음".to_string());
+        }
+        if last_heartbeat.elapsed() >= Duration::from_secs(HEARTBEAT_SECS) {
+            msg_ref += 1;
+            ws.send(Message::text(heartbeat_message(msg_ref).to_string()))
+                .await
+                .map_err(send_error)?;
+            last_heartbeat = Instant::now();
+
+            if let Some(token) = current_token().filter(|t| Some(t) != sent_token.as_ref()) {
+                msg_ref += 1;
+                ws.send(Message::text(
+                    access_token_message(&token, msg_ref).to_string(),
+                ))
+                .await
+                .map_err(send_error)?;
+                sent_token = Some(token);
+            }
+        }
+
+        // 1초마다 깨어나 중지/heartbeat 확인 (ping 응답은 tungstenite가 처리)
+        let message = tokio::select! {
+            message = ws.next() => message,
+            _ = tokio::time::sleep(Duration::from_secs(1)) => continue,
+        };
+        let message = match message {
+            Some(Ok(message)) => message,
+            Some(Err(e)) => return Err(format!("수신 실패: {}", e)),
+            None => return Ok(joined),
+        };
+        last_received = Instant::now();
+
+        match message {
+            Message::Text(text) => match parse_inbound(text.as_str()) {
+                Inbound::Joined if !joined => {
+                    joined = true;
+                    log::info!("📡 Realtime 구독 시작 ({})", REALTIME_TABLES.join(", "));
+                    on_status(RealtimeStatus::Connected);
+                }
+                Inbound::JoinFailed(reason) => {
+                    let _ = ws.close(None).await;
+                    return Err(format!("구독 실패: {}", reason));
+                }
+                Inbound::ChannelClosed(event) => {
+                    log::warn!("⚠ Realtime 채널 종료: {}", event);
+                    let _ = ws.close(None).await;
+                    return Ok(joined);
+                }
+                Inbound::Change(change) => on_change(change),
+                _ => {}
+            },
+            Message::Close(_) => return Ok(joined),
+            _ => {}
+        }
+    }
+}
+
+/// 재연결 루프: 연결마다 구독 대상을 새로 받아 run_session → 실패하면 backoff 후 재시도
+/// subscription이 None(로그아웃)이나 Err(토큰 갱신 실패)를 주거나 세대가 바뀌면 종료
+async fn subscribe_loop<S, F>(
+    url: &str,
+    anon_key: &str,
+    mut subscription: S,
+    current_token: &(dyn Fn() -> Option<String> + Sync),
+    is_current: &(dyn Fn() -> bool + Sync),
+    on_change: &mut (dyn FnMut(RemoteChange) + Send),
+    on_status: &mut (dyn FnMut(RealtimeStatus) + Send),
+) where
+    S: FnMut() -> F,
+    F: Future<Output = Result<Option<Subscription>, String>>,
+{
+    let mut attempt = 0u32;
+    let reason = loop {
+        if !is_current() {
+            break None;
+        }
+        let sub = match subscription().await {
+            Ok(Some(sub)) => sub,
+            Ok(None) => break None,
+            Err(e) => {
+                log::warn!("⚠ Realtime 중지: {}", e);
+                break Some(e);
+            }
+        };
+        let result = run_session(
+            url,
+            &sub,
+            anon_key,
+            current_token,
+            is_current,
+            on_change,
+            on_status,
+        )
+        .await;
+        match result {
+            Ok(true) => attempt = 0,
+            Ok(false) => attempt += 1,
+            Err(ref e) => {
+                attempt += 1;
+                log::warn!("⚠ Realtime 연결 끊김: {}", e);
+            }
+        }
+        if !is_current() {
+            break None;
+        }
+        let retry_in = reconnect_backoff(attempt.max(1));
+        on_status(RealtimeStatus::Reconnecting {
+            attempt,
+            retry_in_secs: retry_in.as_secs(),
+            error: result.err(),
+        });
+        tokio::time::sleep(retry_in).await;
+    };
+    on_status(RealtimeStatus::Stopped { reason });
+}
+
+/// 변경 1건 처리: local_db 반영 → orchx:remote-change emit (+ 새 agent task 알림)
+fn handle_change(app: &tauri::AppHandle, db: &LocalDb, change: RemoteChange) {
+    let applied = apply_change(db, &change).unwrap_or_else(|e| {
+        log::warn!("⚠ Realtime {} 반영 실패: {}", change.table, e);
+        false
+    });
+
+    if change.table == "agent_tasks" && change.change_type == "INSERT" {
+        notify_agent_task(app, &change.record);
+    }
+
+    let event = serde_json::json!({
+        "table": change.table,
+        "change_type": change.change_type,
+        "record_id": change.record_id(),
+        "record": change.record,
+        "applied": applied,
+    });
+    if let Err(e) = app.emit("orchx:remote-change", &event) {
+        log::warn!("  ❌ Tauri emit 실패: {}", e);
+    }
+}
+
+/// 웹 대시보드에서 할당된 작업 → 데스크톱 알림 + 트레이 툴팁
+fn notify_agent_task(app: &tauri::AppHandle, record: &serde_json::Value) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let instruction: String = record["instruction"]
+        .as_str()
+        .unwrap_or("새 작업")
+        .chars()
+        .take(NOTIFY_BODY_CHARS)
+        .collect();
+    log::info!("🤖 새 에이전트 작업: {}", instruction);
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("🤖 새 에이전트 작업")
+        .body(&instruction)
+        .show()
+    {
+        log::warn!("⚠ 알림 표시 실패: {}", e);
+    }
+    if let Some(tray) = app.tray_by_id(crate::sync_worker::TRAY_ID) {
+        let _ = tray.set_tooltip(Some(
+            format!("Orchestrator — 새 작업: {}", instruction).as_str(),
+        ));
+    }
+}
+
+/// 구독 세션 관리 (시작/중지 시 세대 증가 → 이전 루프는 스스로 종료)
+#[derive(Default)]
+pub struct Realtime {
+    generation: Arc<AtomicU64>,
+}
+
+impl Realtime {
+    /// 로그인 사용자 구독 시작 (기존 구독은 교체, 로그아웃되면 종료)
+    pub fn start(
+        &self,
+        app: tauri::AppHandle,
+        config: SupabaseConfig,
+        db: Arc<LocalDb>,
+        auth: Arc<AuthSession>,
+    ) {
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let current = self.generation.clone();
+
+        tauri::async_runtime::spawn(async move {
+            let url = websocket_url(&config);
+            let is_current = move || current.load(Ordering::SeqCst) == generation;
+            let current_token = {
+                let auth = auth.clone();
+                move || auth.current_access_token()
+            };
+            // 재연결마다 만료 임박 토큰 갱신
+            let subscription = move || {
+                let auth = auth.clone();
+                async move {
+                    let access_token = auth.access_token().await?;
+                    Ok(auth.user_id().map(|user_id| Subscription {
+                        user_id,
+                        access_token: Some(access_token),
+                    }))
+                }
+            };
+            let mut on_change = |change: RemoteChange| handle_change(&app, &db, change);
+            let mut on_status = |status: RealtimeStatus| {
+                if let Err(e) = app.emit("orchx:realtime-status", &status) {
+                    log::warn!("  ❌ Tauri emit 실패: {}", e);
+                }
+            };
+            subscribe_loop(
+                &url,
+                &config.anon_key,
+                subscription,
+                &current_token,
+                &is_current,
+                &mut on_change,
+                &mut on_status,
+            )
+            .await;
+            log::info!("📡 Realtime 구독 종료");
+        });
+    }
+
+    pub fn stop(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+
+    #[test]
+    fn test_websocket_url_and_join_message() {
+        let config = SupabaseConfig {
+            url: "https://abc.supabase.co/".to_string(),
+            anon_key: "anon".to_string(),
+        };
+        assert_eq!(
+            websocket_url(&config),
+            "wss://abc.supabase.co/realtime/v1/websocket?apikey=anon&vsn=1.0.0"
+        );
+
+        let sub = Subscription {
+            user_id: "u1".to_string(),
+            access_token: None,
+        };
+        let join = join_message(&sub, "anon", 1);
+        assert_eq!(join["payload"]["access_token"], "anon");
+        let changes = join["payload"]["config"]["postgres_changes"]
+            .as_array()
+            .unwrap();
+        assert_eq!(changes.len(), REALTIME_TABLES.len());
+        assert_eq!(changes[0]["filter"], "user_id=eq.u1");
+
+        let refresh = access_token_message("jwt2", 3);
+        assert_eq!(refresh["topic"], CHANNEL_TOPIC);
+        assert_eq!(refresh["event"], "access_token");
+        assert_eq!(refresh["payload"]["access_token"], "jwt2");
+    }
+
+    #[test]
+    fn test_parse_inbound() {
+        let msg = |event: &str, payload: serde_json::Value| {
+            serde_json::json!({ "topic": CHANNEL_TOPIC, "event": event, "payload": payload })
+                .to_string()
+        };
+        assert_eq!(
+            parse_inbound(&msg("phx_reply", serde_json::json!({ "status": "ok" }))),
+            Inbound::Joined
+        );
+        assert!(matches!(
+            parse_inbound(&msg(
+                "system",
+                serde_json::json!({ "status": "error", "message": "bad filter" })
+            )),
+            Inbound::JoinFailed(reason) if reason == "bad filter"
+        ));
+        assert_eq!(
+            parse_inbound(&msg("phx_error", serde_json::json!({}))),
+            Inbound::ChannelClosed("phx_error".to_string())
+        );
+        let heartbeat_reply =
+            serde_json::json!({ "topic": "phoenix", "event": "phx_reply", "payload": {} });
+        assert_eq!(parse_inbound(&heartbeat_reply.to_string()), Inbound::Other);
+        assert_eq!(reconnect_backoff(1), Duration::from_secs(1));
+        assert_eq!(
+            reconnect_backoff(30),
+            Duration::from_secs(RECONNECT_MAX_SECS)
+        );
+    }
+
+    fn server_text(value: serde_json::Value) -> Message {
+        Message::text(value.to_string())
+    }
+
+    fn change_message(table: &str, change_type: &str, record: serde_json::Value) -> Message {
+        server_text(serde_json::json!({
+            "topic": CHANNEL_TOPIC,
+            "event": "postgres_changes",
+            "payload": {
+                "data": {
+                    "schema": "public",
+                    "table": table,
+                    "type": change_type,
+                    "record": record,
+                    "old_record": { "id": record["id"] },
+                    "commit_timestamp": "2025-01-01T00:00:00Z",
+                },
+                "ids": [1],
+            },
+        }))
+    }
+
+    #[tokio::test]
+    // accept_hdr_async 콜백 시그니처가 tungstenite ErrorResponse를 요구
+    #[allow(clippy::result_large_err)]
+    async fn test_session_against_mock_server() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // mock Realtime 서버: 핸드셰이크 → join 응답 → 변경 2건 → close
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut request = String::new();
+            let mut ws =
+                tokio_tungstenite::accept_hdr_async(socket, |req: &Request, resp: Response| {
+                    request = format!("{} {}", req.method(), req.uri());
+                    Ok(resp)
+                })
+                .await
+                .unwrap();
+
+            let join = loop {
+                if let Message::Text(text) = ws.next().await.unwrap().unwrap() {
+                    break serde_json::from_str::<serde_json::Value>(text.as_str()).unwrap();
+                }
+            };
+
+            ws.send(server_text(serde_json::json!({
+                "topic": CHANNEL_TOPIC,
+                "event": "phx_reply",
+                "payload": { "status": "ok", "response": {} },
+                "ref": join["ref"],
+            })))
+            .await
+            .unwrap();
+            ws.send(change_message(
+                "plans",
+                "INSERT",
+                serde_json::json!({
+                    "id": "p1",
+                    "title": "From dashboard",
+                    "status": "backlog",
+                    "updated_at": "2025-01-01T00:00:00+00:00",
+                }),
+            ))
+            .await
+            .unwrap();
+            ws.send(change_message(
+                "agent_tasks",
+                "INSERT",
+                serde_json::json!({ "id": "t1", "instruction": "Fix the build" }),
+            ))
+            .await
+            .unwrap();
+            let _ = ws.close(None).await;
+            (request, join)
+        });
+
+        let url = format!("ws://{}/realtime/v1/websocket?apikey=anon&vsn=1.0.0", addr);
+        let sub = Subscription {
+            user_id: "u1".to_string(),
+            access_token: Some("jwt".to_string()),
+        };
+        let mut changes = Vec::new();
+        let mut statuses = Vec::new();
+        let joined = run_session(
+            &url,
+            &sub,
+            "anon",
+            &|| Some("jwt".to_string()),
+            &|| true,
+            &mut |c| changes.push(c),
+            &mut |s| statuses.push(s),
+        )
+        .await
+        .unwrap();
+        assert!(joined);
+        assert_eq!(statuses, [RealtimeStatus::Connected]);
+
+        let (request, join) = server.await.unwrap();
+        assert_eq!(request, "GET /realtime/v1/websocket?apikey=anon&vsn=1.0.0");
+        assert_eq!(join["event"], "phx_join");
+        assert_eq!(join["payload"]["access_token"], "jwt");
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].table, "plans");
+        assert_eq!(changes[1].table, "agent_tasks");
+
+        // local_db 반영: plans는 저장, agent_tasks는 이벤트만
+        let tmp = std::env::temp_dir().join("orchestrator_realtime_test.db");
+        let _ = std::fs::remove_file(&tmp);
+        let db = LocalDb::open_at(&tmp).unwrap();
+        assert!(apply_change(&db, &changes[0]).unwrap());
+        assert!(!apply_change(&db, &changes[1]).unwrap());
+        let record = db.get_record("plans", "p1").unwrap().unwrap();
+        assert_eq!(record["title"], "From dashboard");
+        assert_eq!(record["sync_status"], "synced");
+
+        let delete = RemoteChange {
+            change_type: "DELETE".to_string(),
+            record: serde_json::Value::Null,
+            ..changes[0].clone()
+        };
+        assert!(apply_change(&db, &delete).unwrap());
+        assert!(db.get_record("plans", "p1").unwrap().is_none());
+
+        drop(db);
+        let _ = std::fs::remove_file(&tmp);
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_and_reports_status_on_bad_handshake() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        // 101 응답이지만 Sec-WebSocket-Accept가 키와 맞지 않는 서버 (연결마다 같은 응답)
+        let server = tokio::spawn(async move {
+            let mut accepted = 0;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                accepted += 1;
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buf.extend_from_slice(&chunk[..n]);
+                }
+                let _ = socket
+                    .write_all(
+                        b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+                          Connection: Upgrade\r\nSec-WebSocket-Accept: mock\r\n\r\n",
+                    )
+                    .await;
+                if accepted == 2 {
+                    break;
+                }
+            }
+            accepted
+        });
+
+        let url = format!("ws://{}/realtime/v1/websocket", addr);
+        let subscribed = AtomicU64::new(0);
+        let statuses = std::sync::Mutex::new(Vec::new());
+        // 재연결 2회 보고 후 중지
+        let is_current = || statuses.lock().unwrap().len() < 2;
+        subscribe_loop(
+            &url,
+            "anon",
+            || {
+                subscribed.fetch_add(1, Ordering::SeqCst);
+                async {
+                    Ok(Some(Subscription {
+                        user_id: "u1".to_string(),
+                        access_token: None,
+                    }))
+                }
+            },
+            &|| None,
+            &is_current,
+            &mut |_| {},
+            &mut |s| statuses.lock().unwrap().push(s),
+        )
+        .await;
+
+        assert_eq!(server.await.unwrap(), 2);
+        assert_eq!(subscribed.load(Ordering::SeqCst), 2);
+        let statuses = statuses.into_inner().unwrap();
+        assert_eq!(statuses.len(), 3);
+        for (i, status) in statuses[..2].iter().enumerate() {
+            let attempt = i as u32 + 1;
+            assert!(matches!(
+                status,
+                RealtimeStatus::Reconnecting { attempt: a, retry_in_secs, error: Some(e) }
+                    if *a == attempt
+                        && *retry_in_secs == reconnect_backoff(attempt).as_secs()
+                        && e.starts_with("연결 실패")
+            ));
+        }
+        assert_eq!(statuses[2], RealtimeStatus::Stopped { reason: None });
+    }
+
+    #[tokio::test]
+    async fn test_stops_when_token_refresh_fails() {
+        let mut statuses = Vec::new();
+        subscribe_loop(
+            "ws://127.0.0.1:9/realtime/v1/websocket",
+            "anon",
+            || async { Err("refresh token 만료".to_string()) },
+            &|| None,
+            &|| true,
+            &mut |_| {},
+            &mut |s| statuses.push(s),
+        )
+        .await;
+        assert_eq!(
+            statuses,
+            [RealtimeStatus::Stopped {
+                reason: Some("refresh token 만료".to_string())
+            }]
+        );
+    }
+}
@@ -1,33 +1,253 @@
 // ===========================================
 // session.rs — 세션 관리 (orchx session 재작성)
-// .orchestrator/session.json 읽기/쓰기
+// 레지스트리: .orchestrator/sessions/<id>.json (작업 트리당 여러 에이전트 동시 세션)
+//           + .orchestrator/session.json (orchx CLI 단일 세션, 레지스트리 항목으로 취급)
+// 시작 / 종료 / 에이전트 간 인계 (종료된 세션은 호출자가 session_logs에 보관)
+// 쓰기: session.json.lock(orchx CLI와 공유) 아래에서 임시 파일 → rename
 // ===========================================
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-/// 실행 계약서 (allowed_paths, allowed_commands)
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// 잠금 대기 한도 (넘으면 쓰기 실패)
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+/// 이보다 오래된 잠금 파일 = 비정상 종료한 프로세스가 남긴 것
+const LOCK_STALE: Duration = Duration::from_secs(10);
+/// 파일 변경/커밋 통계 기록 주기 (그 사이 변경은 병합)
+pub const STATS_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 위험도 등급 (agent_tasks.risk_tier)
+/// low: 스타일/텍스트, mid: 로직 변경, high: DB/API/보안
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskTier {
+    Low,
+    #[default]
+    Mid,
+    High,
+}
+
+/// 계약 규칙 심각도 (info < warn < block)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warn,
+    Block,
+}
+
+/// 경로별 심각도 지정 (glob 패턴)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PathSeverity {
+    pub pattern: String,
+    pub severity: Severity,
+}
+
+/// 실행 계약서 (allowed/denied paths, allowed_commands, 위험도)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct ExecutionContract {
     #[serde(default)]
     pub allowed_paths: Vec<String>,
     #[serde(default)]
     pub allowed_commands: Vec<String>,
+    /// 항상 allowed_paths보다 우선하는 금지 경로
+    #[serde(default)]
+    pub denied_paths: Vec<String>,
+    #[serde(default)]
+    pub risk_tier: RiskTier,
+    /// 위반 심각도 재지정 (첫 매칭 우선)
+    #[serde(default)]
+    pub path_severity: Vec<PathSeverity>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_minutes: Option<u64>,
 }
 
+/// 세션 예산 (None = 제한 없음)
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct Budget {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_minutes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_files: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_commits: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+}
+
+impl Budget {
+    pub fn is_empty(&self) -> bool {
+        self.max_minutes.is_none()
+            && self.max_files.is_none()
+            && self.max_commits.is_none()
+            && self.max_tokens.is_none()
+    }
+}
+
+/// orchx session start와 같은 에이전트 종류
+pub const AGENT_TYPES: &[&str] = &[
+    "cursor",
+    "claude_code",
+    "codex",
+    "windsurf",
+    "copilot",
+    "antigravity",
+    "custom",
+];
+
+/// 세션 종료 결과 (orchx session end --result)
+pub const SESSION_RESULTS: &[&str] = &["success", "failure", "partial", "timeout"];
+
 /// orchx 세션 정보
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
     pub session_id: String,
     pub agent_type: String,
     pub task_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_path: Option<String>,
     #[serde(default)]
     pub files_changed: u64,
     #[serde(default)]
     pub commits_detected: u64,
     #[serde(default)]
     pub execution_contract: Option<ExecutionContract>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<Budget>,
+    /// 에이전트가 보고한 누적 토큰 사용량
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_used: Option<u64>,
+    /// 인계받은 이전 세션 ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handoff_from: Option<String>,
+}
+
+/// 종료된 세션 (session_logs 보관용)
+#[derive(Debug, Clone, Serialize)]
+pub struct FinishedSession {
+    pub session: Session,
+    pub ended_at: String,
+    pub duration_min: i64,
+    /// success | failure | partial | timeout | handoff
+    pub result: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// 인계 대상 에이전트 (result = handoff)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub handoff_to: Option<String>,
+}
+
+impl FinishedSession {
+    fn new(
+        session: Session,
+        result: &str,
+        summary: Option<String>,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        let duration_min = session
+            .started_at_utc()
+            .map(|started| {
+                ((now - started).num_seconds() as f64 / 60.0)
+                    .round()
+                    .max(0.0) as i64
+            })
+            .unwrap_or(0);
+        FinishedSession {
+            session,
+            ended_at: now.to_rfc3339(),
+            duration_min,
+            result: result.to_string(),
+            summary,
+            handoff_to: None,
+        }
+    }
+
+    /// session_logs.result (Supabase: done / partial / blocked)
+    fn log_result(&self) -> &'static str {
+        match self.result.as_str() {
+            "success" => "done",
+            "partial" | "handoff" => "partial",
+            _ => "blocked",
+        }
+    }
+
+    /// session_logs 행 (id = session_id, editor_type = agent_type)
+    /// 작업 항목(work_item_id)과 연결되지 않은 세션은 로컬에만 보관
+    pub fn to_session_log(&self, project_id: Option<String>) -> crate::models::SessionLog {
+        let s = &self.session;
+        let mut metadata = serde_json::json!({
+            "task_name": s.task_name,
+            "result": self.result,
+            "files_changed": s.files_changed,
+            "commits_detected": s.commits_detected,
+        });
+        let optional = [
+            ("tokens_used", s.tokens_used.map(serde_json::Value::from)),
+            (
+                "handoff_from",
+                s.handoff_from.clone().map(serde_json::Value::from),
+            ),
+            (
+                "handoff_to",
+                self.handoff_to.clone().map(serde_json::Value::from),
+            ),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                metadata[key] = value;
+            }
+        }
+        crate::models::SessionLog {
+            id: s.session_id.clone(),
+            work_item_id: None,
+            started_at: Some(
+                s.started_at
+                    .clone()
+                    .unwrap_or_else(|| self.ended_at.clone()),
+            ),
+            ended_at: Some(Some(self.ended_at.clone())),
+            result: Some(Some(self.log_result().to_string())),
+            done_log: Some(self.summary.clone()),
+            project_id: Some(project_id),
+            editor_type: Some(Some(s.agent_type.clone())),
+            duration_min: Some(Some(self.duration_min)),
+            summary: Some(self.summary.clone()),
+            metadata,
+            sync_status: None,
+            created_at: None,
+        }
+    }
+}
+
+impl Session {
+    /// 세션 예산 + 계약서 예산(budget_minutes/budget_tokens) 병합
+    /// 세션에 직접 지정된 값이 우선
+    pub fn effective_budget(&self) -> Budget {
+        let mut budget = self.budget.clone().unwrap_or_default();
+        if let Some(ref contract) = self.execution_contract {
+            budget.max_minutes = budget.max_minutes.or(contract.budget_minutes);
+            budget.max_tokens = budget.max_tokens.or(contract.budget_tokens);
+        }
+        budget
+    }
+
+    /// 세션 시작 시각 (started_at 파싱)
+    pub fn started_at_utc(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let raw = self.started_at.as_deref()?;
+        chrono::DateTime::parse_from_rfc3339(raw)
+            .ok()
+            .map(|t| t.with_timezone(&chrono::Utc))
+    }
 }
 
 /// .orchestrator 디렉토리 경로
@@ -35,28 +255,426 @@ fn orchestrator_dir(project_path: &Path) -> PathBuf {
     project_path.join(".orchestrator")
 }
 
-/// 세션 파일 경로
-fn session_file(project_path: &Path) -> PathBuf {
+/// 단일 세션 파일 경로 (orchx CLI 호환)
+pub fn session_file(project_path: &Path) -> PathBuf {
     orchestrator_dir(project_path).join("session.json")
 }
 
-/// 세션 읽기 — 없으면 None
-pub fn read_session(project_path: &Path) -> Option<Session> {
-    let path = session_file(project_path);
-    let content = fs::read_to_string(&path).ok()?;
-    serde_json::from_str(&content).ok()
+/// 세션 레지스트리 디렉토리
+pub fn sessions_dir(project_path: &Path) -> PathBuf {
+    orchestrator_dir(project_path).join("sessions")
+}
+
+fn registry_file(project_path: &Path, session_id: &str) -> PathBuf {
+    sessions_dir(project_path).join(format!("{}.json", session_id))
+}
+
+/// 세션 파일 여부 (session.json 또는 sessions/*.json, 임시/잠금 파일 제외)
+pub fn is_session_file(project_path: &Path, path: &Path) -> bool {
+    *path == session_file(project_path)
+        || (path.parent() == Some(sessions_dir(project_path).as_path())
+            && path.extension().map(|e| e == "json").unwrap_or(false))
+}
+
+fn lock_file(project_path: &Path) -> PathBuf {
+    orchestrator_dir(project_path).join("session.json.lock")
+}
+
+/// 세션 파일 권고 잠금 (레지스트리 포함 작업 트리 전체) — 잠금 파일 배타 생성
+/// (orchx CLI도 같은 파일 사용), 내용 "<pid> <토큰>", drop 시 해제
+struct SessionLock {
+    path: PathBuf,
+    token: String,
+}
+
+impl SessionLock {
+    fn acquire(project_path: &Path) -> Result<Self, String> {
+        fs::create_dir_all(orchestrator_dir(project_path)).map_err(|e| e.to_string())?;
+        let path = lock_file(project_path);
+        let token = format!("{} {}", std::process::id(), uuid::Uuid::new_v4());
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    let _ = file.write_all(token.as_bytes());
+                    return Ok(SessionLock { path, token });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if steal_stale_lock(&path) {
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(format!("세션 잠금 대기 시간 초과: {}", path.display()));
+                    }
+                    std::thread::sleep(Duration::from_millis(20));
+                }
+                Err(e) => return Err(format!("세션 잠금 실패: {}", e)),
+            }
+        }
+    }
+}
+
+impl Drop for SessionLock {
+    fn drop(&mut self) {
+        // 오래 붙잡는 사이 다른 프로세스가 빼앗아 새로 만든 잠금은 지우지 않음
+        if fs::read_to_string(&self.path).ok().as_deref() == Some(self.token.as_str()) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn is_stale_lock(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.elapsed().ok())
+        .map(|age| age > LOCK_STALE)
+        .unwrap_or(false)
+}
+
+/// 오래된 잠금 빼앗기 (orchx CLI와 같은 규약) — true면 즉시 생성 재시도
+/// 1. 고유 이름으로 rename: 원자적이라 같은 잠금은 한 프로세스만 옮김
+/// 2. 옮긴 파일이 판정한 그 잠금인지(같은 내용 + 여전히 오래됨) 재확인 후 제거
+/// 3. 판정 뒤 새로 생긴 잠금을 옮겼으면 원래 이름으로 복원 (그새 생긴 잠금은 덮어쓰지 않음)
+fn steal_stale_lock(path: &Path) -> bool {
+    if !is_stale_lock(path) {
+        return false;
+    }
+    let Ok(seen) = fs::read_to_string(path) else {
+        return true;
+    };
+    let grabbed = path.with_extension(format!("lock.stale.{}", uuid::Uuid::new_v4()));
+    if fs::rename(path, &grabbed).is_err() {
+        // 다른 프로세스가 먼저 옮김
+        return true;
+    }
+    if is_stale_lock(&grabbed) && fs::read_to_string(&grabbed).ok() == Some(seen) {
+        log::warn!("⚠ 오래된 세션 잠금 제거: {}", path.display());
+    } else {
+        let _ = fs::hard_link(&grabbed, path);
+    }
+    let _ = fs::remove_file(&grabbed);
+    true
+}
+
+/// 세션 파일 읽기 — 없으면 Ok(None), 읽을 수 없거나 손상됐으면 Err
+/// (잠금 없이 쓰는 이전 CLI의 쓰기 도중일 수 있어 한 번 재시도)
+pub fn load_session_file(path: &Path) -> Result<Option<Session>, String> {
+    let mut retried = false;
+    loop {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("{} 읽기 실패: {}", path.display(), e)),
+        };
+        match serde_json::from_str(&content) {
+            Ok(session) => return Ok(Some(session)),
+            Err(_) if !retried => {
+                retried = true;
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(e) => return Err(format!("세션 파일 손상 ({}): {}", path.display(), e)),
+        }
+    }
+}
+
+/// 활성 세션 목록 (session.json + 레지스트리, 시작 시각 순)
+#[derive(Debug, Default)]
+pub struct SessionListing {
+    pub sessions: Vec<(PathBuf, Session)>,
+    /// 읽을 수 없거나 손상된 세션 파일
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+pub fn list_sessions(project_path: &Path) -> SessionListing {
+    let mut listing = SessionListing::default();
+    let mut files = vec![session_file(project_path)];
+    if let Ok(entries) = fs::read_dir(sessions_dir(project_path)) {
+        let mut registry: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| is_session_file(project_path, p))
+            .collect();
+        registry.sort();
+        files.extend(registry);
+    }
+    for path in files {
+        match load_session_file(&path) {
+            Ok(Some(session)) => listing.sessions.push((path, session)),
+            Ok(None) => {}
+            Err(e) => listing.errors.push((path, e)),
+        }
+    }
+    listing
+        .sessions
+        .sort_by(|a, b| a.1.started_at.cmp(&b.1.started_at));
+    listing
+}
+
+/// 세션 ID로 찾기 — None이면 활성 세션이 하나일 때만 그 세션
+fn find_session(
+    project_path: &Path,
+    session_id: Option<&str>,
+) -> Result<(PathBuf, Session), String> {
+    let listing = list_sessions(project_path);
+    let found = match session_id {
+        Some(id) => listing
+            .sessions
+            .into_iter()
+            .find(|(_, s)| s.session_id == id),
+        None if listing.sessions.len() > 1 => {
+            return Err("활성 세션이 여러 개 — 세션 ID 필요".to_string())
+        }
+        None => listing.sessions.into_iter().next(),
+    };
+    match (found, listing.errors.into_iter().next()) {
+        (Some(found), _) => Ok(found),
+        // 찾는 세션이 손상된 파일일 수 있음 → "세션 없음" 대신 손상 보고
+        (None, Some((_, e))) => Err(e),
+        (None, None) => Err(match session_id {
+            Some(id) => session_not_found(id),
+            None => "활성 세션 없음".to_string(),
+        }),
+    }
+}
+
+fn session_not_found(session_id: &str) -> String {
+    format!("활성 세션 없음: {}", session_id)
 }
 
-/// 세션 통계 업데이트
-pub fn update_session_stats(project_path: &Path, files_changed: u64, commits_detected: u64) {
-    let path = session_file(project_path);
-    if let Ok(content) = fs::read_to_string(&path) {
-        if let Ok(mut session) = serde_json::from_str::<Session>(&content) {
+/// 임시 파일에 쓰고 rename → 읽는 쪽은 이전/새 내용 중 하나만 봄 (잠금 보유 상태에서 호출)
+fn write_session_file(path: &Path, session: &Session) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(session).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension(format!("json.tmp.{}", std::process::id()));
+    let written = fs::File::create(&tmp).and_then(|mut file| {
+        file.write_all(content.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|_| fs::rename(&tmp, path)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(format!("{} 쓰기 실패: {}", path.display(), e));
+    }
+    Ok(())
+}
+
+fn check_agent_type(agent_type: &str) -> Result<(), String> {
+    if !AGENT_TYPES.contains(&agent_type) {
+        return Err(format!(
+            "알 수 없는 에이전트: {} (가능: {})",
+            agent_type,
+            AGENT_TYPES.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// 세션 시작 결과 — 작업 범위가 겹치는 다른 활성 세션 포함
+#[derive(Debug, Clone, Serialize)]
+pub struct StartedSession {
+    pub session: Session,
+    pub overlaps: Vec<String>,
+}
+
+/// 세션 시작 → 레지스트리에 등록 (다른 에이전트 세션과 동시 진행 가능)
+/// budget: 세션 예산 (계약서의 budget_minutes/budget_tokens와 병합해 집행)
+pub fn start_session(
+    project_path: &Path,
+    agent_type: &str,
+    task_name: &str,
+    contract: Option<ExecutionContract>,
+    budget: Option<Budget>,
+) -> Result<StartedSession, String> {
+    check_agent_type(agent_type)?;
+    if task_name.trim().is_empty() {
+        return Err("작업 이름 필요".to_string());
+    }
+
+    let session = Session {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        agent_type: agent_type.to_string(),
+        task_name: task_name.to_string(),
+        project_path: Some(project_path.to_string_lossy().to_string()),
+        files_changed: 0,
+        commits_detected: 0,
+        execution_contract: contract,
+        started_at: Some(chrono::Utc::now().to_rfc3339()),
+        budget: budget.filter(|b| !b.is_empty()),
+        tokens_used: None,
+        handoff_from: None,
+    };
+    let _lock = SessionLock::acquire(project_path)?;
+    write_session_file(&registry_file(project_path, &session.session_id), &session)?;
+    let active: Vec<Session> = list_sessions(project_path)
+        .sessions
+        .into_iter()
+        .map(|(_, s)| s)
+        .collect();
+    let overlaps = overlapping_sessions(&active, &session);
+    Ok(StartedSession { session, overlaps })
+}
+
+/// 작업 범위(allowed_paths)가 겹치는 다른 활성 세션 ID
+pub fn overlapping_sessions(sessions: &[Session], session: &Session) -> Vec<String> {
+    let scope = |s: &Session| {
+        s.execution_contract
+            .as_ref()
+            .map(|c| c.allowed_paths.clone())
+            .unwrap_or_default()
+    };
+    sessions
+        .iter()
+        .filter(|other| other.session_id != session.session_id)
+        .filter(|other| crate::contract::scopes_overlap(&scope(other), &scope(session)))
+        .map(|other| other.session_id.clone())
+        .collect()
+}
+
+/// 세션 종료 — archive(보관) 성공 후에만 세션 파일 삭제
+/// session_id None = 유일한 활성 세션
+pub fn end_session(
+    project_path: &Path,
+    session_id: Option<&str>,
+    result: &str,
+    summary: Option<String>,
+    archive: impl FnOnce(&FinishedSession) -> Result<(), String>,
+) -> Result<FinishedSession, String> {
+    if !SESSION_RESULTS.contains(&result) {
+        return Err(format!(
+            "알 수 없는 결과: {} (가능: {})",
+            result,
+            SESSION_RESULTS.join(", ")
+        ));
+    }
+    let _lock = SessionLock::acquire(project_path)?;
+    let (path, session) = find_session(project_path, session_id)?;
+    let finished = FinishedSession::new(session, result, summary, chrono::Utc::now());
+    archive(&finished)?;
+    fs::remove_file(&path).map_err(|e| e.to_string())?;
+    Ok(finished)
+}
+
+/// 다른 에이전트로 인계 — 현재 세션은 result=handoff로 종료(archive),
+/// 같은 작업/계약서/예산으로 새 세션 시작 (session.json 세션은 session.json에 이어서)
+pub fn handoff_session(
+    project_path: &Path,
+    session_id: Option<&str>,
+    to_agent: &str,
+    note: Option<String>,
+    archive: impl FnOnce(&FinishedSession) -> Result<(), String>,
+) -> Result<(FinishedSession, Session), String> {
+    check_agent_type(to_agent)?;
+    let _lock = SessionLock::acquire(project_path)?;
+    let (path, current) = find_session(project_path, session_id)?;
+    let now = chrono::Utc::now();
+
+    let next = Session {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        agent_type: to_agent.to_string(),
+        task_name: current.task_name.clone(),
+        project_path: current.project_path.clone(),
+        files_changed: 0,
+        commits_detected: 0,
+        execution_contract: current.execution_contract.clone(),
+        started_at: Some(now.to_rfc3339()),
+        budget: current.budget.clone(),
+        tokens_used: None,
+        handoff_from: Some(current.session_id.clone()),
+    };
+    let mut finished = FinishedSession::new(current, "handoff", note, now);
+    finished.handoff_to = Some(to_agent.to_string());
+
+    archive(&finished)?;
+    if path == session_file(project_path) {
+        write_session_file(&path, &next)?;
+    } else {
+        write_session_file(&registry_file(project_path, &next.session_id), &next)?;
+        fs::remove_file(&path).map_err(|e| e.to_string())?;
+    }
+    Ok((finished, next))
+}
+
+/// 세션 통계 업데이트 — 잠금 아래에서 읽기 → 수정 → 원자적 쓰기
+/// 대상 세션의 파일만 읽음 (다른 세션 파일의 손상은 무관), 이미 끝났으면 "세션 없음" 에러
+pub fn update_session_stats(
+    project_path: &Path,
+    session_id: &str,
+    files_changed: u64,
+    commits_detected: u64,
+) -> Result<(), String> {
+    let _lock = SessionLock::acquire(project_path)?;
+    let registry = registry_file(project_path, session_id);
+    let found = match load_session_file(&registry)? {
+        Some(session) => Some((registry, session)),
+        // session.json은 다른 세션일 수 있음 → 손상돼도 이 세션의 에러로 보지 않음
+        None => {
+            let path = session_file(project_path);
+            load_session_file(&path).ok().flatten().map(|s| (path, s))
+        }
+    };
+    match found {
+        Some((path, mut session)) if session.session_id == session_id => {
             session.files_changed = files_changed;
             session.commits_detected = commits_detected;
-            if let Ok(updated) = serde_json::to_string_pretty(&session) {
-                let _ = fs::write(&path, updated);
+            write_session_file(&path, &session)
+        }
+        _ => Err(session_not_found(session_id)),
+    }
+}
+
+/// 통계 쓰기 병합 — record()는 최신 값만 보관, 백그라운드 스레드가
+/// STATS_FLUSH_INTERVAL마다 세션별로 한 번 기록 (running 해제 시 마지막으로 한 번 더)
+pub struct StatsWriter {
+    pending: Arc<Mutex<HashMap<String, (u64, u64)>>>,
+}
+
+impl StatsWriter {
+    pub fn spawn(
+        project_path: PathBuf,
+        running: Arc<AtomicBool>,
+        on_error: impl Fn(String) + Send + 'static,
+    ) -> Self {
+        let pending: Arc<Mutex<HashMap<String, (u64, u64)>>> = Arc::default();
+        let queue = pending.clone();
+        std::thread::spawn(move || {
+            // 같은 에러는 한 번만 보고
+            let mut last_error: Option<String> = None;
+            loop {
+                std::thread::sleep(STATS_FLUSH_INTERVAL);
+                let stopping = !running.load(Ordering::SeqCst);
+                let batch = queue
+                    .lock()
+                    .map(|mut q| std::mem::take(&mut *q))
+                    .unwrap_or_default();
+                for (session_id, (files, commits)) in batch {
+                    match update_session_stats(&project_path, &session_id, files, commits) {
+                        Ok(()) => last_error = None,
+                        // 그 사이 끝난 세션 → 기록할 곳 없음
+                        Err(e) if e == session_not_found(&session_id) => {}
+                        Err(e) if last_error.as_ref() != Some(&e) => {
+                            on_error(e.clone());
+                            last_error = Some(e);
+                        }
+                        Err(_) => {}
+                    }
+                }
+                if stopping {
+                    break;
+                }
             }
+        });
+        StatsWriter { pending }
+    }
+
+    /// 세션 통계 기록 예약 (다음 flush 때 최신 값으로 한 번)
+    pub fn record(&self, session_id: &str, files_changed: u64, commits_detected: u64) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(session_id.to_string(), (files_changed, commits_detected));
         }
     }
 }
@@ -75,3 +693,328 @@ pub fn read_shutdown_timestamp(project_path: &Path) -> Option<chrono::DateTime<c
     let content = fs::read_to_string(&path).ok()?;
     content.trim().parse().ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_project(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// session.json 읽기 — 없으면 Ok(None), 손상됐으면 Err
+    fn load_session(project_path: &Path) -> Result<Option<Session>, String> {
+        load_session_file(&session_file(project_path))
+    }
+
+    fn scoped(paths: &[&str]) -> Option<ExecutionContract> {
+        Some(ExecutionContract {
+            allowed_paths: paths.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_start_handoff_end() {
+        let dir = temp_project("orchestrator_session_lifecycle_test");
+
+        let first = start_session(&dir, "cursor", "Refactor sync", scoped(&["src/**"]), None)
+            .unwrap()
+            .session;
+        assert!(registry_file(&dir, &first.session_id).exists());
+        assert!(start_session(&dir, "vim", "x", None, None).is_err());
+
+        // 인계: 이전 세션 종료 + 같은 작업/계약서로 새 세션
+        let (handed, second) =
+            handoff_session(&dir, None, "codex", Some("테스트 남음".into()), |_| {
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(handed.result, "handoff");
+        assert_eq!(handed.session.session_id, first.session_id);
+        assert_eq!(
+            second.handoff_from.as_deref(),
+            Some(first.session_id.as_str())
+        );
+        assert!(!registry_file(&dir, &first.session_id).exists());
+        let sessions = list_sessions(&dir).sessions;
+        assert_eq!(sessions.len(), 1);
+        let current = &sessions[0].1;
+        assert_eq!(current.agent_type, "codex");
+        assert_eq!(
+            current.execution_contract.as_ref().unwrap().allowed_paths,
+            vec!["src/**"]
+        );
+
+        let log = handed.to_session_log(Some("p1".to_string()));
+        assert_eq!(log.id, first.session_id);
+        assert_eq!(log.editor_type, Some(Some("cursor".to_string())));
+        assert_eq!(log.metadata["handoff_to"], "codex");
+        assert_eq!(log.result, Some(Some("partial".to_string())));
+
+        // 보관 실패 → 세션 유지
+        assert!(end_session(&dir, None, "success", None, |_| Err("db".to_string())).is_err());
+        assert_eq!(list_sessions(&dir).sessions.len(), 1);
+
+        let ended = end_session(&dir, None, "success", Some("done".into()), |_| Ok(())).unwrap();
+        assert_eq!(ended.session.session_id, second.session_id);
+        assert_eq!(ended.duration_min, 0);
+        assert!(list_sessions(&dir).sessions.is_empty());
+        assert!(end_session(&dir, None, "success", None, |_| Ok(())).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_budgets_from_start_and_injected_contract() {
+        let dir = temp_project("orchestrator_session_budget_test");
+
+        // 세션 시작 예산 + 계약서 예산(agent_tasks.budget_minutes) 병합
+        let contract = ExecutionContract {
+            budget_minutes: Some(30),
+            ..Default::default()
+        };
+        let budget = Budget {
+            max_files: Some(10),
+            max_commits: Some(2),
+            ..Default::default()
+        };
+        let id = start_session(&dir, "cursor", "Budget", Some(contract), Some(budget))
+            .unwrap()
+            .session
+            .session_id;
+        let stored = find_session(&dir, Some(&id)).unwrap().1;
+        assert_eq!(
+            stored.effective_budget(),
+            Budget {
+                max_minutes: Some(30),
+                max_files: Some(10),
+                max_commits: Some(2),
+                max_tokens: None,
+            }
+        );
+        // 빈 예산은 저장하지 않음
+        let id = start_session(&dir, "codex", "None", None, Some(Budget::default()))
+            .unwrap()
+            .session
+            .session_id;
+        assert_eq!(find_session(&dir, Some(&id)).unwrap().1.budget, None);
+
+        // orchx inject가 쓴 session.json (서버 Task의 budget_tokens/budget_minutes)
+        fs::write(
+            session_file(&dir),
+            r#"{
+                "session_id": "cli",
+                "agent_type": "claude_code",
+                "task_name": "Inject",
+                "started_at": "2026-03-01T09:00:00Z",
+                "files_changed": 0,
+                "commits_detected": 0,
+                "execution_contract": {
+                    "allowed_paths": ["src/**"],
+                    "allowed_commands": [],
+                    "risk_tier": "high",
+                    "budget_tokens": 50000,
+                    "budget_minutes": 45
+                },
+                "budget": { "max_files": 20 }
+            }"#,
+        )
+        .unwrap();
+        let injected = load_session(&dir).unwrap().unwrap().effective_budget();
+        assert_eq!(injected.max_tokens, Some(50000));
+        assert_eq!(injected.max_minutes, Some(45));
+        assert_eq!(injected.max_files, Some(20));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_registry_with_cli_session_and_overlaps() {
+        let dir = temp_project("orchestrator_session_registry_test");
+
+        // orchx CLI가 만든 session.json도 레지스트리 항목
+        let cli = Session {
+            session_id: "cli-session".to_string(),
+            agent_type: "claude_code".to_string(),
+            task_name: "Docs".to_string(),
+            project_path: None,
+            files_changed: 3,
+            commits_detected: 0,
+            execution_contract: scoped(&["docs/**"]),
+            started_at: Some("2026-03-01T09:00:00Z".to_string()),
+            budget: None,
+            tokens_used: None,
+            handoff_from: None,
+        };
+        write_session_file(&session_file(&dir), &cli).unwrap();
+
+        let ui = start_session(&dir, "cursor", "UI", scoped(&["src/ui/**"]), None).unwrap();
+        assert!(ui.overlaps.is_empty());
+        let ui = ui.session;
+        let core = start_session(&dir, "codex", "Core", scoped(&["src/**"]), None).unwrap();
+        assert_eq!(core.overlaps, vec![ui.session_id.clone()]);
+        let core = core.session;
+        let listing = list_sessions(&dir);
+        let ids: Vec<_> = listing
+            .sessions
+            .iter()
+            .map(|(_, s)| s.session_id.as_str())
+            .collect();
+        assert_eq!(ids, ["cli-session", &ui.session_id, &core.session_id]);
+
+        let sessions: Vec<Session> = listing.sessions.into_iter().map(|(_, s)| s).collect();
+        assert_eq!(
+            overlapping_sessions(&sessions, &core),
+            vec![ui.session_id.clone()]
+        );
+        assert!(overlapping_sessions(&sessions, &cli).is_empty());
+
+        // 여러 세션 → ID 필요, 통계는 세션별 파일에
+        assert!(end_session(&dir, None, "success", None, |_| Ok(())).is_err());
+        update_session_stats(&dir, &ui.session_id, 4, 1).unwrap();
+        update_session_stats(&dir, "cli-session", 9, 0).unwrap();
+        assert_eq!(
+            update_session_stats(&dir, "ended-session", 1, 1).unwrap_err(),
+            session_not_found("ended-session")
+        );
+        let ui_file = load_session_file(&registry_file(&dir, &ui.session_id))
+            .unwrap()
+            .unwrap();
+        assert_eq!((ui_file.files_changed, ui_file.commits_detected), (4, 1));
+        assert_eq!(load_session(&dir).unwrap().unwrap().files_changed, 9);
+
+        // CLI 세션 인계는 session.json에 이어서
+        let (_, next) =
+            handoff_session(&dir, Some("cli-session"), "codex", None, |_| Ok(())).unwrap();
+        assert_eq!(
+            load_session(&dir).unwrap().unwrap().session_id,
+            next.session_id
+        );
+
+        end_session(&dir, Some(&ui.session_id), "partial", None, |_| Ok(())).unwrap();
+        assert_eq!(list_sessions(&dir).sessions.len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_atomic_writes_and_corruption() {
+        let dir = temp_project("orchestrator_session_atomic_test");
+        assert_eq!(load_session(&dir).unwrap().map(|s| s.session_id), None);
+        let id = start_session(&dir, "codex", "Atomic", None, None)
+            .unwrap()
+            .session
+            .session_id;
+
+        update_session_stats(&dir, &id, 7, 2).unwrap();
+        let session = find_session(&dir, Some(&id)).unwrap().1;
+        assert_eq!((session.files_changed, session.commits_detected), (7, 2));
+        assert!(!lock_file(&dir).exists());
+        let leftovers: Vec<_> = fs::read_dir(sessions_dir(&dir))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".tmp."))
+            .collect();
+        assert!(leftovers.is_empty());
+
+        // 다른 프로세스가 잠금 보유 중 → 대기 후 실패, 오래된 잠금은 제거
+        fs::write(lock_file(&dir), "999999").unwrap();
+        let started = Instant::now();
+        assert!(update_session_stats(&dir, &id, 8, 2).is_err());
+        assert!(started.elapsed() >= LOCK_TIMEOUT);
+        let old = std::time::SystemTime::now() - LOCK_STALE * 2;
+        fs::File::options()
+            .write(true)
+            .open(lock_file(&dir))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        update_session_stats(&dir, &id, 8, 2).unwrap();
+        assert!(!lock_file(&dir).exists());
+
+        // 손상된 파일 → "세션 없음"이 아니라 에러, 덮어쓰지 않음
+        let path = registry_file(&dir, &id);
+        fs::write(&path, "{\"session_id\": \"abc\", \"agent_").unwrap();
+        assert!(load_session_file(&path).unwrap_err().contains("손상"));
+        assert_eq!(list_sessions(&dir).errors.len(), 1);
+        assert!(update_session_stats(&dir, &id, 9, 2)
+            .unwrap_err()
+            .contains("손상"));
+        // 다른 세션 파일의 손상 → 끝난 세션은 "세션 없음"
+        assert_eq!(
+            update_session_stats(&dir, "ended-session", 1, 1).unwrap_err(),
+            session_not_found("ended-session")
+        );
+        assert!(end_session(&dir, Some(&id), "success", None, |_| Ok(())).is_err());
+        assert!(fs::read_to_string(&path).unwrap().ends_with("agent_"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_stale_lock_is_stolen_once() {
+        let dir = temp_project("orchestrator_session_steal_test");
+        fs::create_dir_all(orchestrator_dir(&dir)).unwrap();
+        fs::write(lock_file(&dir), "999999").unwrap();
+        let old = std::time::SystemTime::now() - LOCK_STALE * 2;
+        fs::File::options()
+            .write(true)
+            .open(lock_file(&dir))
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+
+        // 여러 스레드가 동시에 빼앗기 시도 → 잠금 보유자는 항상 하나
+        let holders = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let (dir, holders) = (dir.clone(), holders.clone());
+                std::thread::spawn(move || {
+                    let _lock = SessionLock::acquire(&dir).unwrap();
+                    assert_eq!(holders.fetch_add(1, Ordering::SeqCst), 0);
+                    std::thread::sleep(Duration::from_millis(5));
+                    holders.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert!(!lock_file(&dir).exists());
+        let leftovers = fs::read_dir(orchestrator_dir(&dir))
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().contains(".stale."))
+            .count();
+        assert_eq!(leftovers, 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_stats_writer_coalesces() {
+        let dir = temp_project("orchestrator_session_stats_test");
+        let id = start_session(&dir, "cursor", "Stats", None, None)
+            .unwrap()
+            .session
+            .session_id;
+
+        let running = Arc::new(AtomicBool::new(true));
+        let writer = StatsWriter::spawn(dir.clone(), running.clone(), |_| {});
+        for i in 1..=50 {
+            writer.record(&id, i, 0);
+        }
+        // 아직 기록 전
+        assert_eq!(find_session(&dir, Some(&id)).unwrap().1.files_changed, 0);
+
+        running.store(false, Ordering::SeqCst);
+        std::thread::sleep(STATS_FLUSH_INTERVAL * 2);
+        assert_eq!(find_session(&dir, Some(&id)).unwrap().1.files_changed, 50);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
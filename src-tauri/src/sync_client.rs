@@ -2,12 +2,20 @@
 // sync_client.rs — 서버 통신 (orchx sync 재작성)
 // reqwest 기반 Supabase REST API 호출
 // 멱등성(UUID) + 실패 재시도 + 로컬 저장
+// Realtime 구독 (agent_tasks / plans / work_items → local_db)
+// 로그인 사용자 JWT로 전송 (RLS: user_id = auth.uid())
 // ===========================================
 
+use crate::auth_session::{AuthSession, AuthTokens};
+use crate::event_pipeline::{EventPipeline, FileChange, QueuedEvent};
+use crate::git_discovery::{parse_repo_full_name, GitRepo};
+use crate::local_db::{LocalDb, OutboxEvent};
+use crate::realtime::Realtime;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// CLI 이벤트
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,11 +26,17 @@ pub struct CliEvent {
     pub session_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub project_id: Option<String>,
+    /// 기록 시점 로그인 사용자 (RLS WITH CHECK 대상, 다른 사용자 세션으로는 전송하지 않음)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
     pub status: String,
     pub retry_count: u32,
 }
 
-/// 실패 이벤트 (로컬 저장)
+/// outbox 재전송 1회 최대 건수
+const RETRY_BATCH_SIZE: u32 = 100;
+
+/// 레거시 실패 이벤트 (failed_events.json → outbox 이전용)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FailedEvent {
     pub event_id: String,
@@ -47,67 +61,83 @@ pub struct SyncClient {
     config: SupabaseConfig,
     project_path: PathBuf,
     pub repo_full_name: Option<String>,
+    /// file.changed 배치 전송 큐
+    file_events: EventPipeline,
+    /// 전송 전 기록 → 성공/실패 반영 (local_db.event_outbox)
+    outbox: Arc<LocalDb>,
+    /// 로그인 사용자 세션 (요청 Authorization)
+    auth: Arc<AuthSession>,
+    /// 로그인 사용자 Realtime 구독
+    realtime: Realtime,
 }
 
 impl SyncClient {
-    pub fn new(config: SupabaseConfig, project_path: PathBuf) -> Self {
-        Self {
+    pub fn new(config: SupabaseConfig, project_path: PathBuf, outbox: Arc<LocalDb>) -> Self {
+        let config_for_auth = config.clone();
+        let client = Self {
             client: reqwest::Client::new(),
             config,
             project_path,
             repo_full_name: None,
+            file_events: EventPipeline::new(),
+            outbox,
+            auth: Arc::new(AuthSession::new(config_for_auth)),
+            realtime: Realtime::default(),
+        };
+        client.import_legacy_failed_events();
+        client
+    }
+
+    pub fn config(&self) -> &SupabaseConfig {
+        &self.config
+    }
+
+    pub fn auth(&self) -> Arc<AuthSession> {
+        self.auth.clone()
+    }
+
+    /// 로그인/토큰 갱신 반영 → 사용자가 바뀌면 Realtime 구독 교체
+    pub fn sign_in(&self, app: tauri::AppHandle, tokens: AuthTokens) {
+        let user_id = tokens.user_id.clone();
+        if self.auth.set_session(tokens) {
+            log::info!("🔑 로그인 사용자: {}", user_id);
+            self.realtime
+                .start(app, self.config.clone(), self.outbox.clone(), self.auth.clone());
         }
     }
 
-    /// .git/config에서 remote URL → repo_full_name 추출
-    pub fn resolve_repo_name(&mut self) -> Option<String> {
-        let git_config = self.project_path.join(".git").join("config");
-        let content = fs::read_to_string(&git_config).ok()?;
-
-        // [remote "origin"] 섹션에서 url 추출
-        let url_line = content
-            .lines()
-            .skip_while(|l| !l.contains("[remote \"origin\"]"))
-            .skip(1)
-            .take_while(|l| !l.starts_with('['))
-            .find(|l| l.trim().starts_with("url"))?;
-
-        let raw_url = url_line.split('=').nth(1)?.trim();
-
-        // SSH: git@github.com:owner/repo.git → owner/repo
-        let repo = if raw_url.contains("git@") {
-            raw_url
-                .split(':')
-                .nth(1)?
-                .trim_end_matches(".git")
-                .to_string()
-        } else {
-            // HTTPS: https://github.com/owner/repo.git → owner/repo
-            let parts: Vec<&str> = raw_url.trim_end_matches(".git").split('/').collect();
-            if parts.len() >= 2 {
-                format!("{}/{}", parts[parts.len() - 2], parts[parts.len() - 1])
-            } else {
-                return None;
-            }
-        };
+    /// 로그아웃 → 세션 폐기 + Realtime 중지 (이벤트는 outbox에 보류)
+    pub fn sign_out(&self) {
+        if self.auth.clear() {
+            log::info!("🔑 로그아웃 → 이벤트 전송 보류");
+        }
+        self.realtime.stop();
+    }
 
+    /// origin remote URL → repo_full_name 추출
+    /// (worktree/서브모듈은 `gitdir:`이 가리키는 저장소 설정 기준)
+    pub fn resolve_repo_name(&mut self) -> Option<String> {
+        let url = GitRepo::discover(&self.project_path)?.origin_url()?;
+        let repo = parse_repo_full_name(&url)?;
         self.repo_full_name = Some(repo.clone());
         log::info!("🔗 저장소: {}", repo);
         Some(repo)
     }
 
-    /// 이벤트 전송 (멱등성: event_id UNIQUE 제약)
-    pub async fn send_event(
+    /// 전송용 CliEvent 생성 (새 event_id + repo_full_name 주입)
+    fn build_event(
         &self,
         event_type: &str,
         payload: serde_json::Value,
-    ) -> Result<(), String> {
-        let session = crate::session::read_session(&self.project_path);
-        let event_id = uuid::Uuid::new_v4().to_string();
-
+        session_id: Option<String>,
+    ) -> CliEvent {
         let mut event_payload = payload;
         if let Some(ref repo) = self.repo_full_name {
-            if let Some(obj) = event_payload.as_object_mut() {
+            // 큐에서 레포별로 지정한 값이 우선
+            if let Some(obj) = event_payload
+                .as_object_mut()
+                .filter(|o| !o.contains_key("repo_full_name"))
+            {
                 obj.insert(
                     "repo_full_name".to_string(),
                     serde_json::Value::String(repo.clone()),
@@ -115,150 +145,107 @@ impl SyncClient {
             }
         }
 
-        let event = CliEvent {
-            event_id: event_id.clone(),
+        CliEvent {
+            event_id: uuid::Uuid::new_v4().to_string(),
             event_type: event_type.to_string(),
             payload: event_payload,
-            session_id: session.as_ref().map(|s| s.session_id.clone()),
+            session_id,
             project_id: None,
+            user_id: self.auth.user_id(),
             status: "pending".to_string(),
             retry_count: 0,
-        };
+        }
+    }
 
-        let url = format!("{}/rest/v1/cli_events", self.config.url);
-        let result = self
-            .client
-            .post(&url)
-            .header("apikey", &self.config.anon_key)
-            .header("Authorization", format!("Bearer {}", self.config.anon_key))
-            .header("Content-Type", "application/json")
-            .header("Prefer", "return=minimal")
-            .json(&event)
-            .send()
-            .await;
+    /// file.changed 이벤트를 배치 큐에 추가 (경로별 병합 후 bulk 전송)
+    pub fn queue_file_change(self: &Arc<Self>, change: FileChange) {
+        self.file_events.push(self, change);
+    }
 
-        match result {
-            Ok(resp) => {
-                let status = resp.status();
-                if status.is_success() || status.as_u16() == 201 {
-                    log::info!("✓ 이벤트 전송: {} ({}...)", event_type, &event_id[..8]);
-                    Ok(())
-                } else if status.as_u16() == 409 {
-                    // UNIQUE 위반 = 이미 전송됨 (멱등성 보장)
-                    log::warn!("⚠ 이벤트 {}... 이미 전송됨", &event_id[..8]);
-                    Ok(())
-                } else {
-                    let body = resp.text().await.unwrap_or_default();
-                    let msg = format!("전송 실패 ({}): {}", status, body);
-                    self.save_failed_event(FailedEvent {
-                        event_id,
-                        event_type: event_type.to_string(),
-                        payload: event.payload,
-                        session_id: event.session_id,
-                        failed_at: chrono::Utc::now().to_rfc3339(),
-                        retry_count: 0,
-                        error: msg.clone(),
-                    });
-                    Err(msg)
-                }
-            }
-            Err(e) => {
-                let msg = format!("네트워크 오류: {}", e);
-                self.save_failed_event(FailedEvent {
-                    event_id,
-                    event_type: event_type.to_string(),
-                    payload: event.payload,
-                    session_id: event.session_id,
-                    failed_at: chrono::Utc::now().to_rfc3339(),
-                    retry_count: 0,
-                    error: msg.clone(),
-                });
-                Err(msg)
-            }
+    /// 여러 이벤트를 한 번에 전송 (PostgREST 배열 insert)
+    /// 세션은 이벤트마다 큐에 넣을 때 귀속된 값 (flush 시점의 세션이 아님)
+    pub async fn send_events(&self, events: Vec<QueuedEvent>) -> Result<(), String> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let batch: Vec<CliEvent> = events
+            .into_iter()
+            .map(|e| self.build_event(&e.event_type, e.payload, e.session_id))
+            .collect();
+
+        self.record_outbox(&batch);
+        let result = self.deliver(&batch).await;
+        if result.is_ok() {
+            log::info!("✓ 이벤트 배치 전송: {}건", batch.len());
         }
+        result
     }
 
-    /// 실패 이벤트 재전송 (최대 3회)
-    pub async fn retry_failed(&self) -> (u32, u32) {
-        let failed = self.load_failed_events();
-        if failed.is_empty() {
-            return (0, 0);
+    /// 이벤트 전송 (멱등성: event_id UNIQUE 제약)
+    /// 세션/레포는 호출한 watcher가 귀속시킨 값 (앱 작업 디렉토리의 세션이 아님)
+    pub async fn send_event(&self, event: QueuedEvent) -> Result<(), String> {
+        let event_type = event.event_type.clone();
+        let event = self.build_event(&event.event_type, event.payload, event.session_id);
+        let batch = [event];
+
+        self.record_outbox(&batch);
+        let result = self.deliver(&batch).await;
+        if result.is_ok() {
+            log::info!("✓ 이벤트 전송: {} ({}...)", event_type, &batch[0].event_id[..8]);
         }
+        result
+    }
 
-        let mut succeeded = 0u32;
-        let mut remaining = Vec::new();
-
-        for event in &failed {
-            if event.retry_count >= 3 {
-                log::error!("✗ 이벤트 {}... 최대 재시도 초과", &event.event_id[..8]);
-                remaining.push(event.clone());
-                continue;
+    /// outbox에서 재시도 시각이 된 이벤트 재전송 → (시도, 성공)
+    pub async fn retry_outbox(&self) -> (u32, u32) {
+        let user_id = match self.auth.user_id() {
+            Some(id) => id,
+            None => return (0, 0),
+        };
+        let due = match self.outbox.outbox_due(RETRY_BATCH_SIZE, &user_id) {
+            Ok(d) => d,
+            Err(e) => {
+                log::warn!("⚠ outbox 조회 실패: {}", e);
+                return (0, 0);
             }
+        };
+        if due.is_empty() {
+            return (0, 0);
+        }
 
-            // Exponential backoff: 1s, 2s, 4s
-            let delay = 2u64.pow(event.retry_count) * 1000;
-            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
-
-            let url = format!("{}/rest/v1/cli_events", self.config.url);
-            let retry_event = CliEvent {
-                event_id: event.event_id.clone(),
-                event_type: event.event_type.clone(),
-                payload: event.payload.clone(),
-                session_id: event.session_id.clone(),
+        let batch: Vec<CliEvent> = due
+            .into_iter()
+            .map(|e| CliEvent {
+                event_id: e.event_id,
+                event_type: e.event_type,
+                payload: e.payload,
+                session_id: e.session_id,
                 project_id: None,
+                user_id: e.user_id,
                 status: "pending".to_string(),
-                retry_count: event.retry_count + 1,
-            };
-
-            match self
-                .client
-                .post(&url)
-                .header("apikey", &self.config.anon_key)
-                .header("Authorization", format!("Bearer {}", self.config.anon_key))
-                .header("Content-Type", "application/json")
-                .header("Prefer", "return=minimal")
-                .json(&retry_event)
-                .send()
-                .await
-            {
-                Ok(resp) if resp.status().is_success() || resp.status().as_u16() == 409 => {
-                    succeeded += 1;
-                    log::info!(
-                        "✓ 재전송 성공: {} ({}...)",
-                        event.event_type,
-                        &event.event_id[..8]
-                    );
-                }
-                Ok(resp) => {
-                    let body = resp.text().await.unwrap_or_default();
-                    remaining.push(FailedEvent {
-                        retry_count: event.retry_count + 1,
-                        error: format!("재전송 실패: {}", body),
-                        ..event.clone()
-                    });
-                }
-                Err(e) => {
-                    remaining.push(FailedEvent {
-                        retry_count: event.retry_count + 1,
-                        error: format!("네트워크 오류: {}", e),
-                        ..event.clone()
-                    });
-                }
+                retry_count: e.attempts,
+            })
+            .collect();
+
+        match self.deliver(&batch).await {
+            Ok(()) => {
+                log::info!("✓ outbox 재전송 성공: {}건", batch.len());
+                (batch.len() as u32, batch.len() as u32)
+            }
+            Err(e) => {
+                log::warn!("⚠ outbox 재전송 실패: {}", e);
+                (batch.len() as u32, 0)
             }
         }
-
-        self.write_failed_events(&remaining);
-        (failed.len() as u32, succeeded)
     }
 
-    /// 연결 상태 확인
+    /// 연결 상태 확인 (인증 불필요: Auth 서버 health)
     pub async fn check_connection(&self) -> bool {
-        let url = format!("{}/rest/v1/cli_events?select=id&limit=1", self.config.url);
+        let url = format!("{}/auth/v1/health", self.config.url);
         match self
             .client
             .get(&url)
             .header("apikey", &self.config.anon_key)
-            .header("Authorization", format!("Bearer {}", self.config.anon_key))
             .send()
             .await
         {
@@ -267,39 +254,170 @@ impl SyncClient {
         }
     }
 
-    // --- 로컬 실패 이벤트 관리 ---
+    /// 사용자 토큰으로 전송 → outbox 반영
+    /// 로그아웃 상태면 전송하지 않고 outbox에 보류 (시도 횟수 증가 없음)
+    /// 기록 사용자가 현재 로그인 사용자와 다른 이벤트는 보류 (해당 사용자 재로그인 시 재전송)
+    async fn deliver(&self, batch: &[CliEvent]) -> Result<(), String> {
+        let (token, user_id) = match self.auth.access_token().await {
+            Ok(token) => match self.auth.user_id() {
+                Some(user_id) => (token, user_id),
+                None => return Err(crate::auth_session::NOT_SIGNED_IN.to_string()),
+            },
+            Err(e) => {
+                log::warn!("⚠ 이벤트 {}건 보류: {}", batch.len(), e);
+                return Err(e);
+            }
+        };
+        let (owned, held) = split_by_owner(batch, &user_id);
+        if held > 0 {
+            log::warn!("⚠ 다른 사용자가 기록한 이벤트 {}건 보류", held);
+        }
+        if owned.is_empty() {
+            return Err("기록 사용자와 로그인 사용자가 다름".to_string());
+        }
+        let result = self.post_events(&owned, &token).await;
+        self.settle_outbox(&owned, &result);
+        result
+    }
 
-    fn failed_events_path(&self) -> PathBuf {
-        self.project_path
-            .join(".orchestrator")
-            .join("failed_events.json")
+    /// cli_events POST (단건/배열 공용)
+    /// 중복 event_id는 무시 → 재전송해도 멱등, 409도 전송 완료로 간주
+    async fn post_events(&self, batch: &[CliEvent], access_token: &str) -> Result<(), String> {
+        let url = format!("{}/rest/v1/cli_events?on_conflict=event_id", self.config.url);
+        let result = self
+            .client
+            .post(&url)
+            .header("apikey", &self.config.anon_key)
+            .header("Authorization", format!("Bearer {}", access_token))
+            .header("Content-Type", "application/json")
+            .header("Prefer", "return=minimal,resolution=ignore-duplicates")
+            .json(batch)
+            .send()
+            .await;
+
+        match result {
+            Ok(resp) if resp.status().is_success() => Ok(()),
+            Ok(resp) if resp.status().as_u16() == 409 => {
+                log::warn!("⚠ 이벤트 {}건 이미 전송됨", batch.len());
+                Ok(())
+            }
+            Ok(resp) => {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                Err(format!("전송 실패 ({}): {}", status, body))
+            }
+            Err(e) => Err(format!("네트워크 오류: {}", e)),
+        }
     }
 
-    fn load_failed_events(&self) -> Vec<FailedEvent> {
-        let path = self.failed_events_path();
-        match fs::read_to_string(&path) {
-            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
-            Err(_) => Vec::new(),
+    // --- 로컬 outbox (local_db.event_outbox) ---
+
+    /// 전송 전 outbox 기록 (크래시 시에도 재시도 가능)
+    /// 로그아웃 중 기록된 이벤트는 dead로 보관 (requeue 시 현재 사용자로 귀속)
+    fn record_outbox(&self, batch: &[CliEvent]) {
+        let rows: Vec<OutboxEvent> = batch
+            .iter()
+            .map(|e| OutboxEvent {
+                event_id: e.event_id.clone(),
+                event_type: e.event_type.clone(),
+                payload: e.payload.clone(),
+                session_id: e.session_id.clone(),
+                user_id: e.user_id.clone(),
+                status: unowned_status(&e.user_id),
+                attempts: e.retry_count,
+                next_attempt_at: String::new(),
+                last_error: e
+                    .user_id
+                    .is_none()
+                    .then(|| crate::auth_session::NOT_SIGNED_IN.to_string()),
+                created_at: String::new(),
+            })
+            .collect();
+        if let Err(e) = self.outbox.outbox_insert(&rows) {
+            log::warn!("⚠ outbox 기록 실패: {}", e);
         }
     }
 
-    fn save_failed_event(&self, event: FailedEvent) {
-        let dir = self.project_path.join(".orchestrator");
-        let _ = fs::create_dir_all(&dir);
-        let mut events = self.load_failed_events();
-        events.push(event);
-        self.write_failed_events(&events);
+    /// 전송 결과 반영: 성공 → delivered, 실패 → attempts 증가 + 다음 시도 예약
+    fn settle_outbox(&self, batch: &[CliEvent], result: &Result<(), String>) {
+        let ids: Vec<String> = batch.iter().map(|e| e.event_id.clone()).collect();
+        let recorded = match result {
+            Ok(()) => self.outbox.outbox_mark_delivered(&ids),
+            Err(msg) => self.outbox.outbox_mark_failed(&ids, msg),
+        };
+        if let Err(e) = recorded {
+            log::warn!("⚠ outbox 상태 기록 실패: {}", e);
+        }
     }
 
-    fn write_failed_events(&self, events: &[FailedEvent]) {
-        let dir = self.project_path.join(".orchestrator");
-        let _ = fs::create_dir_all(&dir);
-        if let Ok(json) = serde_json::to_string_pretty(events) {
-            let _ = fs::write(self.failed_events_path(), json);
+    /// 레거시 <project>/.orchestrator/failed_events.json → outbox 이전 후 삭제
+    /// 기록 사용자를 알 수 없으므로 dead로 보관 (requeue 시 현재 사용자로 귀속)
+    fn import_legacy_failed_events(&self) {
+        let path = self
+            .project_path
+            .join(".orchestrator")
+            .join("failed_events.json");
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+        let failed: Vec<FailedEvent> = serde_json::from_str(&content).unwrap_or_default();
+        let rows: Vec<OutboxEvent> = failed
+            .into_iter()
+            .map(|e| OutboxEvent {
+                event_id: e.event_id,
+                event_type: e.event_type,
+                payload: e.payload,
+                session_id: e.session_id,
+                user_id: None,
+                status: unowned_status(&None),
+                attempts: e.retry_count,
+                next_attempt_at: String::new(),
+                last_error: Some(e.error),
+                created_at: String::new(),
+            })
+            .collect();
+        let count = rows.len();
+        match self.outbox.outbox_insert(&rows) {
+            Ok(()) => {
+                let _ = fs::remove_file(&path);
+                log::info!("📦 failed_events.json {}건 → outbox 이전", count);
+            }
+            Err(e) => log::warn!("⚠ failed_events.json 이전 실패: {}", e),
         }
     }
 }
 
+/// 기록 사용자가 없는 이벤트는 자동 전송 대상에서 제외
+fn unowned_status(user_id: &Option<String>) -> String {
+    if user_id.is_some() { "pending" } else { "dead" }.to_string()
+}
+
+/// 현재 로그인 사용자가 기록한 이벤트만 분리 → (전송 대상, 보류 건수)
+fn split_by_owner(batch: &[CliEvent], user_id: &str) -> (Vec<CliEvent>, usize) {
+    let owned: Vec<CliEvent> = batch
+        .iter()
+        .filter(|e| e.user_id.as_deref() == Some(user_id))
+        .cloned()
+        .collect();
+    let held = batch.len() - owned.len();
+    (owned, held)
+}
+
+/// 빌드 시점에 임베드된 Supabase 설정 (build.rs, 패키징된 앱용)
+pub fn embedded_supabase_config() -> Option<SupabaseConfig> {
+    match (
+        option_env!("VITE_SUPABASE_URL"),
+        option_env!("VITE_SUPABASE_ANON_KEY"),
+    ) {
+        (Some(url), Some(key)) if !url.is_empty() && !key.is_empty() => Some(SupabaseConfig {
+            url: url.to_string(),
+            anon_key: key.to_string(),
+        }),
+        _ => None,
+    }
+}
+
 /// .env 파일에서 Supabase 설정 로드
 /// 우선순위: .env.local → .env
 pub fn load_supabase_config(project_path: &Path) -> Option<SupabaseConfig> {
@@ -377,4 +495,26 @@ SOME_OTHER=value
             "https://test.supabase.co"
         );
     }
+
+    #[test]
+    fn test_split_by_owner_holds_other_users() {
+        let event = |id: &str, user: Option<&str>| CliEvent {
+            event_id: id.to_string(),
+            event_type: "file.changed".to_string(),
+            payload: serde_json::json!({}),
+            session_id: None,
+            project_id: None,
+            user_id: user.map(str::to_string),
+            status: "pending".to_string(),
+            retry_count: 0,
+        };
+        let unowned = serde_json::to_value(event("e0", None)).unwrap();
+        assert!(unowned.get("user_id").is_none());
+
+        let batch = [event("e1", Some("u1")), event("e2", Some("u2")), event("e3", None)];
+        let (owned, held) = split_by_owner(&batch, "u1");
+        assert_eq!(held, 2);
+        assert_eq!(owned.len(), 1);
+        assert_eq!(serde_json::to_value(&owned[0]).unwrap()["user_id"], "u1");
+    }
 }
@@ -0,0 +1,266 @@
+// ===========================================
+// sync_conflict.rs — 동기화 충돌 해결 정책
+// 로컬 미전송 변경 + 원격 변경(기준 updated_at 불일치) → 테이블별 정책 적용
+// last-writer-wins | metadata 필드 병합 | manual (db_get_conflicts로 노출)
+// ===========================================
+
+use crate::local_db::LocalDb;
+use serde::{Deserialize, Serialize};
+
+/// 정책 저장 키 접두사 (user_preferences: conflict_policy:<table>)
+pub const POLICY_PREF_PREFIX: &str = "conflict_policy:";
+
+/// 테이블별 충돌 해결 정책
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// updated_at이 더 최근인 쪽 채택 (같으면 원격)
+    LastWriterWins,
+    /// 최근 쪽을 기준으로 하되 metadata는 키 단위 병합
+    MergeMetadata,
+    /// 자동 해결하지 않고 사용자 선택 대기
+    Manual,
+}
+
+impl ConflictPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "last_writer_wins" => Some(ConflictPolicy::LastWriterWins),
+            "merge_metadata" => Some(ConflictPolicy::MergeMetadata),
+            "manual" => Some(ConflictPolicy::Manual),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConflictPolicy::LastWriterWins => "last_writer_wins",
+            ConflictPolicy::MergeMetadata => "merge_metadata",
+            ConflictPolicy::Manual => "manual",
+        }
+    }
+
+    /// 설정이 없을 때 기본 정책
+    pub fn default_for(table_name: &str) -> Self {
+        match table_name {
+            // 세션 로그는 append-only
+            "session_logs" => ConflictPolicy::LastWriterWins,
+            _ => ConflictPolicy::MergeMetadata,
+        }
+    }
+}
+
+/// 테이블 정책 (user_preferences 설정 → 없거나 잘못된 값이면 기본값)
+pub fn policy_for(db: &LocalDb, table_name: &str) -> ConflictPolicy {
+    db.get_preference(&format!("{}{}", POLICY_PREF_PREFIX, table_name))
+        .ok()
+        .flatten()
+        .and_then(|v| ConflictPolicy::parse(&v))
+        .unwrap_or_else(|| ConflictPolicy::default_for(table_name))
+}
+
+/// 충돌 해결 결과
+#[derive(Debug, Clone, PartialEq)]
+pub enum Resolution {
+    /// 로컬(병합 결과 포함) 유지 → 원격으로 push
+    Local(serde_json::Value),
+    /// 원격 채택 → 로컬 변경 폐기
+    Remote,
+    /// 사용자 선택 대기
+    Manual,
+}
+
+/// 정책에 따라 로컬 레코드 vs 원격 행 해결
+pub fn resolve(
+    policy: ConflictPolicy,
+    local: &serde_json::Value,
+    remote: &serde_json::Value,
+) -> Resolution {
+    let local_newer = is_newer(&local["updated_at"], &remote["updated_at"]);
+    match policy {
+        ConflictPolicy::Manual => Resolution::Manual,
+        ConflictPolicy::LastWriterWins => {
+            if local_newer {
+                Resolution::Local(local.clone())
+            } else {
+                Resolution::Remote
+            }
+        }
+        // 원격 테이블에 metadata 컬럼이 없으면 병합할 것이 없음
+        ConflictPolicy::MergeMetadata if remote.get("metadata").is_none() => {
+            resolve(ConflictPolicy::LastWriterWins, local, remote)
+        }
+        ConflictPolicy::MergeMetadata => {
+            let (older, newer) = if local_newer {
+                (remote, local)
+            } else {
+                (local, remote)
+            };
+            let metadata = merge_metadata(&older["metadata"], &newer["metadata"]);
+            // 원격 기준인데 병합으로 바뀐 것이 없으면 그대로 원격 채택
+            if !local_newer && metadata == remote["metadata"] {
+                return Resolution::Remote;
+            }
+            let mut merged = newer.clone();
+            if let Some(obj) = merged.as_object_mut() {
+                obj.insert("metadata".to_string(), metadata);
+            }
+            Resolution::Local(merged)
+        }
+    }
+}
+
+/// metadata 키 단위 병합 (양쪽에 있으면 newer 우선, 객체가 아니면 newer 그대로)
+pub fn merge_metadata(older: &serde_json::Value, newer: &serde_json::Value) -> serde_json::Value {
+    match (older.as_object(), newer.as_object()) {
+        (Some(o), Some(n)) => {
+            let mut merged = o.clone();
+            for (key, value) in n {
+                merged.insert(key.clone(), value.clone());
+            }
+            serde_json::Value::Object(merged)
+        }
+        (Some(_), None) if newer.is_null() => older.clone(),
+        _ => newer.clone(),
+    }
+}
+
+/// 로컬 updated_at이 원격보다 나중인지 (파싱 불가 시 false → 원격 우선)
+fn is_newer(local: &serde_json::Value, remote: &serde_json::Value) -> bool {
+    match (
+        local.as_str().and_then(parse_timestamp),
+        remote.as_str().and_then(parse_timestamp),
+    ) {
+        (Some(l), Some(r)) => l > r,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+/// RFC3339 (원격) 또는 SQLite datetime('now') (로컬, UTC) 파싱
+pub fn parse_timestamp(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&chrono::Utc));
+    }
+    chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f")
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// 같은 시각인지 (원격 표기 차이: "+00:00" vs "Z", 소수점 자릿수)
+pub fn same_timestamp(a: &str, b: &str) -> bool {
+    match (parse_timestamp(a), parse_timestamp(b)) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_parse_and_defaults() {
+        for policy in [
+            ConflictPolicy::LastWriterWins,
+            ConflictPolicy::MergeMetadata,
+            ConflictPolicy::Manual,
+        ] {
+            assert_eq!(ConflictPolicy::parse(policy.as_str()), Some(policy));
+        }
+        assert_eq!(ConflictPolicy::parse("ours"), None);
+        assert_eq!(
+            ConflictPolicy::default_for("plans"),
+            ConflictPolicy::MergeMetadata
+        );
+        assert_eq!(
+            ConflictPolicy::default_for("session_logs"),
+            ConflictPolicy::LastWriterWins
+        );
+    }
+
+    #[test]
+    fn test_resolve_by_policy() {
+        let local = serde_json::json!({
+            "id": "w1",
+            "title": "Local",
+            "metadata": { "a": 1, "shared": "local" },
+            "updated_at": "2025-01-02 00:00:00",
+        });
+        let remote = serde_json::json!({
+            "id": "w1",
+            "title": "Remote",
+            "metadata": { "b": 2, "shared": "remote" },
+            "updated_at": "2025-01-01T00:00:00+00:00",
+        });
+
+        assert_eq!(
+            resolve(ConflictPolicy::LastWriterWins, &local, &remote),
+            Resolution::Local(local.clone())
+        );
+        assert_eq!(
+            resolve(ConflictPolicy::LastWriterWins, &remote, &local),
+            Resolution::Remote
+        );
+        assert_eq!(
+            resolve(ConflictPolicy::Manual, &local, &remote),
+            Resolution::Manual
+        );
+
+        // 로컬이 최근 → 로컬 필드 + metadata 병합
+        match resolve(ConflictPolicy::MergeMetadata, &local, &remote) {
+            Resolution::Local(merged) => {
+                assert_eq!(merged["title"], "Local");
+                assert_eq!(merged["metadata"]["a"], 1);
+                assert_eq!(merged["metadata"]["b"], 2);
+                assert_eq!(merged["metadata"]["shared"], "local");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // 원격이 최근 → 원격 필드, 로컬 전용 metadata 키는 살려서 push
+        let older_local = serde_json::json!({
+            "id": "w1",
+            "title": "Local",
+            "metadata": { "a": 1 },
+            "updated_at": "2024-12-31 00:00:00",
+        });
+        match resolve(ConflictPolicy::MergeMetadata, &older_local, &remote) {
+            Resolution::Local(merged) => {
+                assert_eq!(merged["title"], "Remote");
+                assert_eq!(merged["metadata"]["a"], 1);
+                assert_eq!(merged["metadata"]["shared"], "remote");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let no_metadata = serde_json::json!({ "id": "w1", "updated_at": "2025-01-01T00:00:00Z" });
+        assert_eq!(
+            resolve(ConflictPolicy::MergeMetadata, &older_local, &no_metadata),
+            Resolution::Remote
+        );
+
+        // 병합해도 원격과 같으면 원격 채택
+        let subset_local = serde_json::json!({
+            "metadata": { "b": 2 },
+            "updated_at": "2024-12-31 00:00:00",
+        });
+        assert_eq!(
+            resolve(ConflictPolicy::MergeMetadata, &subset_local, &remote),
+            Resolution::Remote
+        );
+    }
+
+    #[test]
+    fn test_same_timestamp_normalizes_format() {
+        assert!(same_timestamp(
+            "2025-01-01T00:00:00+00:00",
+            "2025-01-01T00:00:00.000Z"
+        ));
+        assert!(!same_timestamp(
+            "2025-01-01T00:00:00+00:00",
+            "2025-01-01T00:00:01+00:00"
+        ));
+        assert!(parse_timestamp("2025-01-01 09:30:00").is_some());
+    }
+}
@@ -0,0 +1,1072 @@
+// ===========================================
+// sync_engine.rs — local_db ↔ Supabase 양방향 동기화
+// push: sync_queue → PostgREST upsert/delete → mark_synced
+// pull: 테이블별 (기준 시각, id) 커서 이후 변경 → 로컬 반영
+// 양쪽 모두 기준 버전(remote_updated_at) 비교로 충돌 감지 → sync_conflict 정책
+// 로그인 사용자 JWT로 요청 (로그아웃 상태면 건너뜀)
+// ===========================================
+
+use crate::auth_session::{self, AuthSession};
+use crate::local_db::{self, LocalDb, RemoteApply};
+use crate::models::{GOAL_STATUSES, WORK_STATUSES};
+use crate::sync_client::SupabaseConfig;
+use crate::sync_conflict::{self, Resolution};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// 동기화 대상 테이블 → pull 기준 컬럼
+/// (session_logs는 원격에 updated_at이 없어 created_at 기준)
+pub const SYNC_TABLES: &[(&str, &str)] = &[
+    ("work_items", "updated_at"),
+    ("plans", "updated_at"),
+    ("goals", "updated_at"),
+    ("session_logs", "created_at"),
+];
+
+/// pull 1회 최대 행 수
+const PULL_PAGE_SIZE: usize = 500;
+/// 한 번에 따라잡을 최대 페이지 수
+const MAX_PULL_PAGES: usize = 20;
+
+/// pull 위치 (기준 컬럼 값, id) — 같은 시각 행이 한 페이지를 넘어도 누락 없이 이어받기
+/// 저장 형식 "<값>|<id>", 이전 형식(값만)은 id "" → 해당 시각부터 다시 받음
+#[derive(Debug, Clone, PartialEq)]
+struct PullCursor {
+    at: String,
+    id: String,
+}
+
+impl PullCursor {
+    fn parse(stored: &str) -> Self {
+        match stored.split_once('|') {
+            Some((at, id)) => Self {
+                at: at.to_string(),
+                id: id.to_string(),
+            },
+            None => Self {
+                at: stored.to_string(),
+                id: String::new(),
+            },
+        }
+    }
+
+    fn encode(&self) -> String {
+        format!("{}|{}", self.at, self.id)
+    }
+
+    /// PostgREST 필터: (column, id) > (at, id)
+    fn filter(&self, column: &str) -> (String, String) {
+        (
+            "or".to_string(),
+            format!(
+                r#"({col}.gt."{at}",and({col}.eq."{at}",id.gt."{id}"))"#,
+                col = column,
+                at = self.at,
+                id = self.id
+            ),
+        )
+    }
+}
+
+/// 동기화 대상 테이블인지
+pub fn is_sync_table(table_name: &str) -> bool {
+    SYNC_TABLES.iter().any(|(t, _)| *t == table_name)
+}
+
+/// 원격 updated_at으로 버전 비교가 가능한 테이블인지
+fn tracks_versions(table_name: &str) -> bool {
+    SYNC_TABLES
+        .iter()
+        .any(|(t, c)| *t == table_name && *c == "updated_at")
+}
+
+/// 테이블별 push 컬럼 (Supabase 스키마, user_id 제외)
+/// 그 외 컬럼을 보내면 PostgREST가 배치 전체를 거부
+const REMOTE_COLUMNS: &[(&str, &[&str])] = &[
+    (
+        "work_items",
+        &[
+            "id",
+            "project_id",
+            "title",
+            "status",
+            "next_action",
+            "estimate_min",
+            "energy",
+            "due_at",
+            "source_app",
+            "source_ref",
+            "goal_id",
+            "started_at",
+            "completed_at",
+            "deleted_at",
+            "actual_min",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "plans",
+        &[
+            "id",
+            "title",
+            "plan_type",
+            "status",
+            "priority",
+            "description",
+            "due_at",
+            "metadata",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "goals",
+        &[
+            "id",
+            "project_id",
+            "plan_id",
+            "title",
+            "status",
+            "priority",
+            "description",
+            "due_at",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    (
+        "session_logs",
+        &[
+            "id",
+            "work_item_id",
+            "started_at",
+            "ended_at",
+            "result",
+            "done_log",
+            "created_at",
+        ],
+    ),
+];
+
+/// 행 소유자(user_id) 컬럼이 있는 테이블 (goals는 없음)
+const OWNED_TABLES: &[&str] = &["work_items", "plans", "session_logs"];
+
+/// 원격에 올리지 않는 레코드인지 (작업 항목과 연결되지 않은 에이전트 세션 기록)
+fn is_local_only(table_name: &str, payload: &serde_json::Value) -> bool {
+    table_name == "session_logs" && !payload["work_item_id"].is_string()
+}
+
+/// 큐 payload → 원격 행 (허용 컬럼만 + 소유자)
+/// v9 이전에 큐에 쌓인 'open' 상태는 backlog로, 그 외 원격에 없는 상태값은 거부
+fn remote_row(
+    table_name: &str,
+    payload: &serde_json::Value,
+    user_id: &str,
+) -> Result<serde_json::Value, String> {
+    let columns = REMOTE_COLUMNS
+        .iter()
+        .find(|(t, _)| *t == table_name)
+        .map(|(_, c)| *c)
+        .ok_or_else(|| format!("동기화 대상이 아닌 테이블: {}", table_name))?;
+    let mut row: serde_json::Map<String, serde_json::Value> = columns
+        .iter()
+        .filter_map(|c| payload.get(*c).map(|v| (c.to_string(), v.clone())))
+        .collect();
+
+    let statuses = match table_name {
+        "goals" => GOAL_STATUSES,
+        "session_logs" => &[],
+        _ => WORK_STATUSES,
+    };
+    if let Some(status) = row.get("status").and_then(|s| s.as_str()) {
+        if status == "open" {
+            row.insert("status".to_string(), serde_json::json!("backlog"));
+        } else if !statuses.contains(&status) {
+            return Err(format!("원격에 없는 상태값: {}", status));
+        }
+    }
+    if OWNED_TABLES.contains(&table_name) {
+        row.insert(
+            "user_id".to_string(),
+            serde_json::Value::String(user_id.to_string()),
+        );
+    }
+    Ok(serde_json::Value::Object(row))
+}
+
+/// push 실패 구분
+#[derive(Debug)]
+enum PushError {
+    /// 서버가 행을 거부 (4xx, 재시도해도 같은 결과) → 해당 레코드만 격리
+    Rejected(String),
+    /// 네트워크/서버/인증 오류 → 순서 보장을 위해 다음 주기에 재시도
+    Transient(String),
+}
+
+/// 동기화 1회 결과
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SyncSummary {
+    pub pushed: usize,
+    pub pulled: usize,
+    /// 로컬 미전송 변경 때문에 반영하지 않은 원격 행
+    pub skipped: usize,
+    /// 감지된 충돌 (자동 해결 포함)
+    pub conflicts: usize,
+    /// 원격이 거부해 큐에서 뺀 레코드 (sync_status = 'failed', 다음 수정 시 재전송)
+    pub rejected: usize,
+    pub errors: Vec<String>,
+}
+
+/// 레코드 1건의 마지막 payload + 해당 큐 항목
+#[derive(Debug, Clone, PartialEq)]
+struct PushRecord {
+    id: String,
+    payload: serde_json::Value,
+    queue_ids: Vec<i64>,
+}
+
+/// 같은 테이블/작업의 연속 큐 항목 묶음
+/// (같은 배치 내 중복 id는 PostgREST upsert 오류 → 레코드별 하나로)
+#[derive(Debug, Clone, PartialEq)]
+struct PushBatch {
+    table: String,
+    operation: String,
+    records: Vec<PushRecord>,
+}
+
+impl PushBatch {
+    fn queue_ids(&self) -> Vec<i64> {
+        self.records
+            .iter()
+            .flat_map(|r| r.queue_ids.iter().copied())
+            .collect()
+    }
+}
+
+/// sync_queue 항목(get_pending_sync) → 순서를 유지한 push 배치
+fn plan_push(items: &[serde_json::Value]) -> Vec<PushBatch> {
+    let mut batches: Vec<PushBatch> = Vec::new();
+    for item in items {
+        let (queue_id, table, record_id, operation) = match (
+            item["id"].as_i64(),
+            item["table_name"].as_str(),
+            item["record_id"].as_str(),
+            item["operation"].as_str(),
+        ) {
+            (Some(q), Some(t), Some(r), Some(o)) => (q, t, r, o),
+            _ => continue,
+        };
+        let operation = if operation == "delete" {
+            "delete"
+        } else {
+            "upsert"
+        };
+
+        let same = batches
+            .last()
+            .map(|b| b.table == table && b.operation == operation)
+            .unwrap_or(false);
+        if !same {
+            batches.push(PushBatch {
+                table: table.to_string(),
+                operation: operation.to_string(),
+                records: Vec::new(),
+            });
+        }
+        let batch = batches.last_mut().expect("batch pushed above");
+
+        let mut payload = item["payload"].clone();
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert("id".to_string(), serde_json::json!(record_id));
+        }
+        match batch.records.iter_mut().find(|r| r.id == record_id) {
+            Some(existing) => {
+                existing.payload = payload;
+                existing.queue_ids.push(queue_id);
+            }
+            None => batch.records.push(PushRecord {
+                id: record_id.to_string(),
+                payload,
+                queue_ids: vec![queue_id],
+            }),
+        }
+    }
+    batches
+}
+
+/// 양방향 동기화 엔진
+pub struct SyncEngine {
+    client: reqwest::Client,
+    config: SupabaseConfig,
+    db: Arc<LocalDb>,
+    /// 로그인 사용자 세션 (요청 Authorization + push 행 user_id)
+    auth: Arc<AuthSession>,
+    /// push/pull 동시 실행 방지
+    running: AtomicBool,
+    /// 미동의 중 비운 큐를 pending 레코드로 재구성했는지
+    queue_restored: AtomicBool,
+}
+
+impl SyncEngine {
+    pub fn new(config: SupabaseConfig, db: Arc<LocalDb>, auth: Arc<AuthSession>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+            db,
+            auth,
+            running: AtomicBool::new(false),
+            queue_restored: AtomicBool::new(false),
+        }
+    }
+
+    /// 동기화 동의(sync_consent) 여부
+    fn has_consent(&self) -> bool {
+        matches!(self.db.get_preference("sync_consent"), Ok(Some(v)) if v == "true")
+    }
+
+    /// push → pull 1회 실행 (미동의/로그아웃/이미 실행 중이면 빈 결과)
+    /// 미동의 중에는 sync_queue를 비워 무한정 쌓이지 않게 함
+    pub async fn sync_once(&self) -> SyncSummary {
+        let mut summary = SyncSummary::default();
+        if !self.has_consent() {
+            self.queue_restored.store(false, Ordering::SeqCst);
+            if let Err(e) = self.db.clear_sync_queue() {
+                summary.errors.push(format!("sync_queue 정리 실패: {}", e));
+            }
+            return summary;
+        }
+        if !self.auth.is_signed_in() || self.running.swap(true, Ordering::SeqCst) {
+            return summary;
+        }
+
+        if !self.queue_restored.swap(true, Ordering::SeqCst) {
+            let tables: Vec<&str> = SYNC_TABLES.iter().map(|(t, _)| *t).collect();
+            match self.db.requeue_pending_records(&tables) {
+                Ok(0) => {}
+                Ok(n) => log::info!("🔄 미동기화 레코드 {}건 큐 재등록", n),
+                Err(e) => {
+                    self.queue_restored.store(false, Ordering::SeqCst);
+                    summary
+                        .errors
+                        .push(format!("sync_queue 재구성 실패: {}", e));
+                }
+            }
+        }
+
+        self.push(&mut summary).await;
+        for (table, cursor_column) in SYNC_TABLES {
+            self.pull(table, cursor_column, &mut summary).await;
+        }
+        self.running.store(false, Ordering::SeqCst);
+
+        if summary.pushed > 0 || summary.pulled > 0 || summary.conflicts > 0 {
+            log::info!(
+                "🔄 동기화: push {}건, pull {}건 (보류 {}건, 충돌 {}건)",
+                summary.pushed,
+                summary.pulled,
+                summary.skipped,
+                summary.conflicts
+            );
+        }
+        summary
+    }
+
+    /// sync_queue → 원격 테이블
+    /// 원격에 올리지 않는/거부된 레코드는 큐에서 빼고 나머지는 계속 전송
+    async fn push(&self, summary: &mut SyncSummary) {
+        let items = match self.db.get_pending_sync() {
+            Ok(items) => items,
+            Err(e) => {
+                summary.errors.push(format!("sync_queue 조회 실패: {}", e));
+                return;
+            }
+        };
+        let Some(user_id) = self.auth.user_id() else {
+            summary.errors.push(auth_session::NOT_SIGNED_IN.to_string());
+            return;
+        };
+
+        for mut batch in plan_push(&items) {
+            if !is_sync_table(&batch.table) {
+                // 원격에 없는 테이블 → 큐에서 제외 (매 주기 재시도 방지)
+                if let Err(e) = self.db.mark_synced(&batch.queue_ids()) {
+                    summary.errors.push(format!("mark_synced 실패: {}", e));
+                    return;
+                }
+                continue;
+            }
+            let upsert = batch.operation != "delete";
+            if upsert {
+                if let Err(e) = self.screen(&mut batch, &user_id, summary) {
+                    summary.errors.push(e);
+                    return;
+                }
+                if batch.records.is_empty() {
+                    continue;
+                }
+            }
+            if upsert && tracks_versions(&batch.table) {
+                if let Err(e) = self.check_conflicts(&mut batch, summary).await {
+                    summary.errors.push(e);
+                    return;
+                }
+                if batch.records.is_empty() {
+                    continue;
+                }
+            }
+
+            let result = match self.push_batch(&batch, &user_id).await {
+                Ok(returned) => self.finish_batch(&batch, &returned, summary),
+                Err(PushError::Rejected(e)) if batch.records.len() == 1 => {
+                    self.set_aside(&batch, "failed", Some(&e), summary)
+                }
+                // 어느 레코드가 거부됐는지 모름 → 레코드별로 다시
+                Err(PushError::Rejected(_)) => self.push_each(batch, &user_id, summary).await,
+                Err(PushError::Transient(e)) => Err(e),
+            };
+            if let Err(e) = result {
+                // 이후 배치는 순서 보장을 위해 다음 주기로
+                summary.errors.push(e);
+                return;
+            }
+        }
+    }
+
+    /// 원격에 올릴 수 없는 레코드를 배치에서 제외
+    /// (작업 항목 없는 세션 기록 → 'local', 원격 스키마 위반 → 'failed')
+    fn screen(
+        &self,
+        batch: &mut PushBatch,
+        user_id: &str,
+        summary: &mut SyncSummary,
+    ) -> Result<(), String> {
+        let mut kept = Vec::with_capacity(batch.records.len());
+        for record in std::mem::take(&mut batch.records) {
+            let (sync_status, reason) = if is_local_only(&batch.table, &record.payload) {
+                ("local", None)
+            } else {
+                match remote_row(&batch.table, &record.payload, user_id) {
+                    Ok(_) => {
+                        kept.push(record);
+                        continue;
+                    }
+                    Err(e) => ("failed", Some(e)),
+                }
+            };
+            let single = PushBatch {
+                table: batch.table.clone(),
+                operation: batch.operation.clone(),
+                records: vec![record],
+            };
+            self.set_aside(&single, sync_status, reason.as_deref(), summary)?;
+        }
+        batch.records = kept;
+        Ok(())
+    }
+
+    /// 거부된 배치를 레코드 1건씩 다시 전송 → 거부된 레코드만 격리
+    async fn push_each(
+        &self,
+        batch: PushBatch,
+        user_id: &str,
+        summary: &mut SyncSummary,
+    ) -> Result<(), String> {
+        let PushBatch {
+            table,
+            operation,
+            records,
+        } = batch;
+        for record in records {
+            let single = PushBatch {
+                table: table.clone(),
+                operation: operation.clone(),
+                records: vec![record],
+            };
+            match self.push_batch(&single, user_id).await {
+                Ok(returned) => self.finish_batch(&single, &returned, summary)?,
+                Err(PushError::Rejected(e)) => {
+                    self.set_aside(&single, "failed", Some(&e), summary)?
+                }
+                Err(PushError::Transient(e)) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// push 성공 → 큐 완료 + 레코드 synced + 서버가 매긴 updated_at을 기준 버전으로
+    fn finish_batch(
+        &self,
+        batch: &PushBatch,
+        returned: &[serde_json::Value],
+        summary: &mut SyncSummary,
+    ) -> Result<(), String> {
+        let queue_ids = batch.queue_ids();
+        self.db
+            .mark_synced(&queue_ids)
+            .map_err(|e| format!("mark_synced 실패: {}", e))?;
+        if batch.operation != "delete" {
+            let record_ids: Vec<String> = batch.records.iter().map(|r| r.id.clone()).collect();
+            let _ = self.db.mark_records_synced(&batch.table, &record_ids);
+            for row in returned {
+                if let (Some(id), Some(updated_at)) =
+                    (row["id"].as_str(), row["updated_at"].as_str())
+                {
+                    let _ = self.db.set_remote_baseline(&batch.table, id, updated_at);
+                }
+            }
+        }
+        summary.pushed += queue_ids.len();
+        Ok(())
+    }
+
+    /// 전송하지 않을 레코드를 큐에서 빼고 sync_status 기록 (reason 있으면 거부로 집계)
+    /// 다음 로컬 수정 시 다시 pending → 재전송
+    fn set_aside(
+        &self,
+        batch: &PushBatch,
+        sync_status: &str,
+        reason: Option<&str>,
+        summary: &mut SyncSummary,
+    ) -> Result<(), String> {
+        self.db
+            .mark_synced(&batch.queue_ids())
+            .map_err(|e| format!("mark_synced 실패: {}", e))?;
+        let record_ids: Vec<String> = batch.records.iter().map(|r| r.id.clone()).collect();
+        if batch.operation != "delete" {
+            self.db
+                .set_records_sync_status(&batch.table, &record_ids, sync_status)
+                .map_err(|e| format!("sync_status 기록 실패: {}", e))?;
+        }
+        if let Some(reason) = reason {
+            log::warn!(
+                "⚠ {}/{} 전송 거부 → 보류: {}",
+                batch.table,
+                record_ids.join(","),
+                reason
+            );
+            summary.rejected += record_ids.len();
+            summary.errors.push(format!(
+                "{}/{} 전송 거부: {}",
+                batch.table,
+                record_ids.join(","),
+                reason
+            ));
+        }
+        Ok(())
+    }
+
+    /// push 전 원격 버전 확인 — 기준 버전 이후 원격이 바뀐 레코드는 정책대로 처리
+    /// (로컬 유지 시 병합 payload로 교체, 원격 채택/수동 대기 시 배치에서 제외)
+    async fn check_conflicts(
+        &self,
+        batch: &mut PushBatch,
+        summary: &mut SyncSummary,
+    ) -> Result<(), String> {
+        let ids: Vec<&str> = batch.records.iter().map(|r| r.id.as_str()).collect();
+        let remote_rows = self
+            .fetch(
+                &batch.table,
+                vec![
+                    ("select".to_string(), "*".to_string()),
+                    ("id".to_string(), format!("in.({})", ids.join(","))),
+                ],
+            )
+            .await?;
+
+        let table = batch.table.clone();
+        let mut kept = Vec::with_capacity(batch.records.len());
+        for mut record in std::mem::take(&mut batch.records) {
+            let remote = match remote_rows
+                .iter()
+                .find(|r| r["id"].as_str() == Some(record.id.as_str()))
+            {
+                Some(remote) => remote,
+                None => {
+                    kept.push(record);
+                    continue;
+                }
+            };
+            let baseline = self
+                .db
+                .get_remote_baseline(&table, &record.id)
+                .ok()
+                .flatten();
+            let remote_changed = match (baseline.as_deref(), remote["updated_at"].as_str()) {
+                (Some(base), Some(current)) => !sync_conflict::same_timestamp(base, current),
+                _ => false,
+            };
+            if !remote_changed {
+                kept.push(record);
+                continue;
+            }
+            if let Some(payload) = self.settle_conflict(&table, &record.id, remote, summary) {
+                record.payload = payload;
+                kept.push(record);
+            }
+        }
+        batch.records = kept;
+        Ok(())
+    }
+
+    /// 충돌 1건에 테이블 정책 적용 + 기록, 로컬을 유지하면 push할 payload 반환
+    fn settle_conflict(
+        &self,
+        table: &str,
+        record_id: &str,
+        remote: &serde_json::Value,
+        summary: &mut SyncSummary,
+    ) -> Option<serde_json::Value> {
+        let local = match self.db.get_record(table, record_id) {
+            Ok(Some(record)) => local_db::strip_local_columns(&record),
+            Ok(None) => return None,
+            Err(e) => {
+                summary
+                    .errors
+                    .push(format!("{} 로컬 조회 실패: {}", table, e));
+                return None;
+            }
+        };
+        let policy = sync_conflict::policy_for(&self.db, table);
+        summary.conflicts += 1;
+
+        let (result, resolution, payload) = match sync_conflict::resolve(policy, &local, remote) {
+            Resolution::Local(merged) => {
+                let label = if merged == local { "local" } else { "merged" };
+                let result = self
+                    .db
+                    .keep_local(table, &merged, remote["updated_at"].as_str());
+                (result, Some(label), Some(merged))
+            }
+            Resolution::Remote => (self.db.take_remote(table, remote), Some("remote"), None),
+            Resolution::Manual => (Ok(()), None, None),
+        };
+        if let Err(e) = result {
+            summary
+                .errors
+                .push(format!("{} 충돌 해결 실패: {}", table, e));
+            return None;
+        }
+        if resolution.is_none() {
+            log::warn!("⚠ {}/{} 충돌 → 수동 해결 대기", table, record_id);
+        }
+        if let Err(e) = self.db.record_conflict(
+            table,
+            record_id,
+            policy.as_str(),
+            &local,
+            remote,
+            resolution,
+        ) {
+            summary.errors.push(format!("충돌 기록 실패: {}", e));
+        }
+        payload
+    }
+
+    /// upsert는 서버가 반영한 행(updated_at 포함)을 돌려받음
+    async fn push_batch(
+        &self,
+        batch: &PushBatch,
+        user_id: &str,
+    ) -> Result<Vec<serde_json::Value>, PushError> {
+        let url = format!("{}/rest/v1/{}", self.config.url, batch.table);
+        let request = if batch.operation == "delete" {
+            let ids: Vec<&str> = batch.records.iter().map(|r| r.id.as_str()).collect();
+            self.client
+                .delete(&url)
+                .query(&[("id", format!("in.({})", ids.join(",")))])
+        } else {
+            let rows = batch
+                .records
+                .iter()
+                .map(|r| remote_row(&batch.table, &r.payload, user_id))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(PushError::Rejected)?;
+            self.client
+                .post(&url)
+                .query(&[("on_conflict", "id")])
+                .header("Content-Type", "application/json")
+                .header(
+                    "Prefer",
+                    "resolution=merge-duplicates,return=representation",
+                )
+                .json(&rows)
+        };
+
+        let resp = self
+            .authorize(request)
+            .await
+            .map_err(PushError::Transient)?
+            .send()
+            .await
+            .map_err(|e| PushError::Transient(format!("네트워크 오류: {}", e)))?;
+        let status = resp.status();
+        if status.is_success() {
+            return Ok(resp
+                .json::<Vec<serde_json::Value>>()
+                .await
+                .unwrap_or_default());
+        }
+        let body = resp.text().await.unwrap_or_default();
+        let message = format!("{} push 실패 ({}): {}", batch.table, status, body);
+        // 인증 만료/요청 제한은 재시도, 그 외 4xx는 행 자체 문제
+        let retryable = matches!(status.as_u16(), 401 | 408 | 429) || !status.is_client_error();
+        Err(if retryable {
+            PushError::Transient(message)
+        } else {
+            PushError::Rejected(message)
+        })
+    }
+
+    /// high-water mark 이후 원격 변경 → 로컬
+    async fn pull(&self, table: &str, cursor_column: &str, summary: &mut SyncSummary) {
+        let mut cursor = self
+            .db
+            .get_sync_cursor(table)
+            .ok()
+            .flatten()
+            .map(|c| PullCursor::parse(&c));
+
+        for _ in 0..MAX_PULL_PAGES {
+            let rows = match self
+                .fetch_since(table, cursor_column, cursor.as_ref())
+                .await
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    summary.errors.push(e);
+                    return;
+                }
+            };
+            let page_len = rows.len();
+
+            for row in &rows {
+                match self.db.apply_remote_row(table, row) {
+                    Ok(RemoteApply::Applied) => summary.pulled += 1,
+                    Ok(RemoteApply::Skipped) => summary.skipped += 1,
+                    Ok(RemoteApply::Conflict) => {
+                        let record_id = row["id"].as_str().unwrap_or_default();
+                        if let Some(payload) = self.settle_conflict(table, record_id, row, summary)
+                        {
+                            // 병합/로컬 유지 결과는 다음 push로 원격에 반영
+                            if let Err(e) = self.db.enqueue_sync(
+                                table,
+                                record_id,
+                                "update",
+                                &payload.to_string(),
+                            ) {
+                                summary
+                                    .errors
+                                    .push(format!("{} 큐 추가 실패: {}", table, e));
+                            }
+                        }
+                    }
+                    Err(e) => summary.errors.push(format!("{} 반영 실패: {}", table, e)),
+                }
+                if let (Some(at), Some(id)) = (row[cursor_column].as_str(), row["id"].as_str()) {
+                    cursor = Some(PullCursor {
+                        at: at.to_string(),
+                        id: id.to_string(),
+                    });
+                }
+            }
+
+            if let Some(ref c) = cursor {
+                if let Err(e) = self.db.set_sync_cursor(table, &c.encode()) {
+                    summary.errors.push(format!("cursor 저장 실패: {}", e));
+                    return;
+                }
+            }
+            if page_len < PULL_PAGE_SIZE {
+                return;
+            }
+        }
+    }
+
+    async fn fetch_since(
+        &self,
+        table: &str,
+        cursor_column: &str,
+        cursor: Option<&PullCursor>,
+    ) -> Result<Vec<serde_json::Value>, String> {
+        let mut query = vec![
+            ("select".to_string(), "*".to_string()),
+            ("order".to_string(), format!("{}.asc,id.asc", cursor_column)),
+            ("limit".to_string(), PULL_PAGE_SIZE.to_string()),
+        ];
+        if let Some(c) = cursor {
+            query.push(c.filter(cursor_column));
+        }
+        self.fetch(table, query).await
+    }
+
+    async fn fetch(
+        &self,
+        table: &str,
+        query: Vec<(String, String)>,
+    ) -> Result<Vec<serde_json::Value>, String> {
+        let url = format!("{}/rest/v1/{}", self.config.url, table);
+        let resp = self
+            .authorize(self.client.get(&url).query(&query))
+            .await?
+            .send()
+            .await
+            .map_err(|e| format!("네트워크 오류: {}", e))?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            return Err(format!("{} pull 실패 ({}): {}", table, status, body));
+        }
+        resp.json::<Vec<serde_json::Value>>()
+            .await
+            .map_err(|e| format!("{} 응답 파싱 실패: {}", table, e))
+    }
+
+    /// 사용자 JWT 첨부 (로그아웃 상태면 NOT_SIGNED_IN)
+    async fn authorize(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder, String> {
+        let token = self.auth.access_token().await?;
+        Ok(request
+            .header("apikey", &self.config.anon_key)
+            .header("Authorization", format!("Bearer {}", token)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_push_groups_in_order_and_dedupes() {
+        let item = |id: i64, table: &str, record: &str, op: &str, title: &str| {
+            serde_json::json!({
+                "id": id,
+                "table_name": table,
+                "record_id": record,
+                "operation": op,
+                "payload": { "title": title },
+            })
+        };
+        let items = vec![
+            item(1, "work_items", "a", "insert", "A1"),
+            item(2, "work_items", "a", "update", "A2"),
+            item(3, "work_items", "b", "insert", "B"),
+            item(4, "plans", "p", "insert", "P"),
+            item(5, "work_items", "a", "delete", ""),
+        ];
+
+        let batches = plan_push(&items);
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].table, "work_items");
+        assert_eq!(batches[0].queue_ids(), vec![1, 2, 3]);
+        assert_eq!(batches[0].records.len(), 2);
+        assert_eq!(batches[0].records[0].payload["title"], "A2");
+        assert_eq!(batches[0].records[0].payload["id"], "a");
+        assert_eq!(batches[0].records[0].queue_ids, vec![1, 2]);
+        assert_eq!(batches[1].table, "plans");
+        assert_eq!(batches[2].operation, "delete");
+        assert!(is_sync_table("goals"));
+        assert!(!is_sync_table("projects"));
+        assert!(tracks_versions("plans"));
+        assert!(!tracks_versions("session_logs"));
+    }
+
+    /// Supabase 마이그레이션 → 테이블별 (컬럼, status CHECK 허용값)
+    fn remote_schema() -> std::collections::HashMap<String, (Vec<String>, Vec<String>)> {
+        let migrations = [
+            include_str!("../../supabase/migrations/001_initial_schema.sql"),
+            include_str!("../../supabase/migrations/001_create_plans.sql"),
+            include_str!("../../supabase/migrations/006_add_user_id_rls.sql"),
+            include_str!("../../supabase/migrations/011_work_item_lifecycle.sql"),
+            include_str!("../../supabase/migrations/013_hierarchical_structure.sql"),
+        ];
+        let mut schema: std::collections::HashMap<String, (Vec<String>, Vec<String>)> =
+            Default::default();
+        for sql in migrations {
+            let sql: String = sql
+                .lines()
+                .map(|l| l.split("--").next().unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(" ");
+            for statement in sql.split(';') {
+                let words: Vec<&str> = statement.split_whitespace().collect();
+                let upper: Vec<String> = words.iter().map(|w| w.to_uppercase()).collect();
+                if upper.starts_with(&["CREATE".into(), "TABLE".into()]) {
+                    let table = words[words.iter().position(|w| *w == "(").unwrap_or(5) - 1];
+                    let body = &statement[statement.find('(').unwrap() + 1..];
+                    let mut depth = 0;
+                    let mut part = String::new();
+                    let mut parts = Vec::new();
+                    for c in body.chars() {
+                        match c {
+                            '(' => depth += 1,
+                            ')' if depth == 0 => break,
+                            ')' => depth -= 1,
+                            ',' if depth == 0 => {
+                                parts.push(std::mem::take(&mut part));
+                                continue;
+                            }
+                            _ => {}
+                        }
+                        part.push(c);
+                    }
+                    parts.push(part);
+                    let entry = schema.entry(table.to_string()).or_default();
+                    for part in parts {
+                        let name = part.split_whitespace().next().unwrap_or_default();
+                        if name.is_empty() || name.chars().any(|c| c.is_ascii_uppercase()) {
+                            continue;
+                        }
+                        entry.0.push(name.to_string());
+                        if name == "status" {
+                            entry.1 = part
+                                .split('\'')
+                                .skip(1)
+                                .step_by(2)
+                                .map(String::from)
+                                .collect();
+                        }
+                    }
+                } else if upper.starts_with(&["ALTER".into(), "TABLE".into()]) {
+                    for (i, w) in upper.iter().enumerate() {
+                        if w == "COLUMN" && upper[i - 1] == "ADD" {
+                            let name = if upper.get(i + 1).map(String::as_str) == Some("IF") {
+                                words[i + 4]
+                            } else {
+                                words[i + 1]
+                            };
+                            let entry = schema.entry(words[2].to_string()).or_default();
+                            entry.0.push(name.to_string());
+                        }
+                    }
+                }
+            }
+        }
+        schema
+    }
+
+    #[test]
+    fn test_remote_row_matches_supabase_schema() {
+        let schema = remote_schema();
+        let tmp = std::env::temp_dir().join("orchestrator_push_payload_test.db");
+        let _ = std::fs::remove_file(&tmp);
+        let db = LocalDb::open_at(&tmp).unwrap();
+
+        // 모든 필드를 채운 로컬 행 → push payload
+        let upsert = |table: &str, value: serde_json::Value| {
+            let record = crate::models::SyncRecord::decode(table, value).unwrap();
+            db.upsert_syncable(&record).unwrap()
+        };
+        let plan_id = upsert(
+            "plans",
+            serde_json::json!({
+                "title": "P", "plan_type": "event", "status": "active", "priority": "high",
+                "description": "d", "due_at": "2026-03-01", "metadata": { "start_at": "2026-02-01" },
+            }),
+        );
+        let goal_id = upsert(
+            "goals",
+            serde_json::json!({
+                "title": "G", "plan_id": plan_id, "status": "active", "priority": 2,
+                "description": "d", "due_at": "2026-03-01",
+            }),
+        );
+        let item_id = upsert(
+            "work_items",
+            serde_json::json!({
+                "title": "W", "status": "done", "project_id": "p1", "next_action": "n",
+                "estimate_min": 30, "energy": "low", "due_at": "2026-03-01",
+                "source_app": "github", "source_ref": "#1", "goal_id": goal_id,
+                "started_at": "2026-03-01T00:00:00Z",
+            }),
+        );
+        let log_id = upsert(
+            "session_logs",
+            serde_json::json!({
+                "work_item_id": item_id, "started_at": "2026-03-01 09:00:00",
+                "ended_at": "2026-03-01 09:45:00", "result": "done", "done_log": "ok",
+                "project_id": "p1", "editor_type": "cursor", "duration_min": 45,
+                "summary": "ok", "metadata": { "task_name": "t" },
+            }),
+        );
+
+        for (table, id) in [
+            ("plans", &plan_id),
+            ("goals", &goal_id),
+            ("work_items", &item_id),
+            ("session_logs", &log_id),
+        ] {
+            let (columns, statuses) = &schema[table];
+            let local = local_db::strip_local_columns(&db.get_record(table, id).unwrap().unwrap());
+            assert!(!is_local_only(table, &local));
+            let row = remote_row(table, &local, "u1").unwrap();
+            for key in row.as_object().unwrap().keys() {
+                assert!(columns.contains(key), "{}.{} 원격에 없음", table, key);
+            }
+            if let Some(status) = row["status"].as_str() {
+                assert!(
+                    statuses.iter().any(|s| s == status),
+                    "{}: {}",
+                    table,
+                    status
+                );
+            }
+            // 원격 컬럼이 있는 로컬 값은 빠짐없이 전송
+            for key in local.as_object().unwrap().keys() {
+                if columns.contains(key) {
+                    assert!(row.get(key).is_some(), "{}.{} 누락", table, key);
+                }
+            }
+            assert_eq!(
+                row.get("user_id").is_some(),
+                columns.iter().any(|c| c == "user_id"),
+                "{} user_id",
+                table
+            );
+        }
+        drop(db);
+        let _ = std::fs::remove_file(&tmp);
+
+        // v9 이전에 큐에 쌓인 payload: 'open' → backlog, 로컬 전용 컬럼 제외
+        let legacy = serde_json::json!({
+            "id": "w1", "title": "W", "status": "open", "priority": "medium",
+            "description": "d", "metadata": {},
+        });
+        assert_eq!(
+            remote_row("work_items", &legacy, "u1").unwrap(),
+            serde_json::json!({ "id": "w1", "title": "W", "status": "backlog", "user_id": "u1" })
+        );
+        let invalid = serde_json::json!({ "id": "g1", "title": "G", "status": "blocked" });
+        assert!(remote_row("goals", &invalid, "u1").is_err());
+        assert!(remote_row("projects", &invalid, "u1").is_err());
+        assert!(is_local_only(
+            "session_logs",
+            &serde_json::json!({ "id": "s1", "work_item_id": null, "editor_type": "cursor" })
+        ));
+    }
+
+    #[test]
+    fn test_pull_cursor_compound_filter() {
+        let cursor = PullCursor {
+            at: "2025-01-01T00:00:00+00:00".to_string(),
+            id: "w7".to_string(),
+        };
+        assert_eq!(PullCursor::parse(&cursor.encode()), cursor);
+        assert_eq!(
+            cursor.filter("updated_at"),
+            (
+                "or".to_string(),
+                r#"(updated_at.gt."2025-01-01T00:00:00+00:00",and(updated_at.eq."2025-01-01T00:00:00+00:00",id.gt."w7"))"#
+                    .to_string()
+            )
+        );
+
+        // 이전 형식(시각만) → 같은 시각 행부터 다시
+        let legacy = PullCursor::parse("2025-01-01T00:00:00+00:00");
+        assert_eq!(legacy.at, "2025-01-01T00:00:00+00:00");
+        assert_eq!(legacy.id, "");
+    }
+}
@@ -0,0 +1,223 @@
+// ===========================================
+// sync_worker.rs — 백그라운드 동기화 워커
+// 연결 확인 → 토큰 갱신 → outbox 재전송 + 테이블 동기화 → orchx:sync-status emit + 트레이 툴팁
+// 오프라인/실패 시 jitter가 섞인 지수 backoff
+// ===========================================
+
+use crate::local_db::LocalDb;
+use crate::sync_client::SyncClient;
+use crate::sync_engine::SyncEngine;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::Emitter;
+
+/// 트레이 아이콘 ID (툴팁 갱신용)
+pub const TRAY_ID: &str = "main";
+
+/// 정상 상태 확인 주기 (초)
+const HEALTHY_INTERVAL_SECS: u64 = 30;
+/// backoff 시작/상한 (초)
+const BACKOFF_BASE_SECS: u64 = 5;
+const BACKOFF_MAX_SECS: u64 = 300;
+/// jitter 비율 (±)
+const JITTER_RATIO: f64 = 0.2;
+/// 한 tick에서 재전송할 최대 배치 수
+const MAX_DRAIN_ROUNDS: u32 = 10;
+/// delivered 이벤트 보관 기간 (일)
+const DELIVERED_RETENTION_DAYS: u32 = 7;
+
+/// 동기화 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStatus {
+    Online,
+    Offline,
+    /// 연결은 되지만 재전송 실패 또는 dead 이벤트 존재
+    Degraded,
+    /// 로그아웃/세션 만료 → 이벤트는 outbox에 보류
+    SignedOut,
+}
+
+impl SyncStatus {
+    fn label(&self) -> &'static str {
+        match self {
+            SyncStatus::Online => "온라인",
+            SyncStatus::Offline => "오프라인",
+            SyncStatus::Degraded => "동기화 지연",
+            SyncStatus::SignedOut => "로그인 필요",
+        }
+    }
+}
+
+/// orchx:sync-status 페이로드
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncStatusEvent {
+    pub status: SyncStatus,
+    pub queue_depth: u64,
+    pub dead_letters: u64,
+    pub last_success_at: Option<String>,
+    pub next_check_secs: u64,
+}
+
+/// 연결/로그인 여부 + 재전송 결과 → 상태
+fn classify(online: bool, signed_in: bool, retry_failed: bool, dead_letters: u64) -> SyncStatus {
+    if !online {
+        SyncStatus::Offline
+    } else if !signed_in {
+        SyncStatus::SignedOut
+    } else if retry_failed || dead_letters > 0 {
+        SyncStatus::Degraded
+    } else {
+        SyncStatus::Online
+    }
+}
+
+/// 연속 실패 횟수 → 다음 확인까지 대기 (jitter: -1.0 ~ 1.0)
+fn next_delay(failures: u32, jitter: f64) -> Duration {
+    if failures == 0 {
+        return Duration::from_secs(HEALTHY_INTERVAL_SECS);
+    }
+    let exp = (failures - 1).min(16);
+    let base = (BACKOFF_BASE_SECS << exp).min(BACKOFF_MAX_SECS) as f64;
+    let jittered = base * (1.0 + JITTER_RATIO * jitter.clamp(-1.0, 1.0));
+    Duration::from_secs_f64(jittered.max(1.0))
+}
+
+/// -1.0 ~ 1.0 범위 jitter (여러 클라이언트 재시도 분산용)
+fn random_jitter() -> f64 {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    (nanos % 2001) as f64 / 1000.0 - 1.0
+}
+
+/// 상태 → 트레이 툴팁 문자열
+fn tooltip(event: &SyncStatusEvent) -> String {
+    let mut text = format!("Orchestrator — {}", event.status.label());
+    if event.queue_depth > 0 {
+        text.push_str(&format!(" · 대기 {}건", event.queue_depth));
+    }
+    if event.dead_letters > 0 {
+        text.push_str(&format!(" · 실패 {}건", event.dead_letters));
+    }
+    text
+}
+
+/// 워커 시작 (앱 종료까지 유지)
+pub fn spawn(
+    app: tauri::AppHandle,
+    client: Arc<SyncClient>,
+    engine: Arc<SyncEngine>,
+    db: Arc<LocalDb>,
+) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = db.outbox_prune_delivered(DELIVERED_RETENTION_DAYS) {
+            log::warn!("⚠ outbox 정리 실패: {}", e);
+        }
+
+        let mut failures = 0u32;
+        let mut last_status = None;
+        let mut last_success_at: Option<String> = None;
+
+        let auth = client.auth();
+
+        loop {
+            let online = client.check_connection().await;
+
+            // 만료 임박 토큰은 여기서 미리 갱신 (거부되면 세션 폐기 → SignedOut)
+            if online && auth.is_signed_in() {
+                if let Err(e) = auth.access_token().await {
+                    log::warn!("⚠ {}", e);
+                }
+            }
+            let signed_in = auth.is_signed_in();
+
+            let mut retry_failed = false;
+            if online && signed_in {
+                for _ in 0..MAX_DRAIN_ROUNDS {
+                    let (attempted, succeeded) = client.retry_outbox().await;
+                    if attempted == 0 {
+                        break;
+                    }
+                    if succeeded < attempted {
+                        retry_failed = true;
+                        break;
+                    }
+                }
+
+                let summary = engine.sync_once().await;
+                for e in &summary.errors {
+                    log::warn!("⚠ 테이블 동기화: {}", e);
+                }
+                retry_failed |= !summary.errors.is_empty();
+            }
+
+            let (queue_depth, dead_letters) = db.outbox_counts().unwrap_or((0, 0));
+            let status = classify(online, signed_in, retry_failed, dead_letters);
+            if status == SyncStatus::Offline || retry_failed {
+                failures = failures.saturating_add(1);
+            } else {
+                failures = 0;
+                if signed_in {
+                    last_success_at = Some(chrono::Utc::now().to_rfc3339());
+                }
+            }
+            let delay = next_delay(failures, random_jitter());
+
+            let event = SyncStatusEvent {
+                status,
+                queue_depth,
+                dead_letters,
+                last_success_at: last_success_at.clone(),
+                next_check_secs: delay.as_secs(),
+            };
+            if last_status != Some(status) {
+                log::info!(
+                    "🔄 동기화 상태: {} (대기 {}건)",
+                    status.label(),
+                    queue_depth
+                );
+                last_status = Some(status);
+            }
+            if let Err(e) = app.emit("orchx:sync-status", &event) {
+                log::warn!("  ❌ Tauri emit 실패: {}", e);
+            }
+            if let Some(tray) = app.tray_by_id(TRAY_ID) {
+                let _ = tray.set_tooltip(Some(tooltip(&event).as_str()));
+            }
+
+            tokio::time::sleep(delay).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(false, true, false, 0), SyncStatus::Offline);
+        assert_eq!(classify(true, false, false, 0), SyncStatus::SignedOut);
+        assert_eq!(classify(true, true, true, 0), SyncStatus::Degraded);
+        assert_eq!(classify(true, true, false, 3), SyncStatus::Degraded);
+        assert_eq!(classify(true, true, false, 0), SyncStatus::Online);
+    }
+
+    #[test]
+    fn test_next_delay_backoff_with_jitter() {
+        assert_eq!(
+            next_delay(0, 1.0),
+            Duration::from_secs(HEALTHY_INTERVAL_SECS)
+        );
+        assert_eq!(next_delay(1, 0.0), Duration::from_secs(5));
+        assert_eq!(next_delay(3, 0.0), Duration::from_secs(20));
+        assert_eq!(next_delay(20, 0.0), Duration::from_secs(BACKOFF_MAX_SECS));
+        assert_eq!(next_delay(1, 1.0), Duration::from_secs(6));
+        assert_eq!(next_delay(1, -1.0), Duration::from_secs(4));
+        let j = random_jitter();
+        assert!((-1.0..=1.0).contains(&j));
+    }
+}
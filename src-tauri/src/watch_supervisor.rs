@@ -0,0 +1,421 @@
+// ===========================================
+// watch_supervisor.rs — 프로젝트별 watcher 수명 관리
+// start/stop/pause/resume + 치명적 notify 에러 / 에러 누적 시 backoff 재시작
+// + 디바운스 캐시 주기 정리 + 상태 지표 (get_watcher_health)
+// ===========================================
+
+use crate::sync_client::SyncClient;
+use crate::watcher::{self, WatcherState, ERROR_WINDOW};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 모니터 주기 (초)
+const MONITOR_TICK_SECS: u64 = 1;
+/// 디바운스 캐시 정리 주기 (모니터 tick 단위)
+const PRUNE_EVERY_TICKS: u64 = 30;
+/// 재시작 backoff 상한
+const MAX_BACKOFF_SECS: u64 = 60;
+/// 이 시간 이상 에러 없이 동작하면 backoff 초기화
+const STABLE_RESET_SECS: u64 = 300;
+/// ERROR_WINDOW 안에 notify 에러가 이만큼 쌓이면 재시작
+const RESTART_ERROR_THRESHOLD: usize = 5;
+
+/// watcher 동작 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchStatus {
+    Running,
+    /// 동작 중이지만 최근 notify 에러 있음 (재시작 기준 미만)
+    Degraded,
+    Paused,
+    Restarting,
+}
+
+/// 프로젝트별 watcher 상태 지표
+#[derive(Debug, Clone, Serialize)]
+pub struct WatcherHealthReport {
+    pub repo_full_name: String,
+    pub path: String,
+    pub status: WatchStatus,
+    pub events_total: u64,
+    pub events_per_sec: f64,
+    pub last_event_at: Option<String>,
+    pub errors_total: u64,
+    pub last_error: Option<String>,
+    pub restarts: u32,
+    pub debounce_entries: usize,
+}
+
+struct ManagedWatcher {
+    path: PathBuf,
+    /// Restarting 동안은 None (notify watcher 해제 상태)
+    state: Option<WatcherState>,
+    status: WatchStatus,
+    /// 누적 재시작 횟수
+    restarts: u32,
+    /// 연속 실패 횟수 (backoff 계산용)
+    attempt: u32,
+    next_restart: Option<Instant>,
+    running_since: Instant,
+    /// 재시작 전 watcher의 누적 에러 수
+    errors_before: u64,
+    last_error: Option<String>,
+}
+
+/// 연속 실패 횟수 → 재시작 대기 시간 (1s, 2s, 4s … 최대 60s)
+fn restart_backoff(attempt: u32) -> Duration {
+    let exp = attempt.saturating_sub(1).min(16);
+    Duration::from_secs((1u64 << exp).min(MAX_BACKOFF_SECS))
+}
+
+/// notify 에러 상태 판정
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Verdict {
+    Healthy,
+    Degraded,
+    Restart,
+}
+
+/// 치명적 에러 또는 구간 내 에러 누적 → 재시작, 일시적 에러만 있으면 degraded
+fn assess(fatal: bool, recent_errors: usize) -> Verdict {
+    if fatal || recent_errors >= RESTART_ERROR_THRESHOLD {
+        Verdict::Restart
+    } else if recent_errors > 0 {
+        Verdict::Degraded
+    } else {
+        Verdict::Healthy
+    }
+}
+
+/// 동작 중 watcher의 다음 상태 (일시 정지 중에는 유지)
+fn next_status(current: WatchStatus, verdict: Verdict) -> WatchStatus {
+    match (current, verdict) {
+        (WatchStatus::Paused, _) => WatchStatus::Paused,
+        (_, Verdict::Restart) => WatchStatus::Restarting,
+        (_, Verdict::Degraded) => WatchStatus::Degraded,
+        (_, Verdict::Healthy) => WatchStatus::Running,
+    }
+}
+
+/// 충분히 오래 안정적으로 동작했으면 연속 실패 횟수 초기화
+fn settled_attempt(attempt: u32, running_for: Duration) -> u32 {
+    if running_for.as_secs() >= STABLE_RESET_SECS {
+        0
+    } else {
+        attempt
+    }
+}
+
+/// 프로젝트별 watcher supervisor
+#[derive(Default)]
+pub struct WatchSupervisor {
+    watchers: Mutex<HashMap<String, ManagedWatcher>>,
+}
+
+impl WatchSupervisor {
+    /// watcher 시작 (기존 watcher는 중지 후 교체)
+    pub fn start(
+        &self,
+        repo_full_name: &str,
+        path: PathBuf,
+        app: &tauri::AppHandle,
+        sync_client: Option<Arc<SyncClient>>,
+    ) -> Result<(), String> {
+        self.stop(repo_full_name);
+        let state = watcher::start_watcher(repo_full_name, path.clone(), app.clone(), sync_client)?;
+        let mut watchers = self.watchers.lock().map_err(|e| e.to_string())?;
+        watchers.insert(
+            repo_full_name.to_string(),
+            ManagedWatcher {
+                path,
+                state: Some(state),
+                status: WatchStatus::Running,
+                restarts: 0,
+                attempt: 0,
+                next_restart: None,
+                running_since: Instant::now(),
+                errors_before: 0,
+                last_error: None,
+            },
+        );
+        Ok(())
+    }
+
+    /// watcher 중지 + 자원 해제, 중지했으면 true
+    pub fn stop(&self, repo_full_name: &str) -> bool {
+        let removed = match self.watchers.lock() {
+            Ok(mut w) => w.remove(repo_full_name),
+            Err(_) => return false,
+        };
+        match removed {
+            Some(managed) => {
+                if let Some(state) = managed.state {
+                    watcher::stop_watcher(state);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 전체 중지
+    pub fn stop_all(&self) {
+        let drained: Vec<(String, ManagedWatcher)> = match self.watchers.lock() {
+            Ok(mut w) => w.drain().collect(),
+            Err(_) => return,
+        };
+        for (name, managed) in drained {
+            if let Some(state) = managed.state {
+                watcher::stop_watcher(state);
+            }
+            log::info!("⏸ {} 감시 중지", name);
+        }
+    }
+
+    /// 일시 정지 (OS 감시만 해제, 상태는 유지)
+    pub fn pause(&self, repo_full_name: &str) -> Result<(), String> {
+        let mut watchers = self.watchers.lock().map_err(|e| e.to_string())?;
+        let managed = watchers
+            .get_mut(repo_full_name)
+            .ok_or_else(|| format!("감시 중이 아님: {}", repo_full_name))?;
+        if let Some(ref mut state) = managed.state {
+            state.pause()?;
+        }
+        managed.status = WatchStatus::Paused;
+        managed.next_restart = None;
+        Ok(())
+    }
+
+    /// 재개 (재시작 대기 중이던 watcher는 즉시 재시작 예약)
+    pub fn resume(&self, repo_full_name: &str) -> Result<(), String> {
+        let mut watchers = self.watchers.lock().map_err(|e| e.to_string())?;
+        let managed = watchers
+            .get_mut(repo_full_name)
+            .ok_or_else(|| format!("감시 중이 아님: {}", repo_full_name))?;
+        match managed.state {
+            Some(ref mut state) => {
+                state.resume()?;
+                managed.status = WatchStatus::Running;
+            }
+            None => {
+                managed.status = WatchStatus::Restarting;
+                managed.next_restart = Some(Instant::now());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn status(&self, repo_full_name: &str) -> Option<WatchStatus> {
+        self.watchers
+            .lock()
+            .ok()?
+            .get(repo_full_name)
+            .map(|m| m.status)
+    }
+
+    /// 전체 watcher 상태 지표
+    pub fn health(&self) -> Vec<WatcherHealthReport> {
+        let watchers = match self.watchers.lock() {
+            Ok(w) => w,
+            Err(_) => return Vec::new(),
+        };
+        let mut reports: Vec<WatcherHealthReport> = watchers
+            .iter()
+            .map(|(name, m)| {
+                let health = m.state.as_ref().map(|s| &s.health);
+                WatcherHealthReport {
+                    repo_full_name: name.clone(),
+                    path: m.path.to_string_lossy().to_string(),
+                    status: m.status,
+                    events_total: health.map(|h| h.events_total()).unwrap_or(0),
+                    events_per_sec: health.map(|h| h.events_per_sec()).unwrap_or(0.0),
+                    last_event_at: health.and_then(|h| h.last_event_at()),
+                    errors_total: m.errors_before + health.map(|h| h.errors_total()).unwrap_or(0),
+                    last_error: health
+                        .and_then(|h| h.last_error())
+                        .or_else(|| m.last_error.clone()),
+                    restarts: m.restarts,
+                    debounce_entries: m.state.as_ref().map(|s| s.debounce_entries()).unwrap_or(0),
+                }
+            })
+            .collect();
+        reports.sort_by(|a, b| a.repo_full_name.cmp(&b.repo_full_name));
+        reports
+    }
+
+    /// 모니터 스레드 시작 (앱 종료까지 유지)
+    pub fn spawn_monitor(
+        self: Arc<Self>,
+        app: tauri::AppHandle,
+        sync_client: Option<Arc<SyncClient>>,
+    ) {
+        std::thread::spawn(move || {
+            let mut ticks = 0u64;
+            loop {
+                std::thread::sleep(Duration::from_secs(MONITOR_TICK_SECS));
+                ticks += 1;
+                self.tick(&app, sync_client.as_ref(), ticks % PRUNE_EVERY_TICKS == 0);
+            }
+        });
+    }
+
+    /// 상태 판정 (running ↔ degraded), 재시작 대상 watcher 해제 → backoff 후 재시작,
+    /// 주기적 디바운스 정리
+    fn tick(&self, app: &tauri::AppHandle, sync_client: Option<&Arc<SyncClient>>, prune: bool) {
+        let now = Instant::now();
+        let mut failed = Vec::new();
+        let mut due = Vec::new();
+
+        {
+            let mut watchers = match self.watchers.lock() {
+                Ok(w) => w,
+                Err(_) => return,
+            };
+            for (name, m) in watchers.iter_mut() {
+                match m.state {
+                    Some(ref state) => {
+                        let recent = state.health.recent_watch_errors(now);
+                        let verdict = assess(state.health.is_fatal(), recent);
+                        match next_status(m.status, verdict) {
+                            WatchStatus::Restarting => {
+                                m.errors_before += state.health.errors_total();
+                                m.last_error = state.health.last_error();
+                                m.attempt += 1;
+                                m.next_restart = Some(now + restart_backoff(m.attempt));
+                                m.status = WatchStatus::Restarting;
+                                if let Some(state) = m.state.take() {
+                                    failed.push((name.clone(), state));
+                                }
+                            }
+                            status => {
+                                if status != m.status {
+                                    match status {
+                                        WatchStatus::Degraded => log::warn!(
+                                            "⚠ {} watcher 에러 {}회/{}초 → degraded",
+                                            name,
+                                            recent,
+                                            ERROR_WINDOW.as_secs()
+                                        ),
+                                        _ => log::info!("✅ {} watcher 정상 동작", name),
+                                    }
+                                    m.status = status;
+                                }
+                                if prune {
+                                    state.prune_debounce();
+                                }
+                                m.attempt =
+                                    settled_attempt(m.attempt, now.duration_since(m.running_since));
+                            }
+                        }
+                    }
+                    None => {
+                        if m.status == WatchStatus::Restarting
+                            && m.next_restart.map(|t| t <= now).unwrap_or(false)
+                        {
+                            due.push((name.clone(), m.path.clone()));
+                        }
+                    }
+                }
+            }
+        }
+
+        // notify watcher drop/생성은 락 밖에서
+        for (name, state) in failed {
+            log::warn!("🔁 {} watcher 에러 → 재시작 예약", name);
+            watcher::stop_watcher(state);
+        }
+
+        for (name, path) in due {
+            let result = watcher::start_watcher(&name, path, app.clone(), sync_client.cloned());
+            let mut watchers = match self.watchers.lock() {
+                Ok(w) => w,
+                Err(_) => return,
+            };
+            // 재시작 중 stop/pause 된 경우 결과 폐기
+            let m = match watchers.get_mut(&name) {
+                Some(m) if m.status == WatchStatus::Restarting && m.state.is_none() => m,
+                _ => {
+                    drop(watchers);
+                    if let Ok(state) = result {
+                        watcher::stop_watcher(state);
+                    }
+                    continue;
+                }
+            };
+            match result {
+                Ok(state) => {
+                    m.state = Some(state);
+                    m.status = WatchStatus::Running;
+                    m.restarts += 1;
+                    m.next_restart = None;
+                    m.running_since = Instant::now();
+                    log::info!("👁 {} watcher 재시작 ({}회)", name, m.restarts);
+                }
+                Err(e) => {
+                    m.attempt += 1;
+                    m.errors_before += 1;
+                    m.last_error = Some(e.clone());
+                    let backoff = restart_backoff(m.attempt);
+                    m.next_restart = Some(Instant::now() + backoff);
+                    log::error!(
+                        "❌ {} watcher 재시작 실패: {} ({}초 후 재시도)",
+                        name,
+                        e,
+                        backoff.as_secs()
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_backoff_caps() {
+        assert_eq!(restart_backoff(1), Duration::from_secs(1));
+        assert_eq!(restart_backoff(2), Duration::from_secs(2));
+        assert_eq!(restart_backoff(4), Duration::from_secs(8));
+        assert_eq!(restart_backoff(7), Duration::from_secs(MAX_BACKOFF_SECS));
+        assert_eq!(restart_backoff(100), Duration::from_secs(MAX_BACKOFF_SECS));
+
+        // 안정적으로 동작하면 backoff 초기화
+        assert_eq!(settled_attempt(3, Duration::from_secs(10)), 3);
+        assert_eq!(
+            settled_attempt(3, Duration::from_secs(STABLE_RESET_SECS)),
+            0
+        );
+    }
+
+    #[test]
+    fn test_restart_policy() {
+        // 일시적 에러는 재시작 없이 degraded, 누적되거나 치명적이면 재시작
+        assert_eq!(assess(false, 0), Verdict::Healthy);
+        assert_eq!(assess(false, 1), Verdict::Degraded);
+        assert_eq!(
+            assess(false, RESTART_ERROR_THRESHOLD - 1),
+            Verdict::Degraded
+        );
+        assert_eq!(assess(false, RESTART_ERROR_THRESHOLD), Verdict::Restart);
+        assert_eq!(assess(true, 0), Verdict::Restart);
+    }
+
+    #[test]
+    fn test_healthy_degraded_transitions() {
+        use WatchStatus::*;
+        assert_eq!(next_status(Running, Verdict::Degraded), Degraded);
+        assert_eq!(next_status(Degraded, Verdict::Degraded), Degraded);
+        // 에러가 구간 밖으로 밀려나면 복귀
+        assert_eq!(next_status(Degraded, Verdict::Healthy), Running);
+        assert_eq!(next_status(Running, Verdict::Healthy), Running);
+        assert_eq!(next_status(Degraded, Verdict::Restart), Restarting);
+        // 일시 정지 중에는 판정과 무관
+        assert_eq!(next_status(Paused, Verdict::Restart), Paused);
+        assert_eq!(next_status(Paused, Verdict::Healthy), Paused);
+    }
+}
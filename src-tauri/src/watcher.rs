@@ -3,27 +3,34 @@
 // notify crate 기반 FSEvents 네이티브 파일 감시
 // ===========================================
 
-use crate::contract::ContractEnforcer;
-use crate::session::{read_session, update_session_stats};
+use crate::budget::{BudgetTracker, BudgetUsage};
+use crate::command_log::CommandLogTail;
+use crate::commit_detector::{CommitDetector, CommitInfo};
+use crate::contract::{ContractEnforcer, Violation};
+use crate::event_pipeline::{FileChange, QueuedEvent};
+use crate::git_discovery::GitRepo;
+use crate::ignore_rules::IgnoreMatcher;
+use crate::session::{
+    is_session_file, list_sessions, load_session_file, Budget, ExecutionContract, RiskTier, Session, SessionListing,
+    Severity, StatsWriter,
+};
 use crate::sync_client::SyncClient;
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use tauri::Emitter;
 
-/// 무시할 디렉토리 패턴
-const IGNORED_DIRS: &[&str] = &[
-    "node_modules",
-    ".git",
-    ".orchestrator",
-    "dist",
-    "build",
-    ".next",
-    "target",
-    ".tauri",
-];
+/// 동일 파일 재발생 무시 구간
+const DEBOUNCE_WINDOW_MS: u64 = 1000;
+/// 디바운스 캐시 최대 항목 수 (대형 monorepo 대량 변경 시 메모리 상한)
+const DEBOUNCE_CAPACITY: usize = 4096;
+/// events/sec 계산 구간 (초)
+const RATE_WINDOW_SECS: usize = 10;
+/// notify 에러 집계 구간 (supervisor의 재시작 판단용)
+pub const ERROR_WINDOW: Duration = Duration::from_secs(60);
 
 /// 파일 변경 이벤트 (프론트엔드로 전송)
 #[derive(Debug, Clone, serde::Serialize)]
@@ -31,29 +38,599 @@ pub struct FileChangeEvent {
     pub path: String,
     pub event_type: String, // "change" | "add" | "unlink"
     pub violation: Option<String>,
+    pub severity: Option<Severity>,
+    /// 변경이 귀속된 세션 (소유 세션이 없으면 None)
+    pub session_id: Option<String>,
+    /// 같은 경로를 주장하는 세션이 여럿이면 전체 목록
+    pub overlap: Vec<String>,
 }
 
-/// Watcher 상태
+/// 에이전트 명령 실행 이벤트 (프론트엔드로 전송)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CommandExecEvent {
+    pub command: String,
+    pub cwd: Option<String>,
+    pub violation: Option<String>,
+    pub severity: Option<Severity>,
+}
+
+/// 세션 변경 이벤트 (세션 파일 추가/변경/종료 → 프론트엔드로 전송)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionChangedEvent {
+    pub session_id: String,
+    pub agent_type: String,
+    pub task_name: String,
+    pub has_contract: bool,
+    pub risk_tier: Option<RiskTier>,
+    /// false = 세션 종료 (세션 파일 삭제)
+    pub active: bool,
+}
+
+/// 같은 경로를 여러 세션이 주장 (프론트엔드로 전송)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SessionOverlapEvent {
+    pub path: String,
+    pub session_ids: Vec<String>,
+}
+
+/// 집행에 영향을 주는 세션 필드 (통계 카운터 변경은 무시)
+#[derive(Debug, Clone, PartialEq)]
+struct SessionKey {
+    session_id: String,
+    agent_type: String,
+    task_name: String,
+    contract: Option<ExecutionContract>,
+    budget: Budget,
+}
+
+/// 세션별 통계/예산 — 같은 세션이면 hot-reload 사이에도 유지
+struct SessionCounters {
+    files_changed: AtomicU64,
+    commits_detected: AtomicU64,
+    budget: Mutex<BudgetTracker>,
+}
+
+impl SessionCounters {
+    fn new(session: &Session) -> Self {
+        SessionCounters {
+            files_changed: AtomicU64::new(session.files_changed),
+            commits_detected: AtomicU64::new(session.commits_detected),
+            budget: Mutex::new(BudgetTracker::new(session.effective_budget())),
+        }
+    }
+}
+
+/// 세션 파일에서 파생된 집행 상태 — 파일이 바뀌면 통째로 교체
+struct LiveSession {
+    /// session.json 또는 sessions/<id>.json
+    source: PathBuf,
+    key: SessionKey,
+    enforcer: Arc<ContractEnforcer>,
+    /// 세션 시작 시각 (started_at 없으면 로드 시각 기준)
+    started: chrono::DateTime<chrono::Utc>,
+    counters: Arc<SessionCounters>,
+}
+
+impl SessionKey {
+    fn from_session(session: &Session) -> Self {
+        SessionKey {
+            session_id: session.session_id.clone(),
+            agent_type: session.agent_type.clone(),
+            task_name: session.task_name.clone(),
+            contract: session.execution_contract.clone(),
+            budget: session.effective_budget(),
+        }
+    }
+}
+
+impl LiveSession {
+    fn from_session(source: PathBuf, session: &Session, counters: Arc<SessionCounters>) -> Self {
+        LiveSession {
+            source,
+            key: SessionKey::from_session(session),
+            enforcer: Arc::new(
+                session
+                    .execution_contract
+                    .clone()
+                    .map(ContractEnforcer::new)
+                    .unwrap_or_else(ContractEnforcer::unrestricted),
+            ),
+            started: session.started_at_utc().unwrap_or_else(chrono::Utc::now),
+            counters,
+        }
+    }
+
+    fn session_id(&self) -> &str {
+        &self.key.session_id
+    }
+
+    fn changed_event(&self, active: bool) -> SessionChangedEvent {
+        SessionChangedEvent {
+            session_id: self.key.session_id.clone(),
+            agent_type: self.key.agent_type.clone(),
+            task_name: self.key.task_name.clone(),
+            has_contract: self.enforcer.has_contract(),
+            risk_tier: self.key.contract.as_ref().map(|c| c.risk_tier),
+            active,
+        }
+    }
+
+    fn usage(&self, tokens: Option<u64>) -> BudgetUsage {
+        BudgetUsage {
+            minutes: elapsed_minutes(&self.started),
+            files: self.counters.files_changed.load(Ordering::SeqCst),
+            commits: self.counters.commits_detected.load(Ordering::SeqCst),
+            tokens,
+        }
+    }
+}
+
+/// 경로 귀속 결과
+#[derive(Debug, Default, PartialEq)]
+struct Attribution {
+    /// 변경을 소유하는 세션 (SessionSet 인덱스)
+    owner: Option<usize>,
+    /// 같은 경로를 주장하는 세션이 둘 이상이면 전체 목록
+    overlap: Vec<String>,
+}
+
+/// 작업 트리의 활성 세션 목록 (시작 시각 순) — 세션 파일이 바뀌면 통째로 교체
+#[derive(Default)]
+struct SessionSet {
+    sessions: Vec<Arc<LiveSession>>,
+}
+
+impl SessionSet {
+    fn get(&self, session_id: &str) -> Option<&Arc<LiveSession>> {
+        self.sessions.iter().find(|s| s.session_id() == session_id)
+    }
+
+    /// 같은 파일의 세션이 집행 필드까지 그대로인지 (통계만 바뀐 쓰기)
+    fn is_current(&self, source: &Path, session: &Session) -> bool {
+        let key = SessionKey::from_session(session);
+        self.sessions.iter().any(|s| s.source == source && s.key == key)
+    }
+
+    /// 경로를 소유하는 세션 찾기
+    /// 1) 범위(allowed_paths)가 경로를 포함하는 세션 중 가장 구체적인 패턴의 세션
+    /// 2) 없으면 범위 미지정 세션 (여럿이면 먼저 시작한 세션)
+    /// 3) 세션이 하나뿐이면 범위 밖 변경도 그 세션 (계약 위반으로 처리)
+    fn attribute(&self, relative_path: &str) -> Attribution {
+        let scoped: Vec<(usize, usize)> = self
+            .sessions
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.enforcer.scope_match(relative_path).map(|m| (i, m)))
+            .collect();
+        let claimants: Vec<usize> = if scoped.is_empty() {
+            (0..self.sessions.len())
+                .filter(|&i| !self.sessions[i].enforcer.is_scoped())
+                .collect()
+        } else {
+            scoped.iter().map(|(i, _)| *i).collect()
+        };
+        // 구체성이 같으면 먼저 시작한 세션
+        let owner = scoped
+            .iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+            .map(|(i, _)| *i)
+            .or_else(|| claimants.first().copied())
+            .or(if self.sessions.len() == 1 { Some(0) } else { None });
+        let overlap = if claimants.len() > 1 {
+            claimants
+                .iter()
+                .map(|&i| self.sessions[i].session_id().to_string())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Attribution { owner, overlap }
+    }
+
+    /// 소유 세션의 계약으로 검사 — 소유 세션이 없으면 모든 세션 중 가장 심각한 위반
+    fn check(
+        &self,
+        owner: Option<usize>,
+        check: impl Fn(&ContractEnforcer) -> Option<Violation>,
+    ) -> Option<Violation> {
+        match owner {
+            Some(i) => check(&self.sessions[i].enforcer),
+            None => self
+                .sessions
+                .iter()
+                .filter_map(|s| check(&s.enforcer))
+                .max_by_key(|v| v.severity),
+        }
+    }
+}
+
+/// 세션 hot-reload에 필요한 watcher 상태 묶음
+struct SessionReload {
+    project_path: PathBuf,
+    live: Arc<RwLock<Arc<SessionSet>>>,
+    stats: StatsWriter,
+    health: Arc<WatcherHealth>,
+}
+
+impl SessionReload {
+    fn current(&self) -> Arc<SessionSet> {
+        match self.live.read() {
+            Ok(l) => l.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// 세션 파일 이벤트 — 통계만 바뀐 쓰기(StatsWriter 주기 기록 등)면 전체 재로드 생략
+    fn on_session_file(&self, app: &tauri::AppHandle, path: &Path) {
+        if let Ok(Some(session)) = load_session_file(path) {
+            if self.current().is_current(path, &session) {
+                return;
+            }
+        }
+        self.reload(app);
+    }
+
+    /// 세션 파일 변경 → 세션 목록 교체 + 세션별 orchx:session-changed
+    /// 손상된 파일은 그 파일의 이전 세션(계약)을 유지한 채 에러만 보고
+    fn reload(&self, app: &tauri::AppHandle) {
+        let listing = list_sessions(&self.project_path);
+        for (_, e) in &listing.errors {
+            report_session_error(app, &self.health, &self.project_path, e.clone());
+        }
+        let previous = self.current();
+        let (next, events) = next_session_set(&previous, listing);
+        if events.is_empty() {
+            return;
+        }
+
+        if let Ok(mut live) = self.live.write() {
+            *live = Arc::new(next);
+        }
+        for event in events {
+            log::info!(
+                "🔄 세션 {}: {} / {} (계약 {})",
+                if event.active { "변경" } else { "종료" },
+                event.agent_type,
+                event.task_name,
+                if event.has_contract { "적용" } else { "없음" }
+            );
+            if let Err(e) = app.emit("orchx:session-changed", &event) {
+                log::warn!("  ❌ Tauri emit 실패: {}", e);
+            }
+        }
+    }
+}
+
+/// 새 세션 목록 계산 → (목록, 추가/변경/종료 이벤트)
+/// 같은 세션은 통계/예산 카운터를 이어받고, 예산이 바뀌면 예산 알림만 초기화
+fn next_session_set(
+    previous: &SessionSet,
+    listing: SessionListing,
+) -> (SessionSet, Vec<SessionChangedEvent>) {
+    let mut sessions: Vec<Arc<LiveSession>> = Vec::new();
+    let mut events = Vec::new();
+    for (source, session) in listing.sessions {
+        let prev = previous.get(&session.session_id);
+        let counters = prev
+            .map(|p| p.counters.clone())
+            .unwrap_or_else(|| Arc::new(SessionCounters::new(&session)));
+        let next = LiveSession::from_session(source, &session, counters);
+        match prev {
+            Some(p) if p.key == next.key && p.source == next.source => {
+                sessions.push(p.clone());
+                continue;
+            }
+            Some(p) if p.key.budget != next.key.budget => {
+                if let Ok(mut tracker) = next.counters.budget.lock() {
+                    *tracker = BudgetTracker::new(next.key.budget.clone());
+                }
+            }
+            _ => {}
+        }
+        events.push(next.changed_event(true));
+        sessions.push(Arc::new(next));
+    }
+    // 손상된 파일 → 이전 세션 유지
+    for (path, _) in &listing.errors {
+        for p in previous.sessions.iter().filter(|p| p.source == *path) {
+            if !sessions.iter().any(|s| s.session_id() == p.session_id()) {
+                sessions.push(p.clone());
+            }
+        }
+    }
+    sessions.sort_by_key(|s| s.started);
+    for p in &previous.sessions {
+        if !sessions.iter().any(|s| s.session_id() == p.session_id()) {
+            events.push(p.changed_event(false));
+        }
+    }
+    (SessionSet { sessions }, events)
+}
+
+/// 경로별 디바운스 캐시 — 크기 상한 + 만료 항목 정리
+pub struct DebounceCache {
+    window: Duration,
+    capacity: usize,
+    entries: HashMap<String, Instant>,
+}
+
+impl DebounceCache {
+    pub fn new(window: Duration, capacity: usize) -> Self {
+        Self {
+            window,
+            capacity,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// 윈도우 이내 동일 경로 재발생이면 false
+    pub fn should_emit(&mut self, key: &str, now: Instant) -> bool {
+        if let Some(last) = self.entries.get(key) {
+            if now.duration_since(*last) < self.window {
+                return false;
+            }
+        } else if self.entries.len() >= self.capacity {
+            self.prune(now);
+            if self.entries.len() >= self.capacity {
+                // 모두 윈도우 이내 → 가장 오래된 항목 제거
+                let oldest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, t)| **t)
+                    .map(|(k, _)| k.clone());
+                if let Some(k) = oldest {
+                    self.entries.remove(&k);
+                }
+            }
+        }
+        self.entries.insert(key.to_string(), now);
+        true
+    }
+
+    /// 윈도우가 지난 항목 제거
+    pub fn prune(&mut self, now: Instant) {
+        let window = self.window;
+        self.entries
+            .retain(|_, t| now.duration_since(*t) < window);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// 초 단위 버킷 기반 events/sec 측정
+struct RateMeter {
+    buckets: [u64; RATE_WINDOW_SECS],
+    last_sec: u64,
+}
+
+impl RateMeter {
+    fn new() -> Self {
+        Self {
+            buckets: [0; RATE_WINDOW_SECS],
+            last_sec: 0,
+        }
+    }
+
+    /// 지나간 초의 버킷 비우기
+    fn advance(&mut self, sec: u64) {
+        if sec <= self.last_sec {
+            return;
+        }
+        let elapsed = (sec - self.last_sec).min(RATE_WINDOW_SECS as u64);
+        for i in 1..=elapsed {
+            self.buckets[((self.last_sec + i) % RATE_WINDOW_SECS as u64) as usize] = 0;
+        }
+        self.last_sec = sec;
+    }
+
+    fn record(&mut self, sec: u64) {
+        self.advance(sec);
+        self.buckets[(sec % RATE_WINDOW_SECS as u64) as usize] += 1;
+    }
+
+    fn per_sec(&mut self, sec: u64) -> f64 {
+        self.advance(sec);
+        self.buckets.iter().sum::<u64>() as f64 / RATE_WINDOW_SECS as f64
+    }
+}
+
+/// 구간(ERROR_WINDOW) 안의 에러 시각
+struct ErrorWindow {
+    times: VecDeque<Instant>,
+}
+
+impl ErrorWindow {
+    fn new() -> Self {
+        Self {
+            times: VecDeque::new(),
+        }
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some(first) = self.times.front() {
+            if now.duration_since(*first) < ERROR_WINDOW {
+                break;
+            }
+            self.times.pop_front();
+        }
+    }
+
+    fn record(&mut self, now: Instant) {
+        self.prune(now);
+        self.times.push_back(now);
+    }
+
+    fn count(&mut self, now: Instant) -> usize {
+        self.prune(now);
+        self.times.len()
+    }
+}
+
+/// 감시 대상 소실 / 감시 한도 초과 — 재시작 없이는 복구 불가
+fn is_fatal_watch_error(error: &notify::Error) -> bool {
+    matches!(
+        error.kind,
+        notify::ErrorKind::PathNotFound
+            | notify::ErrorKind::WatchNotFound
+            | notify::ErrorKind::MaxFilesWatch
+    )
+}
+
+fn unix_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Watcher 상태 지표 (get_watcher_health)
+pub struct WatcherHealth {
+    events_total: AtomicU64,
+    errors_total: AtomicU64,
+    last_event_at: Mutex<Option<String>>,
+    last_error: Mutex<Option<String>>,
+    rate: Mutex<RateMeter>,
+    /// 최근 notify 에러 (누적되면 supervisor가 재시작)
+    watch_errors: Mutex<ErrorWindow>,
+    /// 치명적 notify 에러 발생 → supervisor가 즉시 재시작
+    fatal: AtomicBool,
+}
+
+impl WatcherHealth {
+    fn new() -> Self {
+        Self {
+            events_total: AtomicU64::new(0),
+            errors_total: AtomicU64::new(0),
+            last_event_at: Mutex::new(None),
+            last_error: Mutex::new(None),
+            rate: Mutex::new(RateMeter::new()),
+            watch_errors: Mutex::new(ErrorWindow::new()),
+            fatal: AtomicBool::new(false),
+        }
+    }
+
+    fn record_event(&self) {
+        self.events_total.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut rate) = self.rate.lock() {
+            rate.record(unix_secs());
+        }
+        if let Ok(mut last) = self.last_event_at.lock() {
+            *last = Some(chrono::Utc::now().to_rfc3339());
+        }
+    }
+
+    /// 에러 집계만 (세션 파일 오류 등 — 재시작 판단과 무관)
+    fn record_error(&self, error: String) {
+        self.errors_total.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut last) = self.last_error.lock() {
+            *last = Some(error);
+        }
+    }
+
+    fn record_watch_error(&self, error: &notify::Error, now: Instant) {
+        self.record_error(error.to_string());
+        if let Ok(mut window) = self.watch_errors.lock() {
+            window.record(now);
+        }
+        if is_fatal_watch_error(error) {
+            self.fatal.store(true, Ordering::SeqCst);
+        }
+    }
+
+    pub fn is_fatal(&self) -> bool {
+        self.fatal.load(Ordering::SeqCst)
+    }
+
+    /// ERROR_WINDOW 안의 notify 에러 수
+    pub fn recent_watch_errors(&self, now: Instant) -> usize {
+        self.watch_errors
+            .lock()
+            .map(|mut w| w.count(now))
+            .unwrap_or(0)
+    }
+
+    pub fn events_total(&self) -> u64 {
+        self.events_total.load(Ordering::Relaxed)
+    }
+
+    pub fn errors_total(&self) -> u64 {
+        self.errors_total.load(Ordering::Relaxed)
+    }
+
+    pub fn events_per_sec(&self) -> f64 {
+        self.rate
+            .lock()
+            .map(|mut r| r.per_sec(unix_secs()))
+            .unwrap_or(0.0)
+    }
+
+    pub fn last_event_at(&self) -> Option<String> {
+        self.last_event_at.lock().ok().and_then(|l| l.clone())
+    }
+
+    pub fn last_error(&self) -> Option<String> {
+        self.last_error.lock().ok().and_then(|l| l.clone())
+    }
+}
+
+/// Watcher 상태 — drop 시 notify watcher와 예산 스레드 모두 해제
 pub struct WatcherState {
-    _watcher: RecommendedWatcher,
-    pub running: Arc<AtomicBool>,
+    watcher: RecommendedWatcher,
+    project_path: PathBuf,
+    /// 작업 트리 밖 git 디렉토리 감시 (linked worktree / 서브모듈)
+    git_watches: Vec<(PathBuf, RecursiveMode)>,
+    running: Arc<AtomicBool>,
+    paused: Arc<AtomicBool>,
+    debounce: Arc<Mutex<DebounceCache>>,
+    pub health: Arc<WatcherHealth>,
 }
 
-/// 경로가 무시 대상인지 체크
-fn is_ignored(path: &Path, project_root: &Path) -> bool {
-    let relative = path
-        .strip_prefix(project_root)
-        .unwrap_or(path);
+impl WatcherState {
+    /// 일시 정지: OS 감시 해제 (세션/예산 상태는 유지)
+    pub fn pause(&mut self) -> Result<(), String> {
+        if self.paused.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.watcher
+            .unwatch(&self.project_path)
+            .map_err(|e| format!("감시 해제 실패: {}", e))?;
+        for (path, _) in &self.git_watches {
+            let _ = self.watcher.unwatch(path);
+        }
+        log::info!("⏸ Watcher paused: {}", self.project_path.display());
+        Ok(())
+    }
 
-    for component in relative.components() {
-        if let std::path::Component::Normal(name) = component {
-            let name_str = name.to_string_lossy();
-            if IGNORED_DIRS.iter().any(|d| *d == name_str.as_ref()) {
-                return true;
+    /// 재개: OS 감시 재등록
+    pub fn resume(&mut self) -> Result<(), String> {
+        if !self.paused.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.watcher
+            .watch(&self.project_path, RecursiveMode::Recursive)
+            .map_err(|e| format!("감시 재개 실패: {}", e))?;
+        for (path, mode) in &self.git_watches {
+            if let Err(e) = self.watcher.watch(path, *mode) {
+                log::warn!("  ⚠ git 디렉토리 감시 실패 ({}): {}", path.display(), e);
             }
         }
+        self.paused.store(false, Ordering::SeqCst);
+        log::info!("▶ Watcher resumed: {}", self.project_path.display());
+        Ok(())
+    }
+
+    /// 만료된 디바운스 항목 정리
+    pub fn prune_debounce(&self) {
+        if let Ok(mut cache) = self.debounce.lock() {
+            cache.prune(Instant::now());
+        }
+    }
+
+    pub fn debounce_entries(&self) -> usize {
+        self.debounce.lock().map(|c| c.len()).unwrap_or(0)
     }
-    false
 }
 
 /// 이벤트 종류를 문자열로 변환
@@ -66,49 +643,247 @@ fn event_kind_to_str(kind: &EventKind) -> Option<&'static str> {
     }
 }
 
+/// 명령 로그에 새로 기록된 명령을 계약 집행기로 검사
+/// (실행 디렉토리가 속한 세션의 계약, 귀속 세션이 없으면 모든 세션 계약)
+fn handle_command_log(
+    tail: &Mutex<CommandLogTail>,
+    sessions: &SessionSet,
+    project_root: &Path,
+    watch_key: &str,
+    app: &tauri::AppHandle,
+    sync_client: Option<&Arc<SyncClient>>,
+) {
+    let records = match tail.lock() {
+        Ok(mut t) => t.read_new(),
+        Err(_) => return,
+    };
+
+    for record in records {
+        let owner = record
+            .cwd
+            .as_deref()
+            .and_then(|cwd| Path::new(cwd).strip_prefix(project_root).ok())
+            .and_then(|relative| sessions.attribute(&relative.to_string_lossy()).owner);
+        let violation = sessions.check(owner, |e| e.check_command(&record.command));
+        if let Some(ref v) = violation {
+            log::warn!("🚨 명령 계약 위반: {}", v.reason);
+        } else {
+            log::debug!("⌨ 명령 실행: {}", record.command);
+        }
+
+        let exec_event = CommandExecEvent {
+            command: record.command.clone(),
+            cwd: record.cwd.clone(),
+            violation: violation.as_ref().map(|v| v.reason.clone()),
+            severity: violation.as_ref().map(|v| v.severity),
+        };
+        if let Err(e) = app.emit("orchx:command-executed", &exec_event) {
+            log::warn!("  ❌ Tauri emit 실패: {}", e);
+        }
+
+        if let Some(client) = sync_client {
+            let client = client.clone();
+            let watch_key = watch_key.to_string();
+            let session_id = owner.map(|i| sessions.sessions[i].session_id().to_string());
+            tauri::async_runtime::spawn(async move {
+                let (event_type, payload) = match violation {
+                    Some(v) => (
+                        "contract.violation",
+                        serde_json::json!({
+                            "kind": v.kind,
+                            "command": record.command,
+                            "target": v.target,
+                            "reason": v.reason,
+                            "severity": v.severity,
+                        }),
+                    ),
+                    None => (
+                        "command.executed",
+                        serde_json::json!({
+                            "command": record.command,
+                            "cwd": record.cwd,
+                        }),
+                    ),
+                };
+                let event = QueuedEvent::for_watch(&watch_key, event_type, payload, session_id);
+                if let Err(e) = client.send_event(event).await {
+                    log::warn!("  ❌ Supabase 전송 실패: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// 감지된 커밋 → orchx:commit-detected emit + commit.created 전송
+/// (귀속 세션은 Session 트레일러 기준)
+fn handle_commit(
+    commit: CommitInfo,
+    watch_key: &str,
+    app: &tauri::AppHandle,
+    sync_client: Option<&Arc<SyncClient>>,
+) {
+    log::info!(
+        "⚡ 커밋 감지: {} {} ({})",
+        &commit.sha[..commit.sha.len().min(8)],
+        commit.message.lines().next().unwrap_or(""),
+        commit.branch.as_deref().unwrap_or("detached")
+    );
+    if let Err(e) = app.emit("orchx:commit-detected", &commit) {
+        log::warn!("  ❌ Tauri emit 실패: {}", e);
+    }
+
+    if let Some(client) = sync_client {
+        let client = client.clone();
+        let watch_key = watch_key.to_string();
+        tauri::async_runtime::spawn(async move {
+            let payload = serde_json::to_value(&commit).unwrap_or_default();
+            let event = QueuedEvent::for_watch(&watch_key, "commit.created", payload, commit.session_id);
+            if let Err(e) = client.send_event(event).await {
+                log::warn!("  ❌ Supabase 전송 실패: {}", e);
+            }
+        });
+    }
+}
+
+/// 예산 시간 체크 주기 (파일 변경이 없어도 경과 시간은 증가)
+const BUDGET_TICK_SECS: u64 = 30;
+
+/// 세션 예산 사용량 체크 → 임계치 알림 emit + cli_events 전송
+fn check_budget(
+    session: &LiveSession,
+    tokens: Option<u64>,
+    watch_key: &str,
+    app: &tauri::AppHandle,
+    sync_client: Option<&Arc<SyncClient>>,
+) {
+    let usage = session.usage(tokens);
+    let alerts = match session.counters.budget.lock() {
+        Ok(mut t) => t.check(&usage),
+        Err(_) => return,
+    };
+
+    for mut alert in alerts {
+        alert.session_id = Some(session.session_id().to_string());
+        log::warn!(
+            "💸 예산 {:?} ({:?}): {}/{} [{}]",
+            alert.level,
+            alert.metric,
+            alert.used,
+            alert.limit,
+            session.key.agent_type
+        );
+        if let Err(e) = app.emit(alert.level.tauri_event(), &alert) {
+            log::warn!("  ❌ Tauri emit 실패: {}", e);
+        }
+
+        if let Some(client) = sync_client {
+            let client = client.clone();
+            let watch_key = watch_key.to_string();
+            tauri::async_runtime::spawn(async move {
+                let payload = serde_json::to_value(&alert).unwrap_or_default();
+                let event = QueuedEvent::for_watch(
+                    &watch_key,
+                    alert.level.cli_event(),
+                    payload,
+                    alert.session_id.clone(),
+                );
+                if let Err(e) = client.send_event(event).await {
+                    log::warn!("  ❌ Supabase 전송 실패: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// 세션 시작 이후 경과 분
+fn elapsed_minutes(started: &chrono::DateTime<chrono::Utc>) -> u64 {
+    (chrono::Utc::now() - *started).num_minutes().max(0) as u64
+}
+
 /// 프로젝트 디렉토리에 대한 파일 감시 시작
 pub fn start_watcher(
+    watch_key: &str,
     project_path: PathBuf,
     app_handle: tauri::AppHandle,
     sync_client: Option<Arc<SyncClient>>,
 ) -> Result<WatcherState, String> {
     let running = Arc::new(AtomicBool::new(true));
-    let files_changed = Arc::new(AtomicU64::new(0));
-    let commits_detected = Arc::new(AtomicU64::new(0));
-
-    // 세션에서 계약 정보 로드
-    let session = read_session(&project_path);
-    let enforcer = Arc::new(
-        session
-            .as_ref()
-            .and_then(|s| s.execution_contract.clone())
-            .map(ContractEnforcer::new)
-            .unwrap_or_else(|| {
-                ContractEnforcer::new(crate::session::ExecutionContract::default())
-            }),
-    );
+    let paused = Arc::new(AtomicBool::new(false));
+    let health = Arc::new(WatcherHealth::new());
 
-    // 기존 세션 통계 로드
-    if let Some(ref s) = session {
-        files_changed.store(s.files_changed, Ordering::SeqCst);
-        commits_detected.store(s.commits_detected, Ordering::SeqCst);
+    // 세션 레지스트리에서 세션별 계약/통계/예산 로드
+    // (손상된 세션 파일은 "세션 없음"과 구분해 알림)
+    // 실행 중 세션 파일이 바뀌면 SessionReload가 목록을 통째로 교체
+    let listing = list_sessions(&project_path);
+    for (_, e) in &listing.errors {
+        report_session_error(&app_handle, &health, &project_path, e.clone());
     }
+    let (sessions, _) = next_session_set(&SessionSet::default(), listing);
+
+    // 디바운스 캐시 (경로 -> 마지막 이벤트 시간)
+    let debounce = Arc::new(Mutex::new(DebounceCache::new(
+        Duration::from_millis(DEBOUNCE_WINDOW_MS),
+        DEBOUNCE_CAPACITY,
+    )));
+
+    // 에이전트 명령 로그 (시작 시점 이후 기록만 검사)
+    let command_tail = Arc::new(Mutex::new(CommandLogTail::new(&project_path)));
+    let command_log_path = crate::command_log::command_log_path(&project_path);
 
-    // 디바운스용 버퍼 (경로 -> 마지막 이벤트 시간)
-    let debounce_map: Arc<Mutex<HashMap<String, std::time::Instant>>> = 
-        Arc::new(Mutex::new(HashMap::new()));
+    // 브랜치 tip 기준선 (시작 이전 커밋은 다시 세지 않음)
+    let commit_detector = Arc::new(Mutex::new(CommitDetector::new(&project_path)));
+    // worktree/서브모듈은 `gitdir:`이 가리키는 작업 트리 밖 git 디렉토리
+    let git_repo = GitRepo::discover(&project_path);
+    let git_watches: Vec<(PathBuf, RecursiveMode)> = git_repo
+        .as_ref()
+        .map(|g| g.external_ref_watches())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(path, recursive)| {
+            let mode = if recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+            (path, mode)
+        })
+        .collect();
+
+    // .gitignore / .orchestrator/ignore 기반 제외 규칙
+    let ignore_matcher = Arc::new(IgnoreMatcher::new(&project_path));
+
+    // 세션 통계는 세션별로 병합해서 최대 1초에 한 번 기록 (이벤트마다 쓰지 않음)
+    let stats_writer = {
+        let app = app_handle.clone();
+        let health = health.clone();
+        let path = project_path.clone();
+        StatsWriter::spawn(project_path.clone(), running.clone(), move |e| {
+            report_session_error(&app, &health, &path, e)
+        })
+    };
+    for s in &sessions.sessions {
+        log::info!(
+            "  Agent: {} | Task: {}{}",
+            s.key.agent_type,
+            s.key.task_name,
+            if s.enforcer.has_contract() { " | 🔒 계약 집행" } else { "" }
+        );
+    }
+    let session_state = Arc::new(SessionReload {
+        project_path: project_path.clone(),
+        live: Arc::new(RwLock::new(Arc::new(sessions))),
+        stats: stats_writer,
+        health: health.clone(),
+    });
 
     let project_root = project_path.clone();
-    let project_root_for_stats = project_path.clone();
     let app = app_handle.clone();
-    let enforcer_clone = enforcer.clone();
-    let files_changed_clone = files_changed.clone();
+    let session_clone = session_state.clone();
     let running_clone = running.clone();
+    let paused_clone = paused.clone();
+    let health_clone = health.clone();
     let sync_clone = sync_client.clone();
-    let debounce_map_clone = debounce_map.clone();
+    let debounce_clone = debounce.clone();
+    let watch_key_clone = watch_key.to_string();
 
     let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
-        if !running_clone.load(Ordering::SeqCst) {
+        if !running_clone.load(Ordering::SeqCst) || paused_clone.load(Ordering::SeqCst) {
             return;
         }
 
@@ -116,11 +891,11 @@ pub fn start_watcher(
             Ok(e) => e,
             Err(e) => {
                 log::warn!("🔴 Watcher 에러: {:?}", e);
+                health_clone.record_watch_error(&e, Instant::now());
                 return;
             }
         };
-
-
+        health_clone.record_event();
 
         let event_type = match event_kind_to_str(&event.kind) {
             Some(t) => t,
@@ -130,8 +905,62 @@ pub fn start_watcher(
         };
 
         for path in &event.paths {
+            // 세션 파일 교체 (orchx inject/session, 앱 세션 명령) → 계약 hot-reload
+            if is_session_file(&project_root, path) {
+                session_clone.on_session_file(&app, path);
+                continue;
+            }
+
+            // 명령 로그 변경 = 에이전트 명령 실행
+            if *path == command_log_path {
+                let sessions = session_clone.current();
+                handle_command_log(
+                    &command_tail,
+                    &sessions,
+                    &project_root,
+                    &watch_key_clone,
+                    &app,
+                    sync_clone.as_ref(),
+                );
+                continue;
+            }
+
+            // git 디렉토리 ref 변경 → 새 커밋 감지 (.git 자체는 항상 무시 대상)
+            if let Some(git_relative) = git_repo.as_ref().and_then(|g| g.git_relative(path)) {
+                if CommitDetector::is_ref_change(git_relative) {
+                    let commits = commit_detector
+                        .lock()
+                        .map(|mut d| d.detect())
+                        .unwrap_or_default();
+                    for commit in commits {
+                        // 감지기는 이 작업 트리의 브랜치만 보고 → 커밋 하나당 감시자 하나
+                        // Session 트레일러가 있으면 그 세션, 없으면 이 작업 트리의 모든 활성 세션
+                        let sessions = session_clone.current();
+                        let owners: Vec<&Arc<LiveSession>> =
+                            match commit.session_id.as_deref().and_then(|id| sessions.get(id)) {
+                                Some(owner) => vec![owner],
+                                None => sessions.sessions.iter().collect(),
+                            };
+                        handle_commit(commit, &watch_key_clone, &app, sync_clone.as_ref());
+                        for s in owners {
+                            let commits = s.counters.commits_detected.fetch_add(1, Ordering::SeqCst) + 1;
+                            session_clone.stats.record(
+                                s.session_id(),
+                                s.counters.files_changed.load(Ordering::SeqCst),
+                                commits,
+                            );
+                            check_budget(s, None, &watch_key_clone, &app, sync_clone.as_ref());
+                        }
+                    }
+                }
+                continue;
+            }
+
+            // 제외 규칙 파일 변경 → matcher 갱신 (파일 자체는 아래에서 정상 처리)
+            ignore_matcher.handle_rule_file_change(path);
+
             // 무시 대상 체크
-            if is_ignored(path, &project_root) {
+            if ignore_matcher.is_ignored(path, path.is_dir()) {
                 continue;
             }
 
@@ -141,49 +970,53 @@ pub fn start_watcher(
                 .to_string_lossy()
                 .to_string();
 
-            // 이중 체크 (정규식 기반)
-            if relative.starts_with(".orchestrator")
-                || relative.starts_with(".git/")
-                || relative.contains("node_modules")
-            {
+            // 디바운스 처리 (1초 이내 동일 파일 변경 무시)
+            let emit = debounce_clone
+                .lock()
+                .map(|mut cache| cache.should_emit(&relative, Instant::now()))
+                .unwrap_or(true);
+            if !emit {
                 continue;
             }
 
-            // .git/refs 변경 = 커밋 감지
-            if path.to_string_lossy().contains(".git/refs") {
-                let count = commits_detected.fetch_add(1, Ordering::SeqCst) + 1;
-                let _ = app.emit("orchx:commit-detected", count);
-                update_session_stats(
-                    &project_root_for_stats,
-                    files_changed_clone.load(Ordering::SeqCst),
-                    count,
+            // 경로 → 소유 세션 (범위가 겹치면 알림)
+            let sessions = session_clone.current();
+            let attribution = sessions.attribute(&relative);
+            let owner = attribution.owner.map(|i| &sessions.sessions[i]);
+            if !attribution.overlap.is_empty() {
+                log::warn!(
+                    "⚠ 세션 범위 겹침: {} ({})",
+                    relative,
+                    attribution.overlap.join(", ")
                 );
-                continue;
-            }
-
-            // 디바운스 처리 (1초 이내 동일 파일 변경 무시)
-            {
-                let mut map = debounce_map_clone.lock().unwrap();
-                let now = std::time::Instant::now();
-                if let Some(last_time) = map.get(&relative) {
-                    if now.duration_since(*last_time).as_millis() < 1000 {
-                        continue;
-                    }
+                let overlap_event = SessionOverlapEvent {
+                    path: relative.clone(),
+                    session_ids: attribution.overlap.clone(),
+                };
+                if let Err(e) = app.emit("orchx:session-overlap", &overlap_event) {
+                    log::warn!("  ❌ Tauri emit 실패: {}", e);
                 }
-                map.insert(relative.clone(), now);
             }
 
-            let count = files_changed_clone.fetch_add(1, Ordering::SeqCst) + 1;
-
             // 계약 위반 체크
-            let violation = enforcer_clone.check_path(&relative);
+            let violation = sessions.check(attribution.owner, |e| e.check_path(&relative));
             let violation_msg = violation.as_ref().map(|v| v.reason.clone());
+            if let Some(ref v) = violation {
+                match v.severity {
+                    Severity::Block => log::error!("🚨 계약 위반 (block): {}", v.reason),
+                    Severity::Warn => log::warn!("⚠ 계약 위반 (warn): {}", v.reason),
+                    Severity::Info => log::info!("ℹ 계약 규칙 (info): {}", v.reason),
+                }
+            }
 
             // 이벤트 발행
             let change_event = FileChangeEvent {
                 path: relative.clone(),
                 event_type: event_type.to_string(),
                 violation: violation_msg,
+                severity: violation.as_ref().map(|v| v.severity),
+                session_id: owner.map(|s| s.session_id().to_string()),
+                overlap: attribution.overlap,
             };
 
             log::debug!("📝 파일변경: {} ({})", change_event.path, change_event.event_type);
@@ -193,31 +1026,30 @@ pub fn start_watcher(
                 Err(e) => log::warn!("  ❌ Tauri emit 실패: {}", e),
             }
 
-            // Supabase cli_events에 이벤트 전송 (비동기)
+            // Supabase cli_events 배치 큐에 추가 (경로별 병합 후 bulk 전송)
             if let Some(ref client) = sync_clone {
-                let client = client.clone();
-                let rel = change_event.path.clone();
-                let viol = change_event.violation.clone();
-                tauri::async_runtime::spawn(async move {
-                    let payload = serde_json::json!({
-                        "file": rel,
-                        "event_type": "change",
-                        "violation": viol,
-                    });
-                    if let Err(e) = client.send_event("file.changed", payload).await {
-                        log::warn!("  ❌ Supabase 전송 실패: {}", e);
-                    }
+                client.queue_file_change(FileChange {
+                    watch_key: watch_key_clone.clone(),
+                    file: change_event.path.clone(),
+                    event_type: change_event.event_type.clone(),
+                    violation: change_event.violation.clone(),
+                    severity: change_event.severity,
+                    session_id: change_event.session_id.clone(),
                 });
             } else {
                 log::warn!("  ⚠ SyncClient 없음 → Supabase 전송 스킵");
             }
 
-            // 세션 업데이트
-            update_session_stats(
-                &project_root_for_stats,
-                count,
-                commits_detected.load(Ordering::SeqCst),
-            );
+            // 소유 세션 통계/예산 업데이트
+            if let Some(s) = owner {
+                let files = s.counters.files_changed.fetch_add(1, Ordering::SeqCst) + 1;
+                session_clone.stats.record(
+                    s.session_id(),
+                    files,
+                    s.counters.commits_detected.load(Ordering::SeqCst),
+                );
+                check_budget(s, None, &watch_key_clone, &app, sync_clone.as_ref());
+            }
         }
     })
     .map_err(|e| format!("Watcher 생성 실패: {}", e))?;
@@ -229,22 +1061,254 @@ pub fn start_watcher(
 
     log::info!("👁 Watching: {}", project_path.display());
 
-    if let Some(ref s) = session {
-        log::info!(
-            "  Agent: {} | Task: {}",
-            s.agent_type,
-            s.task_name
-        );
+    // 작업 트리 밖 git 디렉토리 (HEAD / refs) — 실패해도 파일 감시는 유지
+    for (path, mode) in &git_watches {
+        match watcher.watch(path, *mode) {
+            Ok(_) => log::info!("  🌿 git 디렉토리 감시: {}", path.display()),
+            Err(e) => log::warn!("  ⚠ git 디렉토리 감시 실패 ({}): {}", path.display(), e),
+        }
+    }
+
+    // 경과 시간/토큰 예산은 이벤트와 무관하게 세션별로 주기적으로 체크
+    // (세션/예산은 hot-reload로 바뀔 수 있어 매 주기 확인)
+    {
+        let running = running.clone();
+        let app = app_handle.clone();
+        let sync_client = sync_client.clone();
+        let session_state = session_state.clone();
+        let watch_key = watch_key.to_string();
+        std::thread::spawn(move || {
+            let mut ticks = 0u64;
+            while running.load(Ordering::SeqCst) {
+                std::thread::sleep(std::time::Duration::from_secs(1));
+                ticks += 1;
+                if ticks % BUDGET_TICK_SECS != 0 {
+                    continue;
+                }
+                let sessions = session_state.current();
+                let budgeted: Vec<&Arc<LiveSession>> = sessions
+                    .sessions
+                    .iter()
+                    .filter(|s| s.counters.budget.lock().map(|t| t.has_budget()).unwrap_or(false))
+                    .collect();
+                if budgeted.is_empty() {
+                    continue;
+                }
+                // 토큰 사용량은 에이전트가 자기 세션 파일에 보고
+                let tokens: HashMap<String, u64> = list_sessions(&session_state.project_path)
+                    .sessions
+                    .into_iter()
+                    .filter_map(|(_, s)| s.tokens_used.map(|t| (s.session_id, t)))
+                    .collect();
+                for s in budgeted {
+                    let tokens = tokens.get(s.session_id()).copied();
+                    check_budget(s, tokens, &watch_key, &app, sync_client.as_ref());
+                }
+            }
+        });
     }
 
     Ok(WatcherState {
-        _watcher: watcher,
+        watcher,
+        project_path,
+        git_watches,
         running,
+        paused,
+        debounce,
+        health,
     })
 }
 
-/// Watcher 중지
-pub fn stop_watcher(state: &WatcherState) {
+/// 세션 파일 읽기/쓰기 실패 → 로그 + watcher 상태 + orchx:session-error
+fn report_session_error(
+    app: &tauri::AppHandle,
+    health: &WatcherHealth,
+    project_path: &std::path::Path,
+    error: String,
+) {
+    log::error!("🚨 세션 파일 오류: {}", error);
+    health.record_error(error.clone());
+    let _ = app.emit(
+        "orchx:session-error",
+        serde_json::json!({
+            "project_path": project_path.to_string_lossy(),
+            "error": error,
+        }),
+    );
+}
+
+/// Watcher 중지 — 예산 스레드 종료 후 notify watcher drop
+pub fn stop_watcher(state: WatcherState) {
     state.running.store(false, Ordering::SeqCst);
-    log::info!("⏸ Watcher stopped");
+    log::info!("⏹ Watcher stopped: {}", state.project_path.display());
+    drop(state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debounce_cache_window_and_capacity() {
+        let mut cache = DebounceCache::new(Duration::from_millis(1000), 2);
+        let t0 = Instant::now();
+
+        assert!(cache.should_emit("a.ts", t0));
+        assert!(!cache.should_emit("a.ts", t0 + Duration::from_millis(500)));
+        assert!(cache.should_emit("a.ts", t0 + Duration::from_millis(1500)));
+
+        // 상한 도달 → 가장 오래된 항목 제거
+        assert!(cache.should_emit("b.ts", t0 + Duration::from_millis(1600)));
+        assert!(cache.should_emit("c.ts", t0 + Duration::from_millis(1700)));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.should_emit("a.ts", t0 + Duration::from_millis(1800)));
+
+        // 만료 항목 정리
+        cache.prune(t0 + Duration::from_millis(5000));
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_rate_meter_window() {
+        let mut meter = RateMeter::new();
+        for _ in 0..20 {
+            meter.record(100);
+        }
+        meter.record(101);
+        assert_eq!(meter.per_sec(101), 2.1);
+        // 윈도우 밖으로 밀려난 버킷은 제외
+        assert_eq!(meter.per_sec(110), 0.1);
+        assert_eq!(meter.per_sec(200), 0.0);
+    }
+
+    #[test]
+    fn test_watch_errors_window_and_fatal() {
+        let health = WatcherHealth::new();
+        let t0 = Instant::now();
+        health.record_watch_error(&notify::Error::generic("queue overflow"), t0);
+        health.record_watch_error(&notify::Error::generic("queue overflow"), t0);
+        assert!(!health.is_fatal());
+        // 세션 파일 오류는 재시작 판단에 포함하지 않음
+        health.record_error("세션 파일 손상".to_string());
+        assert_eq!(health.recent_watch_errors(t0 + Duration::from_secs(1)), 2);
+        // 구간 밖으로 밀려난 에러는 제외 (누적 수는 유지)
+        assert_eq!(health.recent_watch_errors(t0 + ERROR_WINDOW), 0);
+        assert_eq!(health.errors_total(), 3);
+
+        health.record_watch_error(&notify::Error::path_not_found(), t0);
+        assert!(health.is_fatal());
+    }
+
+    fn session(id: &str, started_at: &str, allowed: &[&str]) -> Session {
+        serde_json::from_value(serde_json::json!({
+            "session_id": id,
+            "agent_type": "cursor",
+            "task_name": "Refactor",
+            "started_at": started_at,
+            "execution_contract": if allowed.is_empty() {
+                serde_json::Value::Null
+            } else {
+                serde_json::json!({ "allowed_paths": allowed, "risk_tier": "high" })
+            },
+        }))
+        .unwrap()
+    }
+
+    fn listing(sessions: &[&Session]) -> SessionListing {
+        SessionListing {
+            sessions: sessions
+                .iter()
+                .map(|s| (PathBuf::from(format!("{}.json", s.session_id)), (*s).clone()))
+                .collect(),
+            errors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_session_set_reload_keeps_counters() {
+        let s1 = session("s1", "2026-03-01T09:00:00Z", &["src/**"]);
+        let (set, events) = next_session_set(&SessionSet::default(), listing(&[&s1]));
+        assert_eq!(events.len(), 1);
+        assert!(events[0].active);
+        assert_eq!(events[0].risk_tier, Some(RiskTier::High));
+        let live = set.get("s1").unwrap().clone();
+        assert!(live.enforcer.check_path("docs/readme.md").is_some());
+        assert_eq!(live.started.to_rfc3339(), "2026-03-01T09:00:00+00:00");
+        live.counters.files_changed.store(5, Ordering::SeqCst);
+
+        // 통계만 바뀐 쓰기 → 교체 불필요
+        let mut counted = s1.clone();
+        counted.files_changed = 12;
+        counted.tokens_used = Some(500);
+        assert!(set.is_current(Path::new("s1.json"), &counted));
+        let (set, events) = next_session_set(&set, listing(&[&counted]));
+        assert!(events.is_empty());
+        assert!(Arc::ptr_eq(set.get("s1").unwrap(), &live));
+
+        // 계약 교체 (orchx inject) + 새 세션 → 카운터는 세션별로 유지
+        let mut injected = s1.clone();
+        injected.execution_contract = Some(ExecutionContract {
+            allowed_paths: vec!["docs/**".to_string()],
+            ..Default::default()
+        });
+        assert!(!set.is_current(Path::new("s1.json"), &injected));
+        let s2 = session("s2", "2026-03-01T08:00:00Z", &[]);
+        assert!(!set.is_current(Path::new("s2.json"), &s2));
+        let (set, events) = next_session_set(&set, listing(&[&injected, &s2]));
+        assert_eq!(events.len(), 2);
+        let ids: Vec<_> = set.sessions.iter().map(|s| s.session_id()).collect();
+        assert_eq!(ids, ["s2", "s1"]);
+        let next = set.get("s1").unwrap();
+        assert!(next.enforcer.check_path("docs/readme.md").is_none());
+        assert_eq!(next.counters.files_changed.load(Ordering::SeqCst), 5);
+
+        // 손상된 파일 → 이전 세션 유지, 삭제된 파일 → 종료 이벤트
+        let mut broken = listing(&[]);
+        broken.errors.push((PathBuf::from("s1.json"), "세션 파일 손상".to_string()));
+        let (set, events) = next_session_set(&set, broken);
+        assert_eq!(set.sessions.len(), 1);
+        assert!(set.get("s1").is_some());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].session_id, "s2");
+        assert!(!events[0].active);
+    }
+
+    #[test]
+    fn test_attribution_by_scope() {
+        let ui = session("ui", "2026-03-01T09:00:00Z", &["src/ui/**"]);
+        let core = session("core", "2026-03-01T09:05:00Z", &["src/**"]);
+        let docs = session("docs", "2026-03-01T09:10:00Z", &["docs/**"]);
+        let (set, _) = next_session_set(&SessionSet::default(), listing(&[&ui, &core, &docs]));
+        let owner = |path: &str| {
+            let a = set.attribute(path);
+            (a.owner.map(|i| set.sessions[i].session_id()), a.overlap)
+        };
+
+        // 더 구체적인 범위가 소유, 겹침은 알림
+        assert_eq!(
+            owner("src/ui/app.tsx"),
+            (Some("ui"), vec!["ui".to_string(), "core".to_string()])
+        );
+        assert_eq!(owner("src/lib.rs"), (Some("core"), vec![]));
+        assert_eq!(owner("docs/guide.md"), (Some("docs"), vec![]));
+        // 어느 범위에도 없음 → 소유 없음, 모든 세션 계약으로 위반 검사
+        assert_eq!(owner("README.md"), (None, vec![]));
+        assert!(set.check(None, |e| e.check_path("README.md")).is_some());
+        assert!(set
+            .check(set.attribute("docs/guide.md").owner, |e| e.check_path("docs/guide.md"))
+            .is_none());
+
+        // 범위 미지정 세션은 범위 밖 경로를 소유, 여럿이면 먼저 시작한 세션 + 겹침
+        let free = session("free", "2026-03-01T10:00:00Z", &[]);
+        let other = session("other", "2026-03-01T11:00:00Z", &[]);
+        let (set, _) = next_session_set(&SessionSet::default(), listing(&[&docs, &free, &other]));
+        let a = set.attribute("README.md");
+        assert_eq!(a.owner.map(|i| set.sessions[i].session_id()), Some("free"));
+        assert_eq!(a.overlap, vec!["free".to_string(), "other".to_string()]);
+
+        // 단일 세션 → 범위 밖 변경도 그 세션 (위반)
+        let (set, _) = next_session_set(&SessionSet::default(), listing(&[&docs]));
+        assert_eq!(set.attribute("README.md").owner, Some(0));
+        assert!(SessionSet::default().attribute("README.md").owner.is_none());
+    }
 }
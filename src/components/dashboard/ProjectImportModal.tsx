@@ -18,6 +18,7 @@ import {
 import { useGitHub } from '../../hooks/useGitHub'
 import type { GitHubRepo } from '../../lib/github/githubApi'
 import type { CreateProjectInput } from '../../lib/supabase/projects'
+import { errorMessage } from '../../lib/tauri/dbError'
 
 // ─── Props ───
 interface ProjectImportModalProps {
@@ -72,7 +73,7 @@ export function ProjectImportModal({
             setSelected(null)
             setSearch('')
         } catch (e) {
-            setError(e instanceof Error ? e.message : '프로젝트 생성 실패')
+            setError(errorMessage(e, '프로젝트 생성 실패'))
         } finally {
             setImporting(false)
         }
@@ -16,6 +16,7 @@ import { Card, CardContent, CardHeader, CardTitle } from '../ui/card'
 import { Badge } from '../ui/badge'
 import { Button } from '../ui/button'
 import { useAuth } from '../../hooks/useAuth'
+import { errorMessage } from '../../lib/tauri/dbError'
 import type { EditorType, AIModel } from '../../types/index'
 import { TASK_TYPES } from '../../features/orchestration/taskTypes'
 import type { TaskType } from '../../features/orchestration/taskTypes'
@@ -402,7 +403,7 @@ export function OrchestrationView({ onNavigateToPlan }: { onNavigateToPlan?: ()
                 }, 1000)
             }
         } catch (err) {
-            setAiError(err instanceof Error ? err.message : '저장 실패')
+            setAiError(errorMessage(err, '저장 실패'))
         } finally {
             setSaving(false)
         }
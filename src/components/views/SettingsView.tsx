@@ -71,9 +71,11 @@ export function SettingsView() {
         loading,
         repos,
         tokenExpired,
+        credentialsLocked,
         connect,
         disconnect,
         reconnect,
+        unlockCredentials,
     } = useGitHub()
 
     const { scores, updateScore } = useModelScores()
@@ -83,6 +85,23 @@ export function SettingsView() {
     const [confirmDisconnect, setConfirmDisconnect] = useState(false)
     const [disconnecting, setDisconnecting] = useState(false)
     const [confirmResetScores, setConfirmResetScores] = useState(false)
+    const [passphrase, setPassphrase] = useState('')
+    const [unlocking, setUnlocking] = useState(false)
+    const [unlockError, setUnlockError] = useState<string | null>(null)
+
+    const handleUnlock = async () => {
+        if (!passphrase) return
+        setUnlocking(true)
+        setUnlockError(null)
+        try {
+            await unlockCredentials(passphrase)
+            setPassphrase('')
+        } catch (e) {
+            setUnlockError(typeof e === 'string' ? e : e instanceof Error ? e.message : '잠금 해제 실패')
+        } finally {
+            setUnlocking(false)
+        }
+    }
 
     const handleDisconnect = async () => {
         setDisconnecting(true)
@@ -160,6 +179,28 @@ export function SettingsView() {
                             <Loader2 className="w-4 h-4 animate-spin" />
                             연결 상태 확인 중...
                         </div>
+                    ) : credentialsLocked ? (
+                        <form
+                            className="space-y-2"
+                            onSubmit={(e) => { e.preventDefault(); void handleUnlock() }}
+                        >
+                            <p className="text-sm text-muted-foreground">
+                                OS 키링을 사용할 수 없어 GitHub 토큰을 암호화 파일에 보관합니다. 암호를 입력해 잠금을 해제하세요.
+                            </p>
+                            <div className="flex items-center gap-2">
+                                <input
+                                    type="password"
+                                    value={passphrase}
+                                    onChange={(e) => setPassphrase(e.target.value)}
+                                    placeholder="자격 증명 암호"
+                                    className="flex-1 h-8 rounded-md border border-border bg-background px-2 text-sm"
+                                />
+                                <Button type="submit" size="sm" disabled={unlocking || !passphrase}>
+                                    {unlocking ? <Loader2 className="w-3.5 h-3.5 animate-spin" /> : '잠금 해제'}
+                                </Button>
+                            </div>
+                            {unlockError && <p className="text-xs text-destructive">{unlockError}</p>}
+                        </form>
                     ) : isConnected ? (
                         <div className="space-y-4">
                             {tokenExpired && (
@@ -6,7 +6,7 @@
 import { useState, useEffect, useCallback } from 'react'
 import { supabase } from '../lib/supabase/client'
 import type { User, Session } from '@supabase/supabase-js'
-import { isTauri, openInExternalBrowser, startOAuthServer, onOAuthCallback } from '../lib/tauri/openExternal'
+import { isTauri, openInExternalBrowser, startOAuthLogin, onOAuthResult } from '../lib/tauri/openExternal'
 
 interface UseAuthReturn {
     user: User | null
@@ -44,41 +44,69 @@ export function useAuth(): UseAuthReturn {
         return () => subscription.unsubscribe()
     }, [])
 
-    const signInWithGitHub = useCallback(async () => {
-        if (isTauri()) {
-            // ─── Tauri Desktop: PKCE + 로컬 콜백 서버 ───
-            // 1. Rust에서 로컬 콜백 서버 시작 → callback URL 획득
-            const callbackUrl = await startOAuthServer()
+    // Tauri: 로그인 세션 → Rust 코어 (사용자 JWT로 이벤트/테이블 동기화 + Realtime 구독)
+    useEffect(() => {
+        if (!isTauri()) return
+        const run = async () => {
+            const { invoke } = await import('@tauri-apps/api/core')
+            if (session?.user) {
+                await invoke('set_auth_session', {
+                    userId: session.user.id,
+                    accessToken: session.access_token,
+                    refreshToken: session.refresh_token,
+                    expiresAt: session.expires_at ?? Math.floor(Date.now() / 1000) + session.expires_in,
+                })
+            } else {
+                await invoke('clear_auth_session')
+            }
+        }
+        run().catch((err) => console.warn('[Auth] Rust 세션 전달 실패:', err))
+    }, [session?.user, session?.access_token, session?.refresh_token, session?.expires_at, session?.expires_in])
 
-            // 2. Supabase OAuth URL 생성 (PKCE + skipBrowserRedirect)
-            const { data, error } = await supabase.auth.signInWithOAuth({
-                provider: 'github',
-                options: {
-                    redirectTo: callbackUrl,
-                    skipBrowserRedirect: true,
-                },
+    // Tauri: Rust에서 갱신한 토큰 반영 (refresh token 회전 → 양쪽 동일 세션 유지)
+    useEffect(() => {
+        if (!isTauri()) return
+        let unlisten: (() => void) | undefined
+        let cancelled = false
+        import('@tauri-apps/api/event').then(({ listen }) =>
+            listen<{ access_token: string; refresh_token: string }>('orchx:auth-refreshed', ({ payload }) => {
+                supabase.auth.setSession({
+                    access_token: payload.access_token,
+                    refresh_token: payload.refresh_token,
+                }).catch((err) => console.warn('[Auth] 갱신 세션 반영 실패:', err))
             })
+        ).then((fn) => {
+            if (cancelled) fn()
+            else unlisten = fn
+        }).catch((err) => console.warn('[Auth] 토큰 갱신 구독 실패:', err))
+        return () => {
+            cancelled = true
+            unlisten?.()
+        }
+    }, [])
 
-            if (error || !data?.url) {
-                console.error('[Auth] OAuth URL 생성 실패:', error)
-                return
-            }
+    const signInWithGitHub = useCallback(async () => {
+        if (isTauri()) {
+            // ─── Tauri Desktop: PKCE + 로컬 콜백 서버 (Rust) ───
+            // 1. Rust에서 콜백 서버 + PKCE/state 생성 → authorize URL 획득
+            const authorizeUrl = await startOAuthLogin()
 
-            // 3. Chrome에서 code 수신 대기
-            onOAuthCallback(async (code) => {
-                try {
-                    // 4. code → 세션 교환
-                    const { error: exchangeError } = await supabase.auth.exchangeCodeForSession(code)
-                    if (exchangeError) {
-                        console.error('[Auth] 세션 교환 실패:', exchangeError)
+            // 2. Rust가 code → 토큰 교환 후 세션 전달 → supabase-js에 반영
+            onOAuthResult(
+                async (result) => {
+                    const { error } = await supabase.auth.setSession({
+                        access_token: result.access_token,
+                        refresh_token: result.refresh_token,
+                    })
+                    if (error) {
+                        console.error('[Auth] 세션 반영 실패:', error)
                     }
-                } catch (err) {
-                    console.error('[Auth] 세션 교환 에러:', err)
-                }
-            })
+                },
+                (message) => console.error('[Auth] OAuth 로그인 실패:', message),
+            )
 
-            // 5. 외부 브라우저(Chrome)에서 GitHub 인증 페이지 열기
-            await openInExternalBrowser(data.url)
+            // 3. 외부 브라우저(Chrome)에서 GitHub 인증 페이지 열기
+            await openInExternalBrowser(authorizeUrl)
         } else {
             // ─── PWA: 기존 리디렉트 방식 ───
             await supabase.auth.signInWithOAuth({
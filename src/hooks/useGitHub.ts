@@ -3,14 +3,16 @@
 // Supabase Auth OAuth 플로우 사용
 // ============================================
 
-import { useState, useEffect, useCallback } from 'react'
+import { useState, useEffect, useCallback, useRef } from 'react'
 import { supabase } from '../lib/supabase/client'
 import type { GitHubConnection, GitHubRepo } from '../lib/github/githubApi'
 import {
     getGitHubConnection,
     disconnectGitHub as disconnectApi,
     syncGitHubWithToken,
+    unlockCredentials as unlockApi,
     listRepos,
+    CredentialsLockedError,
 } from '../lib/github/githubApi'
 
 interface UseGitHubReturn {
@@ -21,6 +23,8 @@ interface UseGitHubReturn {
     loading: boolean
     error: string | null
     tokenExpired: boolean
+    /** 자격 증명 저장소(암호화 파일)가 잠겨 토큰을 읽을 수 없음 */
+    credentialsLocked: boolean
 
     // 레포
     repos: GitHubRepo[]
@@ -30,6 +34,7 @@ interface UseGitHubReturn {
     connect: () => void
     disconnect: () => Promise<void>
     reconnect: () => void
+    unlockCredentials: (passphrase: string) => Promise<void>
     refreshRepos: () => Promise<void>
     refresh: () => Promise<void>
 }
@@ -41,6 +46,9 @@ export function useGitHub(): UseGitHubReturn {
     const [repos, setRepos] = useState<GitHubRepo[]>([])
     const [reposLoading, setReposLoading] = useState(false)
     const [tokenExpired, setTokenExpired] = useState(false)
+    const [credentialsLocked, setCredentialsLocked] = useState(false)
+    // 저장소 잠김으로 저장하지 못한 provider_token (잠금 해제 후 저장)
+    const pendingToken = useRef<string | null>(null)
 
     // ─── 연결 정보 조회 ───
     const refresh = useCallback(async () => {
@@ -50,6 +58,7 @@ export function useGitHub(): UseGitHubReturn {
             setTokenExpired(false)
             const conn = await getGitHubConnection()
             setConnection(conn)
+            setCredentialsLocked(false)
 
             // 연결돼 있으면 레포도 자동 로드
             if (conn) {
@@ -67,6 +76,7 @@ export function useGitHub(): UseGitHubReturn {
                 setRepos([])
             }
         } catch (e) {
+            if (e instanceof CredentialsLockedError) setCredentialsLocked(true)
             setError(e instanceof Error ? e.message : 'GitHub 연결 확인 실패')
         } finally {
             setLoading(false)
@@ -80,14 +90,27 @@ export function useGitHub(): UseGitHubReturn {
     // ─── OAuth 후 provider_token → github_connections 동기화 ───
     const syncGitHub = useCallback(async (providerToken: string) => {
         try {
-
             await syncGitHubWithToken(providerToken)
-
+            pendingToken.current = null
         } catch (e) {
+            if (e instanceof CredentialsLockedError) {
+                pendingToken.current = providerToken
+                setCredentialsLocked(true)
+            }
             console.error('[GitHub] sync 실패:', e)
         }
     }, [])
 
+    // ─── 자격 증명 저장소 잠금 해제 → 미뤄진 토큰 저장 + 재조회 ───
+    const unlockCredentials = useCallback(async (passphrase: string) => {
+        await unlockApi(passphrase)
+        setCredentialsLocked(false)
+        if (pendingToken.current) {
+            await syncGitHub(pendingToken.current)
+        }
+        await refresh()
+    }, [syncGitHub, refresh])
+
     // Supabase auth 세션 변경 구독
     // ⚡ 핵심: Supabase JS가 hash(#access_token=...&provider_token=gho_...)를 먼저 파싱하고
     //         onAuthStateChange 콜백에서 session.provider_token으로 전달함.
@@ -113,31 +136,32 @@ export function useGitHub(): UseGitHubReturn {
 
     // ─── GitHub OAuth를 외부 브라우저/내부 리디렉트 분기 ───
     const triggerOAuth = useCallback(async (scopes?: string) => {
-        const { isTauri, openInExternalBrowser, startOAuthServer, onOAuthCallback } = await import('../lib/tauri/openExternal')
+        const { isTauri, openInExternalBrowser, startOAuthLogin, onOAuthResult } = await import('../lib/tauri/openExternal')
         const origin = window.location.origin
 
         if (isTauri()) {
-            // Tauri: PKCE + 로컬 콜백 서버
-            const callbackUrl = await startOAuthServer()
-
-            const { data, error } = await supabase.auth.signInWithOAuth({
-                provider: 'github',
-                options: {
-                    redirectTo: callbackUrl,
-                    scopes: scopes ?? 'repo,read:user',
-                    skipBrowserRedirect: true,
-                },
-            })
-
-            if (data?.url && !error) {
-                onOAuthCallback(async (code) => {
-                    const { error: exchangeError } = await supabase.auth.exchangeCodeForSession(code)
-                    if (exchangeError) {
-                        console.error('[GitHub] 세션 교환 실패:', exchangeError)
+            // Tauri: PKCE + 로컬 콜백 서버 (Rust가 토큰 교환)
+            const authorizeUrl = await startOAuthLogin(scopes ?? 'repo,read:user')
+
+            onOAuthResult(
+                async (result) => {
+                    const { error: sessionError } = await supabase.auth.setSession({
+                        access_token: result.access_token,
+                        refresh_token: result.refresh_token,
+                    })
+                    if (sessionError) {
+                        console.error('[GitHub] 세션 반영 실패:', sessionError)
+                        return
                     }
-                })
-                await openInExternalBrowser(data.url)
-            }
+                    // setSession 세션에는 provider_token이 없음 → 직접 동기화
+                    if (result.provider_token) {
+                        await syncGitHub(result.provider_token)
+                        void refresh()
+                    }
+                },
+                (message) => console.error('[GitHub] OAuth 실패:', message),
+            )
+            await openInExternalBrowser(authorizeUrl)
         } else {
             // PWA: 기존 리디렉트 방식 — /app으로 이동
             void supabase.auth.signInWithOAuth({
@@ -148,7 +172,7 @@ export function useGitHub(): UseGitHubReturn {
                 },
             })
         }
-    }, [])
+    }, [syncGitHub, refresh])
 
     // ─── GitHub 연결 (Supabase Auth OAuth) ───
     const connect = useCallback(() => {
@@ -208,11 +232,13 @@ export function useGitHub(): UseGitHubReturn {
         loading,
         error,
         tokenExpired,
+        credentialsLocked,
         repos,
         reposLoading,
         connect,
         disconnect,
         reconnect,
+        unlockCredentials,
         refreshRepos,
         refresh,
     }
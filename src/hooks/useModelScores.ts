@@ -5,6 +5,7 @@
 import { useState, useEffect, useCallback } from 'react'
 import { getModelScores, upsertModelScore, type ModelScore } from '../lib/supabase/modelScores'
 import type { AIModel } from '../types/index'
+import { errorMessage } from '../lib/tauri/dbError'
 
 /** Tauri 환경인지 체크 */
 function isTauri(): boolean {
@@ -64,7 +65,7 @@ export function useModelScores(): UseModelScoresReturn {
                 setDbScores(data)
             }
         } catch (err) {
-            setError(err instanceof Error ? err.message : 'Failed to load scores')
+            setError(errorMessage(err, 'Failed to load scores'))
         } finally {
             setLoading(false)
         }
@@ -104,7 +105,7 @@ export function useModelScores(): UseModelScoresReturn {
             }
             await fetchScores()
         } catch (err) {
-            setError(err instanceof Error ? err.message : 'Failed to update score')
+            setError(errorMessage(err, 'Failed to update score'))
         }
     }, [fetchScores])
 
@@ -11,6 +11,7 @@ import {
     type Project,
     type CreateProjectInput,
 } from '../lib/supabase/projects'
+import { errorMessage } from '../lib/tauri/dbError'
 
 /** Tauri 환경인지 체크 */
 function isTauri(): boolean {
@@ -45,7 +46,7 @@ export function useProjects(): UseProjectsReturn {
             }
             setError(null)
         } catch (e) {
-            setError(e instanceof Error ? e.message : 'Failed to fetch projects')
+            setError(errorMessage(e, 'Failed to fetch projects'))
         } finally {
             setLoading(false)
         }
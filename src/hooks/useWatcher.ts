@@ -19,6 +19,7 @@ interface WatchStatus {
         repo_full_name: string
         path: string
         watching: boolean
+        status: 'running' | 'degraded' | 'paused' | 'restarting' | null
     }[]
 }
 
@@ -1,9 +1,12 @@
 // ============================================
 // githubApi.ts — GitHub API 레이어
-// Supabase에 저장된 access_token으로 GitHub 호출
+// 데스크탑: 토큰은 로컬 자격 증명 저장소(키링/암호화 파일), DB에는 참조만
+// 웹(PWA): Supabase github_connections의 access_token으로 GitHub 호출
 // ============================================
 
 import { supabase } from '../supabase/client'
+import { isTauri } from '../tauri/isTauri'
+import { isDbError } from '../tauri/dbError'
 
 // ─── Types ───
 
@@ -37,10 +40,67 @@ export interface GitHubBranch {
     protected: boolean
 }
 
+/** 로컬 DB github_connections 행 (토큰은 자격 증명 저장소 참조) */
+interface LocalGitHubConnection {
+    id: string
+    github_username: string | null
+    access_token_ref: string
+    refresh_token_ref: string | null
+    token_expires_at: string | null
+    connected_at: string
+    updated_at: string
+}
+
+/** 자격 증명 저장소(암호화 파일)가 잠겨 있음 → unlockCredentials 필요 */
+export class CredentialsLockedError extends Error {
+    constructor() {
+        super('자격 증명 저장소가 잠겨 있습니다. 암호를 입력해주세요.')
+        this.name = 'CredentialsLockedError'
+    }
+}
+
+/** Rust credential_store::LOCKED 에러(db_* 커맨드는 DbError kind 'credentials') → CredentialsLockedError */
+function toCredentialsError(e: unknown): unknown {
+    const message = isDbError(e) ? e.message : e
+    return typeof message === 'string' && message.startsWith('자격 증명 저장소 잠김')
+        ? new CredentialsLockedError()
+        : e
+}
+
+/** 자격 증명 저장소 잠금 해제 (최초 호출 시 해당 암호로 생성) → 이전된 평문 토큰 수 */
+export async function unlockCredentials(passphrase: string): Promise<number> {
+    const { invoke } = await import('@tauri-apps/api/core')
+    return invoke<number>('credentials_unlock', { passphrase })
+}
+
 // ─── Connection CRUD ───
 
+/** 로컬 연결 + 저장소의 토큰 (토큰은 메모리에만) */
+async function getLocalConnection(): Promise<GitHubConnection | null> {
+    const { invoke } = await import('@tauri-apps/api/core')
+    const connections = await invoke<LocalGitHubConnection[]>('db_get_github_connections')
+    const conn = connections[0]
+    if (!conn) return null
+    const token = await invoke<string | null>('db_get_github_token', { id: conn.id })
+        .catch((e: unknown) => { throw toCredentialsError(e) })
+    if (!token) return null
+    return {
+        id: conn.id,
+        user_id: conn.id,
+        installation_id: 0,
+        github_username: conn.github_username,
+        access_token: token,
+        refresh_token: null,
+        token_expires_at: conn.token_expires_at,
+        connected_at: conn.connected_at,
+        updated_at: conn.updated_at,
+    }
+}
+
 /** 현재 유저의 GitHub 연결 정보 조회 */
 export async function getGitHubConnection(): Promise<GitHubConnection | null> {
+    if (isTauri()) return getLocalConnection()
+
     const { data, error } = await supabase
         .from('github_connections')
         .select('*')
@@ -52,6 +112,12 @@ export async function getGitHubConnection(): Promise<GitHubConnection | null> {
 
 /** GitHub 연결 해제 */
 export async function disconnectGitHub(connectionId: string): Promise<void> {
+    if (isTauri()) {
+        const { invoke } = await import('@tauri-apps/api/core')
+        await invoke('db_delete_github_connection', { id: connectionId })
+        return
+    }
+
     const { error } = await supabase
         .from('github_connections')
         .delete()
@@ -63,6 +129,7 @@ export async function disconnectGitHub(connectionId: string): Promise<void> {
 /**
  * provider_token을 직접 받아서 github_connections 테이블에 upsert.
  * URL hash에서 추출한 provider_token을 인자로 전달.
+ * 데스크탑은 로컬 자격 증명 저장소에만 저장 (Supabase에 평문 토큰을 쓰지 않음).
  */
 export async function syncGitHubWithToken(providerToken: string): Promise<GitHubConnection | null> {
     const { data: { session } } = await supabase.auth.getSession()
@@ -91,6 +158,24 @@ export async function syncGitHubWithToken(providerToken: string): Promise<GitHub
         // username 못 가져와도 연결은 진행
     }
 
+    if (isTauri()) {
+        const { invoke } = await import('@tauri-apps/api/core')
+        await invoke('db_save_github_connection', {
+            id: session.user.id,
+            githubUsername: username,
+            accessToken: providerToken,
+            refreshToken: null,
+            tokenExpiresAt: null,
+        }).catch((e: unknown) => { throw toCredentialsError(e) })
+        // 이전 버전이 Supabase에 남긴 평문 토큰 행 정리
+        const { error } = await supabase
+            .from('github_connections')
+            .delete()
+            .eq('user_id', session.user.id)
+        if (error) console.error('[syncGitHub] 원격 평문 토큰 삭제 실패:', error)
+        return getLocalConnection()
+    }
+
     // github_connections에 upsert
     type GhInsert = { user_id: string; installation_id: number; github_username: string | null; access_token: string; refresh_token: string | null; token_expires_at: string | null }
     const row: GhInsert = {
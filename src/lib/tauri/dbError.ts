@@ -0,0 +1,27 @@
+// ============================================
+// dbError — Tauri db_* 커맨드 에러 표시 유틸
+// Rust DbError는 Error 인스턴스가 아닌 { kind, field, message } 객체로 전달됨
+// ============================================
+
+export interface DbError {
+    kind: 'invalid' | 'db' | 'migration' | 'credentials'
+    field: string | null
+    message: string
+}
+
+export function isDbError(e: unknown): e is DbError {
+    return typeof e === 'object' && e !== null
+        && 'kind' in e && 'message' in e
+        && typeof (e as DbError).message === 'string'
+}
+
+/**
+ * 에러 → 표시용 메시지.
+ * DbError는 "필드: 메시지", 문자열 에러(String 반환 커맨드)는 그대로.
+ */
+export function errorMessage(e: unknown, fallback: string): string {
+    if (isDbError(e)) return e.field ? `${e.field}: ${e.message}` : e.message
+    if (e instanceof Error) return e.message
+    if (typeof e === 'string' && e) return e
+    return fallback
+}
@@ -30,32 +30,54 @@ export async function openInExternalBrowser(url: string): Promise<void> {
     }
 }
 
+/** Rust OAuth 로그인 결과 (oauth-session 이벤트) */
+export interface OAuthSessionPayload {
+    user_id: string
+    access_token: string
+    refresh_token: string
+    expires_at: number
+    provider_token: string | null
+    provider_refresh_token: string | null
+}
+
 /**
- * Tauri OAuth 콜백 서버 시작 → callback URL 반환
- * Rust 백엔드에서 127.0.0.1:랜덤포트 에 서버 띄움
+ * Tauri OAuth 로그인 시작 → authorize URL 반환
+ * Rust 백엔드가 127.0.0.1:랜덤포트 콜백 서버 + PKCE/state 생성, code → 토큰 교환까지 처리
  */
-export async function startOAuthServer(): Promise<string> {
+export async function startOAuthLogin(scopes?: string): Promise<string> {
     const { invoke } = await import('@tauri-apps/api/core')
-    return invoke<string>('start_oauth_server')
+    return invoke<string>('start_oauth_login', { provider: 'github', scopes })
 }
 
 /**
- * Tauri 이벤트 리스너 — Rust에서 OAuth code를 받으면 콜백
+ * Tauri 이벤트 리스너 — Rust 로그인 완료(oauth-session) 또는 실패(oauth-error)
  * 한 번만 수신하고 자동 해제
  */
-export function onOAuthCallback(callback: (code: string) => void): () => void {
-    let unlisten: (() => void) | null = null
+export function onOAuthResult(
+    onSession: (session: OAuthSessionPayload) => void,
+    onError: (message: string) => void,
+): () => void {
+    const unlisteners: Array<() => void> = []
+    let done = false
+    const cleanup = () => {
+        done = true
+        unlisteners.forEach((fn) => fn())
+    }
 
-    import('@tauri-apps/api/event').then(({ listen }) => {
-        listen<string>('oauth-callback', (event) => {
-            callback(event.payload)
-            if (unlisten) unlisten()
-        }).then((fn) => {
-            unlisten = fn
-        })
+    import('@tauri-apps/api/event').then(async ({ listen }) => {
+        const fns = await Promise.all([
+            listen<OAuthSessionPayload>('oauth-session', (event) => {
+                cleanup()
+                onSession(event.payload)
+            }),
+            listen<string>('oauth-error', (event) => {
+                cleanup()
+                onError(event.payload)
+            }),
+        ])
+        if (done) fns.forEach((fn) => fn())
+        else unlisteners.push(...fns)
     })
 
-    return () => {
-        if (unlisten) unlisten()
-    }
+    return cleanup
 }
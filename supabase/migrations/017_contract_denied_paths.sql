@@ -0,0 +1,10 @@
+-- ============================================
+-- 017: 실행 계약서 금지 경로 + 경로별 심각도
+-- denied_paths는 allowed_paths보다 항상 우선
+-- ============================================
+
+-- 금지 경로 (예: .env*, supabase/migrations/**, **/*.pem)
+ALTER TABLE agent_tasks ADD COLUMN IF NOT EXISTS denied_paths TEXT[] DEFAULT '{}';
+
+-- 경로별 위반 심각도 재지정: [{ "pattern": "docs/**", "severity": "info" }]
+ALTER TABLE agent_tasks ADD COLUMN IF NOT EXISTS path_severity JSONB DEFAULT '[]';
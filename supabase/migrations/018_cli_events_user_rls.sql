@@ -0,0 +1,21 @@
+-- ============================================
+-- 018: cli_events user_id + RLS 정책 전환
+-- 데스크톱 코어가 사용자 JWT로 전송 → USING (true) 임시 정책 제거
+-- 기존 user_id 없는 이벤트는 누구에게도 노출되지 않음
+-- ============================================
+
+-- === 1. user_id 컬럼 추가 (미지정 시 요청 사용자) ===
+ALTER TABLE cli_events
+  ADD COLUMN IF NOT EXISTS user_id UUID REFERENCES auth.users(id) DEFAULT auth.uid();
+
+CREATE INDEX IF NOT EXISTS idx_cli_events_user ON cli_events(user_id);
+
+-- === 2. 전체 허용 정책 제거 ===
+DROP POLICY IF EXISTS cli_events_all ON cli_events;
+
+-- === 3. user_id 기반 RLS 정책 생성 ===
+DROP POLICY IF EXISTS "Users manage own cli_events" ON cli_events;
+CREATE POLICY "Users manage own cli_events" ON cli_events
+  FOR ALL
+  USING (user_id = auth.uid())
+  WITH CHECK (user_id = auth.uid());